@@ -49,6 +49,9 @@ impl RedPipe {
             | Rule::BIN
             | Rule::HEX
             | Rule::OCT
+            | Rule::AddrTerm
+            | Rule::FlagName
+            | Rule::AddrExpr
             | Rule::Command
             | Rule::ArgumentLiteral
             | Rule::Argument
@@ -59,7 +62,9 @@ impl RedPipe {
             | Rule::EmptyLine
             | Rule::HelpLine
             | Rule::CommandLine
-            | Rule::Input => unimplemented_pair(&type_identifier),
+            | Rule::Input
+            | Rule::Statement
+            | Rule::Inputs => unimplemented_pair(&type_identifier),
         }
     }
 }
@@ -95,11 +100,29 @@ impl Argument {
         }
     }
 }
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Loc {
+    Addr(u64),
+    Expr(String),
+}
+
+impl Loc {
+    fn parse_loc(root: Pair<Rule>) -> Result<Self, ParserError> {
+        let pair = root.into_inner().next().unwrap();
+        if pair.as_rule() == Rule::AddrExpr {
+            Ok(Self::Expr(pair.as_str().to_owned()))
+        } else {
+            Ok(Self::Addr(pair_to_num(&pair)?))
+        }
+    }
+}
+
 #[derive(Default, Debug, PartialEq)]
 pub struct Cmd {
     pub command: String,
     pub args: Vec<Argument>,
-    pub loc: Option<u64>,
+    pub loc: Option<Loc>,
     pub red_pipe: Box<RedPipe>,
 }
 
@@ -109,7 +132,10 @@ fn pair_to_num(root: &Pair<Rule>) -> Result<u64, ParserError> {
         Rule::HEX => u64::from_str_radix(&root.as_str()[2..], 16),
         Rule::OCT => u64::from_str_radix(&root.as_str()[1..], 8),
         Rule::DEC => root.as_str().parse::<u64>(),
-        Rule::EOI
+        Rule::AddrTerm
+        | Rule::FlagName
+        | Rule::AddrExpr
+        | Rule::EOI
         | Rule::WHITESPACE
         | Rule::CustomAlpha
         | Rule::CustomAlphaNum
@@ -129,6 +155,8 @@ fn pair_to_num(root: &Pair<Rule>) -> Result<u64, ParserError> {
         | Rule::HelpLine
         | Rule::CommandLine
         | Rule::Input
+        | Rule::Statement
+        | Rule::Inputs
         | Rule::HelpAll => unimplemented_pair(root),
     };
     match result {
@@ -143,7 +171,7 @@ impl Cmd {
         for pair in root.into_inner() {
             match pair.as_rule() {
                 Rule::Command => pair.as_str().clone_into(&mut cmd.command),
-                Rule::Loc => cmd.loc = Some(pair_to_num(&pair.into_inner().next().unwrap())?),
+                Rule::Loc => cmd.loc = Some(Loc::parse_loc(pair)?),
                 Rule::Arguments => cmd.args = Argument::parse_arguments(pair),
                 Rule::RedPipe => cmd.red_pipe = Box::new(RedPipe::parse_redpipe(pair)),
                 Rule::EOI
@@ -157,6 +185,9 @@ impl Cmd {
                 | Rule::BIN
                 | Rule::HEX
                 | Rule::OCT
+                | Rule::AddrTerm
+                | Rule::FlagName
+                | Rule::AddrExpr
                 | Rule::ArgumentLiteral
                 | Rule::Argument
                 | Rule::Pipe
@@ -166,7 +197,9 @@ impl Cmd {
                 | Rule::EmptyLine
                 | Rule::HelpLine
                 | Rule::CommandLine
-                | Rule::Input => unimplemented_pair(&pair),
+                | Rule::Input
+                | Rule::Statement
+                | Rule::Inputs => unimplemented_pair(&pair),
             }
         }
         Ok(cmd)
@@ -241,7 +274,7 @@ mod test_normal_cmd {
         let mut cmd = Cmd::parse_cmd(root).unwrap();
         let mut target = Cmd {
             command: "aa".to_owned(),
-            loc: Some(0x500),
+            loc: Some(Loc::Addr(0x500)),
             ..Default::default()
         };
         assert_eq!(cmd, target);
@@ -251,7 +284,7 @@ mod test_normal_cmd {
             .next()
             .unwrap();
         cmd = Cmd::parse_cmd(root).unwrap();
-        target.loc = Some(500);
+        target.loc = Some(Loc::Addr(500));
         assert_eq!(cmd, target);
 
         root = CliParser::parse(Rule::CommandLine, "aa @ 0500")
@@ -259,7 +292,7 @@ mod test_normal_cmd {
             .next()
             .unwrap();
         cmd = Cmd::parse_cmd(root).unwrap();
-        target.loc = Some(0o500);
+        target.loc = Some(Loc::Addr(0o500));
         assert_eq!(cmd, target);
 
         root = CliParser::parse(Rule::CommandLine, "aa @ 0500")
@@ -267,7 +300,7 @@ mod test_normal_cmd {
             .next()
             .unwrap();
         cmd = Cmd::parse_cmd(root).unwrap();
-        target.loc = Some(0o500);
+        target.loc = Some(Loc::Addr(0o500));
         assert_eq!(cmd, target);
 
         root = CliParser::parse(Rule::CommandLine, "aa @ 0b10100")
@@ -275,8 +308,29 @@ mod test_normal_cmd {
             .next()
             .unwrap();
         cmd = Cmd::parse_cmd(root).unwrap();
-        target.loc = Some(0b10100);
+        target.loc = Some(Loc::Addr(0b10100));
         assert_eq!(cmd, target);
+
+        for expr in [
+            "$$",
+            "$",
+            "$$-0x10",
+            "$-0x100",
+            "$$+0b11-07+$",
+            "0x500+$$",
+            "main",
+            "sym.main+0x10-$$",
+            "0x10+main",
+        ] {
+            let line = format!("aa @ {expr}");
+            root = CliParser::parse(Rule::CommandLine, &line)
+                .unwrap()
+                .next()
+                .unwrap();
+            cmd = Cmd::parse_cmd(root).unwrap();
+            target.loc = Some(Loc::Expr(expr.to_owned()));
+            assert_eq!(cmd, target);
+        }
     }
 
     #[test]
@@ -6,6 +6,7 @@ use crate::{
     grammar::{CliParser, Rule},
     help::HelpCmd,
 };
+use pest::iterators::Pair;
 use pest::Parser;
 
 #[derive(Debug, PartialEq)]
@@ -24,6 +25,31 @@ impl ParseTree {
             return Err(ParserError::Pest(Box::new(pairs.err().unwrap())));
         }
         let pair = pairs.unwrap().next().unwrap().into_inner().next().unwrap();
+        Self::from_pair(pair)
+    }
+
+    /// Parse a line of statements separated by `;`, comments are dropped unless the line
+    /// has nothing else.
+    pub fn construct_many(line: &str) -> Result<Vec<Self>, ParserError> {
+        let pairs = CliParser::parse(Rule::Inputs, line);
+        if pairs.is_err() {
+            return Err(ParserError::Pest(Box::new(pairs.err().unwrap())));
+        }
+        let mut pairs: Vec<_> = pairs
+            .unwrap()
+            .next()
+            .unwrap()
+            .into_inner()
+            .filter(|pair| pair.as_rule() != Rule::EOI)
+            .collect();
+        // a trailing comment only matters if there is nothing else in the line.
+        if pairs.len() > 1 {
+            pairs.retain(|pair| pair.as_rule() != Rule::Comment);
+        }
+        pairs.into_iter().map(Self::from_pair).collect()
+    }
+
+    fn from_pair(pair: Pair<Rule>) -> Result<Self, ParserError> {
         match pair.as_rule() {
             Rule::HelpLine => Ok(Self::Help(HelpCmd::parse_help(pair))),
             Rule::Comment => Ok(Self::Comment),
@@ -40,6 +66,9 @@ impl ParseTree {
             | Rule::BIN
             | Rule::HEX
             | Rule::OCT
+            | Rule::AddrTerm
+            | Rule::FlagName
+            | Rule::AddrExpr
             | Rule::Command
             | Rule::ArgumentLiteral
             | Rule::Argument
@@ -49,7 +78,9 @@ impl ParseTree {
             | Rule::Red
             | Rule::RedCat
             | Rule::RedPipe
-            | Rule::Input => unimplemented_pair(&pair),
+            | Rule::Input
+            | Rule::Statement
+            | Rule::Inputs => unimplemented_pair(&pair),
         }
     }
 }
@@ -57,6 +88,7 @@ impl ParseTree {
 #[cfg(test)]
 mod test_parser {
     use super::*;
+    use crate::cmd::Argument;
     #[test]
     fn test_parser() {
         let mut tree = ParseTree::construct("aa? #and a little comment").unwrap();
@@ -78,4 +110,36 @@ mod test_parser {
         tree = ParseTree::construct("").unwrap();
         assert_eq!(tree, ParseTree::NewLine);
     }
+
+    #[test]
+    fn test_parser_many() {
+        let aa = ParseTree::Cmd(Cmd {
+            command: "aa".to_owned(),
+            ..Default::default()
+        });
+        let bb = ParseTree::Help(HelpCmd {
+            command: "bb".to_owned(),
+        });
+        assert_eq!(
+            ParseTree::construct_many("aa; bb? ;? #; and a comment").unwrap(),
+            vec![aa, bb, ParseTree::HelpAll]
+        );
+        let trees = ParseTree::construct_many("aa \"x;y\" ;aa z;").unwrap();
+        assert_eq!(trees.len(), 2);
+        let ParseTree::Cmd(cmd) = &trees[0] else {
+            panic!("Expected a command");
+        };
+        assert_eq!(cmd.args, vec![Argument::Literal("x;y".to_owned())]);
+        assert_eq!(
+            ParseTree::construct_many("#; comment").unwrap(),
+            vec![ParseTree::Comment]
+        );
+        assert_eq!(
+            ParseTree::construct_many("").unwrap(),
+            vec![ParseTree::NewLine]
+        );
+        ParseTree::construct_many("aa;;bb").unwrap_err();
+        ParseTree::construct_many(";aa").unwrap_err();
+        ParseTree::construct("aa; bb").unwrap_err();
+    }
 }
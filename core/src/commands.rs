@@ -5,11 +5,13 @@ use std::collections::HashSet;
 use crate::{cmd::Cmd, helper::MRc};
 use alloc::collections::BTreeMap;
 use rair_trees::bktree::SpellTree; // for suffex search
+use rair_trees::prefix::PrefixTree; // for completion
 
 #[derive(Default)]
 pub struct Commands {
     suggestions: SpellTree<()>,
     search: BTreeMap<&'static str, MRc<dyn Cmd + Sync + Send>>,
+    completions: PrefixTree<&'static str>,
 }
 
 impl Commands {
@@ -24,6 +26,7 @@ impl Commands {
             false
         } else {
             self.suggestions.insert(command_name.to_owned(), ());
+            self.completions.insert(command_name, command_name);
             self.search.insert(command_name, functionality);
             true
         }
@@ -38,11 +41,11 @@ impl Commands {
         self.suggestions.find(&command.to_owned(), tolerance).1
     }
     #[must_use]
-    pub fn prefix<'a>(&'a self, command: &'a str) -> Vec<&&str> {
-        self.search
-            .range(command..)
-            .take_while(|(k, _)| k.starts_with(command))
-            .map(|(k, _)| k)
+    pub fn prefix(&self, command: &str) -> Vec<&'static str> {
+        self.completions
+            .find_prefix(command)
+            .into_iter()
+            .map(|(_, name)| *name)
             .collect()
     }
     /// iterate over commands with no duplication
@@ -3,15 +3,15 @@
 use crate::cmd::{Cmd, CmdOps};
 use crate::commands::Commands;
 use crate::helper::{error_msg, AddrMode};
-use crate::io::register_io;
-use crate::loc::register_loc;
+use crate::io::{register_io, report_watches, Watches};
+use crate::loc::{register_loc, History};
 use crate::register_diff;
 use crate::utils::register_utils;
 use crate::writer::Writer;
 use alloc::{collections::BTreeMap, sync::Arc};
 use core::mem;
 use parking_lot::{Mutex, RwLock};
-use rair_env::Environment;
+use rair_env::{EnvData, Environment};
 use rair_io::{IoError, RIO};
 use serde::{Deserialize, Serialize};
 use std::io;
@@ -22,6 +22,14 @@ pub struct Core {
     pub mode: AddrMode,
     pub io: RIO,
     loc: u64,
+    // handle used for displaying addresses relative to its base, [None] means absolute.
+    #[serde(default)]
+    pub rel_hndl: Option<u64>,
+    #[serde(default)]
+    pub(crate) history: History,
+    // named addresses that can be used instead of numbers in address expressions.
+    #[serde(default)]
+    pub flags: BTreeMap<String, u64>,
     // Every time you add some new serde(skip) variable
     // make sure that this variable is well initialized
     // in the projects commands.
@@ -33,6 +41,12 @@ pub struct Core {
     commands: Arc<Mutex<Commands>>,
     #[serde(skip)]
     pub env: Arc<RwLock<Environment<Core>>>,
+    // address ranges reported when written to by commands.
+    #[serde(skip)]
+    pub(crate) watches: Watches,
+    // number of errors reported so far, see `Core::error_count`.
+    #[serde(skip)]
+    pub(crate) errors: u64,
 }
 
 impl Default for Core {
@@ -43,8 +57,13 @@ impl Default for Core {
             stderr: Writer::new_write(Box::new(io::stderr())),
             io: RIO::new(),
             loc: 0,
+            rel_hndl: None,
+            history: History::default(),
+            flags: BTreeMap::new(),
             commands: Arc::default(),
             env: Arc::default(),
+            watches: Watches::default(),
+            errors: 0,
         }
     }
 }
@@ -63,6 +82,19 @@ impl Core {
         register_utils(self);
         register_diff(self);
     }
+    /// Number of errors reported through [`error_msg`], [`expect`] and [`expect_range`] so far.
+    /// A command failed if this number changed while running it.
+    ///
+    /// [`expect`]: crate::expect
+    /// [`expect_range`]: crate::expect_range
+    #[must_use]
+    pub fn error_count(&self) -> u64 {
+        self.errors
+    }
+    /// Count an error that was reported without going through [`error_msg`].
+    pub fn count_error(&mut self) {
+        self.errors += 1;
+    }
     /// Returns list of all available commands in [Core].
     pub fn commands(&mut self) -> Arc<Mutex<Commands>> {
         self.commands.clone()
@@ -80,6 +112,30 @@ impl Core {
             "Show help for suggestions in case of invalid Command",
         )
         .unwrap();
+        env.add_u64(
+            "core.historySize",
+            1024,
+            "Maximum number of locations kept in seek history",
+        )
+        .unwrap();
+        env.add_str(
+            "core.historyFile",
+            "",
+            "Path where seek history is saved on exit and loaded from on startup, empty string disables it",
+        )
+        .unwrap();
+        env.add_bool(
+            "core.json",
+            false,
+            "Print tables such as the output of `files` and `maps` as JSON",
+        )
+        .unwrap();
+        env.add_bool(
+            "core.stopOnError",
+            false,
+            "Skip the rest of `;` separated commands once one of them fails",
+        )
+        .unwrap();
     }
     fn init_colors(&mut self, enable: bool) {
         let locked_env = self.env.clone();
@@ -102,8 +158,11 @@ impl Core {
         env.add_color("color.8", (0x2a, 0xa1, 0x98), "").unwrap();
         env.add_color("color.9", (0x85, 0x99, 0x00), "").unwrap();
     }
-    fn new_settings(color: bool) -> Self {
-        let mut core = Core::default();
+    fn new_settings(io: RIO, color: bool) -> Self {
+        let mut core = Core {
+            io,
+            ..Core::default()
+        };
         core.init_colors(color);
         core.init_core_env();
         core.load_commands();
@@ -111,11 +170,60 @@ impl Core {
     }
     #[must_use]
     pub fn new() -> Self {
-        Core::new_settings(true)
+        Core::with_io(RIO::new())
     }
     #[must_use]
     pub fn new_no_colors() -> Self {
-        Core::new_settings(false)
+        Core::with_io_no_colors(RIO::new())
+    }
+    /// Returns new [Core] that uses the given, possibly preconfigured, `io`.
+    #[must_use]
+    pub fn with_io(io: RIO) -> Self {
+        Core::new_settings(io, true)
+    }
+    /// Same as [`Core::with_io`] but with colors disabled.
+    #[must_use]
+    pub fn with_io_no_colors(io: RIO) -> Self {
+        Core::new_settings(io, false)
+    }
+    /// Returns an independent copy of this [Core] that can be modified freely without
+    /// affecting it. The copy uses the [RIO] returned by [`RIO::fork`] and has the same
+    /// location, address mode, seek history, flags and environment variables values.
+    pub fn fork(&self) -> Result<Core, IoError> {
+        let io = self.io.fork()?;
+        let color = self.env.read().get_bool("color.enable").unwrap();
+        let mut core = Core::new_settings(io, color);
+        core.mode = self.mode;
+        core.loc = self.loc;
+        core.rel_hndl = self.rel_hndl;
+        core.history = self.history.clone();
+        core.flags = self.flags.clone();
+        let env = core.env.clone();
+        let mut env = env.write();
+        let mut refused = Vec::new();
+        for (key, data) in self.env.read().iter() {
+            // variables registered after start up are not known to the copy so they are skipped.
+            if !env.contains(key) {
+                continue;
+            }
+            let set = match data {
+                EnvData::Str(s) => env.set_str(key, s, &mut core),
+                EnvData::U64(u) => env.set_u64(key, u, &mut core),
+                EnvData::I64(i) => env.set_i64(key, i, &mut core),
+                EnvData::Bool(b) => env.set_bool(key, b, &mut core),
+                EnvData::Color(r, g, b) => env.set_color(key, (r, g, b), &mut core),
+            };
+            if set.is_err() {
+                refused.push(key.to_owned());
+            }
+        }
+        drop(env);
+        // values refused by the copy are left to their defaults and reported on its stderr.
+        for key in refused {
+            let msg = format!("Value of `{key}` was refused by the copy.");
+            error_msg(&mut core, "Failed to copy environment variable", &msg);
+        }
+        Ok(core)
     }
     pub fn set_loc(&mut self, loc: u64) {
         self.loc = loc;
@@ -125,6 +233,23 @@ impl Core {
     pub fn get_loc(&self) -> u64 {
         self.loc
     }
+    /// Print `rows` as one JSON array if `core.json` is enabled, otherwise print `header`
+    /// followed by every row formatted by `text` on a line of its own.
+    pub fn emit_table<T, F>(&mut self, header: &str, rows: &[T], text: F)
+    where
+        T: Serialize,
+        F: Fn(&T) -> String,
+    {
+        if self.env.read().get_bool("core.json").unwrap() {
+            serde_json::to_writer(&mut self.stdout, rows).unwrap();
+            writeln!(self.stdout).unwrap();
+            return;
+        }
+        writeln!(self.stdout, "{header}").unwrap();
+        for row in rows {
+            writeln!(self.stdout, "{}", text(row)).unwrap();
+        }
+    }
     pub fn add_command<T: Cmd + Sync + Send + 'static>(&mut self, funcs: T) {
         let cmds = funcs.commands();
         let funcs = Arc::new(Mutex::new(funcs));
@@ -136,26 +261,27 @@ impl Core {
         }
     }
     fn command_not_found(&mut self, command: &str) {
-        let msg = format!("Command {} is not found.", command.primary().bold());
-        error_msg(self, "Execution failed", &msg);
-        let commands = self.commands.lock();
-        let similar = commands.suggest(command, 2);
+        let similar: Vec<String> = self
+            .commands
+            .lock()
+            .suggest(command, 2)
+            .into_iter()
+            .cloned()
+            .collect();
         let (r, g, b) = self.env.read().get_color("color.6").unwrap();
+        let mut msg = format!("Unknown command '{}'.", command.primary().bold());
         if !similar.is_empty() {
-            write!(self.stderr, "Similar command: {}", similar[0].rgb(r, g, b)).unwrap();
-            if similar.len() > 1 {
-                for suggestion in &similar[1..] {
-                    write!(self.stderr, ", {}", suggestion.rgb(r, g, b)).unwrap();
-                }
-            }
-            writeln!(self.stderr, ".").unwrap();
+            let suggestions: Vec<String> = similar
+                .iter()
+                .map(|suggestion| format!("'{}'", suggestion.rgb(r, g, b)))
+                .collect();
+            msg = format!("{msg} Did you mean {}?", suggestions.join(", "));
         }
+        error_msg(self, "Execution failed", &msg);
         let show_help = self.env.read().get_bool("core.helpInvalidCommand").unwrap();
         if show_help {
-            let similar: Vec<String> = similar.iter().map(|s| (*s).to_owned()).collect();
-            drop(commands);
             for suggestion in similar {
-                self.help(&suggestion.clone());
+                self.help(&suggestion);
             }
         }
     }
@@ -167,6 +293,9 @@ impl Core {
         drop(cmds_ref);
         if let Some(cmd) = cmd {
             cmd.lock().run(self, args);
+            if !self.watches.is_empty() {
+                report_watches(self);
+            }
         } else {
             self.command_not_found(command);
         }
@@ -196,12 +325,30 @@ impl Core {
             self.command_not_found(command);
         }
     }
+    /// Names of all commands starting with `prefix` sorted alphabetically, suitable for tab
+    /// completion.
+    #[must_use]
+    pub fn complete(&self, prefix: &str) -> Vec<&'static str> {
+        self.commands.lock().prefix(prefix)
+    }
     pub fn read_sparce(&mut self, loc: u64, size: u64) -> Result<BTreeMap<u64, u8>, IoError> {
         match self.mode {
             AddrMode::Phy => self.io.pread_sparce(loc, size),
             AddrMode::Vir => self.io.vread_sparce(loc, size),
         }
     }
+    /// Same as [`Core::read_sparce`] but bytes are read as they were before changes that are
+    /// only kept in memory, see [`RIO::pread_original_sparce`].
+    pub fn read_original_sparce(
+        &mut self,
+        loc: u64,
+        size: u64,
+    ) -> Result<BTreeMap<u64, u8>, IoError> {
+        match self.mode {
+            AddrMode::Phy => self.io.pread_original_sparce(loc, size),
+            AddrMode::Vir => self.io.vread_original_sparce(loc, size),
+        }
+    }
     pub fn read(&mut self, loc: u64, buf: &mut [u8]) -> Result<(), IoError> {
         match self.mode {
             AddrMode::Phy => self.io.pread(loc, buf),
@@ -220,6 +367,7 @@ impl Core {
 mod test_core {
     use super::*;
     use crate::utils::Quit;
+    use rair_io::IoMode;
     fn testings_env(core: &mut Core) {
         let locked_env = core.env.clone();
         let mut env = locked_env.write();
@@ -227,6 +375,51 @@ mod test_core {
             .unwrap();
     }
     #[test]
+    fn test_with_io() {
+        let mut io = RIO::new();
+        io.open("malloc://0x500", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        let mut core = Core::with_io_no_colors(io);
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run("files", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             0\t0x00000000\t0x00000500\tWRITE | READ\tMalloc\t\tmalloc://0x500\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    #[test]
+    fn test_fork() {
+        let mut core = Core::new_no_colors();
+        core.io
+            .open("malloc://0x100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.map(0x0, 0x1000, 0x100).unwrap();
+        core.write(0x10, &[0xaa]).unwrap();
+        core.mode = AddrMode::Vir;
+        core.set_loc(0x1010);
+        let env = core.env.clone();
+        env.write()
+            .set_u64("core.historySize", 5, &mut core)
+            .unwrap();
+        let mut fork = core.fork().unwrap();
+        assert_eq!(fork.mode, AddrMode::Vir);
+        assert_eq!(fork.get_loc(), 0x1010);
+        assert_eq!(fork.env.read().get_u64("core.historySize").unwrap(), 5);
+        let mut fillme = [0];
+        fork.read(0x1010, &mut fillme).unwrap();
+        assert_eq!(fillme, [0xaa]);
+        fork.write(0x1010, &[0xbb]).unwrap();
+        fork.io.map(0x0, 0x2000, 0x100).unwrap();
+        core.read(0x1010, &mut fillme).unwrap();
+        assert_eq!(fillme, [0xaa]);
+        assert_eq!(core.io.map_iter().count(), 1);
+        fork.read(0x2010, &mut fillme).unwrap();
+        assert_eq!(fillme, [0xbb]);
+    }
+    #[test]
     fn test_loc() {
         let mut core = Core::new_no_colors();
         core.set_loc(0x500);
@@ -253,7 +446,7 @@ mod test_core {
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Error: Execution failed\nCommand seeker is not found.\nSimilar command: seek.\n"
+            "Error: Execution failed\nUnknown command 'seeker'. Did you mean 'seek'?\n"
         );
     }
     #[test]
@@ -266,19 +459,63 @@ mod test_core {
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Error: Execution failed\nCommand mep is not found.\nSimilar command: map, maps, m, e, er, eh.\n"
+            "Error: Execution failed\nUnknown command 'mep'. Did you mean 'map', 'maps', 'Lp', 'm', 'e', 'er', 'eh', 'rel'?\n"
         );
     }
     #[test]
+    fn test_error_count() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        assert_eq!(core.error_count(), 0);
+        core.run("s", &["0x10".to_owned()]);
+        assert_eq!(core.error_count(), 0);
+        core.run("s", &[]);
+        core.run("s", &["x".to_owned()]);
+        core.run("nothing", &[]);
+        assert_eq!(core.error_count(), 3);
+        core.count_error();
+        assert_eq!(core.error_count(), 4);
+    }
+    #[test]
     fn test_help_failure_with_extras() {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
         core.help("seeker");
-        assert_eq!(core.stdout.utf8_string().unwrap(), "Commands: [seek | s]\nUsage:\ns +\t\tRedo Seek.\ns -\t\tUndo Seek.\ns +[offset]\tIncrease current loc by offset.\ns -[offset]\tDecrease current loc by offset.\ns [offset]\tSet current location to offset.\n");
+        assert_eq!(core.stdout.utf8_string().unwrap(), "Commands: [seek | s]\nUsage:\ns +\t\tRedo Seek.\ns -\t\tUndo Seek.\ns +[offset]\tIncrease current loc by offset.\ns -[offset]\tDecrease current loc by offset.\ns [offset]\tSet current location to offset.\ns [expr]\tSet current location to address expression using $$ (current location), $ (end of address space), + and -.\n");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Execution failed\nUnknown command 'seeker'. Did you mean 'seek'?\n"
+        );
+    }
+    #[test]
+    fn test_did_you_mean() {
+        let mut core = Core::new_no_colors();
+        testings_env(&mut core);
+        core.stderr = Writer::new_buf();
+        core.run("mpde", &[]);
+        core.run("fiiles", &[]);
+        core.run("xyzzyx", &[]);
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Error: Execution failed\nCommand seeker is not found.\nSimilar command: seek.\n"
+            "Error: Execution failed\nUnknown command 'mpde'. Did you mean 'mode'?\n\
+             Error: Execution failed\nUnknown command 'fiiles'. Did you mean 'files'?\n\
+             Error: Execution failed\nUnknown command 'xyzzyx'.\n"
+        );
+    }
+    #[test]
+    fn test_complete() {
+        let core = Core::new_no_colors();
+        assert_eq!(
+            core.complete("m"),
+            ["m", "map", "mapLayout", "maps", "mode"]
         );
+        assert_eq!(core.complete("op"), ["open", "openMany"]);
+        assert_eq!(core.complete("openMany"), ["openMany"]);
+        assert!(core.complete("xyz").is_empty());
+        let all = core.complete("");
+        assert!(all.contains(&"seek") && all.contains(&"q"));
+        assert!(all.is_sorted());
     }
 }
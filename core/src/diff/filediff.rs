@@ -0,0 +1,232 @@
+//! command for comparing open files.
+
+use crate::io::encode_hex;
+use crate::{error_msg, expect, str_to_num, Cmd, Core};
+use core::cmp;
+use rair_io::IoError;
+use std::io::Write;
+
+const CHUNK_SIZE: u64 = 0x1000;
+
+// Run of differing bytes, offset is relative to the start of both files. Runs are printed once
+// they reach `CHUNK_SIZE` bytes so files that differ throughout are never held in memory.
+struct Run {
+    start: u64,
+    old: Vec<u8>,
+    new: Vec<u8>,
+}
+
+impl Run {
+    fn print(&self, core: &mut Core) {
+        let (start, len) = (self.start, self.old.len());
+        let (old, new) = (encode_hex(&self.old), encode_hex(&self.new));
+        writeln!(core.stdout, "0x{start:08x} 0x{len:x}\t{old}\t{new}").unwrap();
+    }
+}
+
+#[derive(Default)]
+pub struct Diff;
+
+impl Diff {
+    // Physical address and size of the file opened as `hndl`.
+    fn file_range(core: &Core, hndl: u64) -> Result<(u64, u64), IoError> {
+        let desc = core
+            .io
+            .hndl_to_desc(hndl)
+            .ok_or(IoError::HndlNotFoundError)?;
+        Ok((desc.paddr_base(), desc.size()))
+    }
+
+    fn diff(core: &mut Core, old: (u64, u64), new: (u64, u64)) -> Result<(), IoError> {
+        let size = cmp::min(old.1, new.1);
+        let mut old_buf = vec![0; cmp::min(size, CHUNK_SIZE) as usize];
+        let mut new_buf = old_buf.clone();
+        let mut run: Option<Run> = None;
+        let mut offset = 0;
+        while offset < size {
+            let len = cmp::min(CHUNK_SIZE, size - offset) as usize;
+            core.io.pread(old.0 + offset, &mut old_buf[..len])?;
+            core.io.pread(new.0 + offset, &mut new_buf[..len])?;
+            for (i, (o, n)) in old_buf[..len].iter().zip(&new_buf[..len]).enumerate() {
+                if o == n {
+                    if let Some(run) = run.take() {
+                        run.print(core);
+                    }
+                    continue;
+                }
+                let current = run.get_or_insert_with(|| Run {
+                    start: offset + i as u64,
+                    old: Vec::new(),
+                    new: Vec::new(),
+                });
+                current.old.push(*o);
+                current.new.push(*n);
+                if current.old.len() as u64 == CHUNK_SIZE {
+                    if let Some(run) = run.take() {
+                        run.print(core);
+                    }
+                }
+            }
+            offset += len as u64;
+        }
+        if let Some(run) = run {
+            run.print(core);
+        }
+        Ok(())
+    }
+}
+
+impl Cmd for Diff {
+    fn commands(&self) -> &'static [&'static str] {
+        &["diff"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "[hndl1] [hndl2]",
+            "Print offset, size, old bytes and new bytes of every range that differs between files with given hndls.",
+        )]
+    }
+
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() != 2 {
+            expect(core, args.len() as u64, 2);
+            return;
+        }
+        let mut hndls = [0; 2];
+        for (hndl, arg) in hndls.iter_mut().zip(args) {
+            *hndl = match str_to_num(arg) {
+                Ok(hndl) => hndl,
+                Err(e) => {
+                    let err_str = format!("{e}");
+                    error_msg(core, "Invalid hndl", &err_str);
+                    return;
+                }
+            };
+        }
+        let ranges = Self::file_range(core, hndls[0])
+            .and_then(|old| Ok((old, Self::file_range(core, hndls[1])?)));
+        let (old, new) = match ranges {
+            Ok(ranges) => ranges,
+            Err(e) => return error_msg(core, "Failed to diff files", &e.to_string()),
+        };
+        if let Err(e) = Self::diff(core, old, new) {
+            return error_msg(core, "Read Failed", &e.to_string());
+        }
+        // bytes past the end of the smaller file only exist in the larger one.
+        let (size, larger, hndl) = if old.1 > new.1 {
+            (new.1, old.1, hndls[0])
+        } else {
+            (old.1, new.1, hndls[1])
+        };
+        if larger > size {
+            let len = larger - size;
+            writeln!(core.stdout, "0x{size:08x} 0x{len:x}\tonly in {hndl}").unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_diff {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use rair_io::IoMode;
+
+    #[test]
+    fn test_help() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let diff = Diff;
+        diff.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [diff]\n\
+             Usage:\n\
+             diff [hndl1] [hndl2]\tPrint offset, size, old bytes and new bytes of every range that differs between files with given hndls.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let perm = IoMode::READ | IoMode::WRITE;
+        let old = core.io.open("malloc://0x2000", perm).unwrap();
+        let new = core.io.open("malloc://0x2010", perm).unwrap();
+        let paddr = core.io.hndl_to_desc(new).unwrap().paddr_base();
+        core.io.pwrite(paddr + 0x10, &[0xde, 0xad]).unwrap();
+        core.io.pwrite(paddr + 0x13, &[0xff]).unwrap();
+        // spans two chunks.
+        core.io.pwrite(paddr + 0xffe, &[1; 4]).unwrap();
+        core.io.pwrite(paddr + 0x1fff, &[2; 0x11]).unwrap();
+        core.run("diff", &[old.to_string(), new.to_string()]);
+        core.run("diff", &[new.to_string(), old.to_string()]);
+        core.run("diff", &[old.to_string(), old.to_string()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00000010 0x2\t0000\tdead\n\
+             0x00000013 0x1\t00\tff\n\
+             0x00000ffe 0x4\t00000000\t01010101\n\
+             0x00001fff 0x1\t00\t02\n\
+             0x00002000 0x10\tonly in 1\n\
+             0x00000010 0x2\tdead\t0000\n\
+             0x00000013 0x1\tff\t00\n\
+             0x00000ffe 0x4\t01010101\t00000000\n\
+             0x00001fff 0x1\t02\t00\n\
+             0x00002000 0x10\tonly in 1\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_long_run() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let perm = IoMode::READ | IoMode::WRITE;
+        let old = core.io.open("malloc://0x2000", perm).unwrap();
+        let new = core.io.open("malloc://0x2000", perm).unwrap();
+        let paddr = core.io.hndl_to_desc(new).unwrap().paddr_base();
+        core.io.pwrite(paddr + 0x10, &[0xff; 0x1800]).unwrap();
+        core.run("diff", &[old.to_string(), new.to_string()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            format!(
+                "0x00000010 0x1000\t{}\t{}\n0x00001010 0x800\t{}\t{}\n",
+                "00".repeat(0x1000),
+                "ff".repeat(0x1000),
+                "00".repeat(0x800),
+                "ff".repeat(0x800)
+            )
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x10", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.run("diff", &["0".to_owned()]);
+        core.run("diff", &["0".to_owned(), "x".to_owned()]);
+        core.run("diff", &["0".to_owned(), "1".to_owned()]);
+        core.run("diff", &["1".to_owned(), "0".to_owned()]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected 2 argument(s), found 1.\n\
+             Error: Invalid hndl\n\
+             invalid digit found in string\n\
+             Error: Failed to diff files\n\
+             Handle Does not exist.\n\
+             Error: Failed to diff files\n\
+             Handle Does not exist.\n"
+        );
+    }
+}
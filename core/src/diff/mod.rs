@@ -1,8 +1,12 @@
+mod filediff;
 mod hexdiff;
+mod verify;
 
 use crate::Core;
 
 pub fn register_diff(core: &mut Core) {
     let hexdiff = hexdiff::HexDiff::new(core);
     core.add_command(hexdiff);
+    core.add_command(verify::Verify);
+    core.add_command(filediff::Diff);
 }
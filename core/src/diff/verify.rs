@@ -0,0 +1,206 @@
+//! command for comparing opened descriptor against file on disk.
+
+use crate::{error_msg, expect, str_to_num, Cmd, Core};
+use core::cmp::min;
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+const CHUNK_SIZE: u64 = 0x1000;
+
+#[derive(Default)]
+pub struct Verify;
+
+impl Verify {
+    // read from `file` until `buf` is full or end of file is reached.
+    fn fill(file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            let n = file.read(&mut buf[filled..])?;
+            if n == 0 {
+                break;
+            }
+            filled += n;
+        }
+        Ok(filled)
+    }
+}
+
+impl Cmd for Verify {
+    fn commands(&self) -> &'static [&'static str] {
+        &["verify"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "[hndl] [path]",
+            "Compare content of file with given [hndl] against file at [path].",
+        )]
+    }
+
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() != 2 {
+            expect(core, args.len() as u64, 2);
+            return;
+        }
+        let hndl = match str_to_num(&args[0]) {
+            Ok(hndl) => hndl,
+            Err(e) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Invalid hndl", &err_str);
+                return;
+            }
+        };
+        let Some(desc) = core.io.hndl_to_desc(hndl) else {
+            return error_msg(core, "Invalid hndl", "Handle Does not exist.");
+        };
+        let (base, size) = (desc.paddr_base(), desc.size());
+        let mut file = match File::open(&args[1]) {
+            Ok(file) => file,
+            Err(e) => {
+                let err_str = format!("{e}.");
+                error_msg(core, "Failed to open file", &err_str);
+                return;
+            }
+        };
+        let file_size = match file.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(e) => {
+                let err_str = format!("{e}.");
+                error_msg(core, "Failed to open file", &err_str);
+                return;
+            }
+        };
+        let common = min(size, file_size);
+        let mut first = None;
+        // bytes that exist only in one of them are counted as different.
+        let mut count = size.max(file_size) - common;
+        let mut desc_buf = vec![0; CHUNK_SIZE as usize];
+        let mut file_buf = vec![0; CHUNK_SIZE as usize];
+        for offset in (0..common).step_by(CHUNK_SIZE as usize) {
+            let len = min(CHUNK_SIZE, common - offset) as usize;
+            if let Err(e) = core.io.pread(base + offset, &mut desc_buf[..len]) {
+                return error_msg(core, "Read Failed", &e.to_string());
+            }
+            match Self::fill(&mut file, &mut file_buf[..len]) {
+                Ok(n) if n == len => (),
+                Ok(_) => {
+                    return error_msg(core, "Read Failed", "File changed while being read.");
+                }
+                Err(e) => {
+                    let err_str = format!("{e}.");
+                    return error_msg(core, "Read Failed", &err_str);
+                }
+            }
+            for (i, (x, y)) in desc_buf[..len].iter().zip(&file_buf[..len]).enumerate() {
+                if x != y {
+                    first.get_or_insert(offset + i as u64);
+                    count += 1;
+                }
+            }
+        }
+        if count != 0 && first.is_none() {
+            first = Some(common);
+        }
+        match first {
+            Some(offset) => writeln!(
+                core.stdout,
+                "First difference at offset 0x{offset:x}, 0x{count:x} byte(s) differ."
+            )
+            .unwrap(),
+            None => writeln!(core.stdout, "identical").unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_verify {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use rair_io::IoMode;
+    use std::path::Path;
+    use test_file::{operate_on_file, DATA};
+
+    #[test]
+    fn test_help() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let verify = Verify;
+        verify.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [verify]\n\
+             Usage:\n\
+             verify [hndl] [path]\tCompare content of file with given [hndl] against file at [path].\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    fn test_verify_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut verify = Verify;
+        let uri = path.to_str().unwrap().to_owned();
+        core.io.open(&uri, IoMode::READ).unwrap();
+        core.io.open_at(&uri, IoMode::COW, 0x1000).unwrap();
+        core.io.pwrite(0x1010, &[0xff, 0xff]).unwrap();
+        core.io.pwrite(0x1020, &[0xff]).unwrap();
+        verify.run(&mut core, &["0".to_owned(), uri.clone()]);
+        verify.run(&mut core, &["1".to_owned(), uri]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "identical\n\
+             First difference at offset 0x10, 0x3 byte(s) differ.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_verify() {
+        operate_on_file(&test_verify_cb, DATA);
+    }
+
+    fn test_verify_size_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut verify = Verify;
+        let uri = path.to_str().unwrap().to_owned();
+        core.io
+            .open("malloc://0x70", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0, DATA).unwrap();
+        verify.run(&mut core, &["0".to_owned(), uri]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "First difference at offset 0x69, 0x7 byte(s) differ.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_verify_size() {
+        operate_on_file(&test_verify_size_cb, DATA);
+    }
+
+    #[test]
+    fn test_verify_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut verify = Verify;
+        verify.run(&mut core, &[]);
+        verify.run(&mut core, &["x".to_owned(), "path".to_owned()]);
+        verify.run(&mut core, &["5".to_owned(), "path".to_owned()]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected 2 argument(s), found 0.\n\
+             Error: Invalid hndl\n\
+             invalid digit found in string\n\
+             Error: Invalid hndl\n\
+             Handle Does not exist.\n"
+        );
+    }
+}
@@ -14,21 +14,83 @@ use yansi::Paint;
 
 pub type MRc<T> = Arc<Mutex<T>>; //mutable refcounter that is thread safe
 
+/// Parse a number as described in [`rair_io::str_to_num`]. A quoted character such as `'A'`
+/// yields its code point.
 pub fn str_to_num(n: &str) -> Result<u64, num::ParseIntError> {
-    if n.len() >= 2 {
-        match &*n[0..2].to_lowercase() {
-            "0b" => return u64::from_str_radix(&n[2..], 2),
-            "0x" => return u64::from_str_radix(&n[2..], 16),
-            _ => (),
+    if let Some(c) = n.strip_prefix('\'').and_then(|c| c.strip_suffix('\'')) {
+        let mut chars = c.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(u64::from(c));
         }
     }
-    if n.len() > 1 && n.starts_with('0') {
-        return u64::from_str_radix(&n[1..], 8);
+    rair_io::str_to_num(n)
+}
+
+// end of the address space (exclusive) that is being used by current address mode.
+fn end_of_space(core: &Core) -> u64 {
+    match core.mode {
+        AddrMode::Phy => core
+            .io
+            .uri_iter()
+            .map(|desc| desc.paddr_base() + desc.size())
+            .max(),
+        AddrMode::Vir => core.io.map_iter().map(|map| map.vaddr + map.size).max(),
+    }
+    .unwrap_or(0)
+}
+
+pub(crate) fn addr_term(core: &Core, term: &str) -> Result<u64, String> {
+    match term {
+        "$$" => Ok(core.get_loc()),
+        "$" => Ok(end_of_space(core)),
+        "" => Err("Missing operand.".to_owned()),
+        // flags come first as names such as `_1` are numbers once `_` is dropped.
+        _ => match core.flags.get(term) {
+            Some(addr) => Ok(*addr),
+            None => str_to_num(term).map_err(|e| e.to_string()),
+        },
+    }
+}
+
+// operands end at the next `+` or `-` unless it is quoted in a char literal such as `'+'`.
+fn term_end(expr: &str) -> usize {
+    let start = expr
+        .strip_prefix('\'')
+        .and_then(|rest| rest.find('\''))
+        .map_or(0, |quote| quote + 2);
+    expr[start..]
+        .find(['+', '-'])
+        .map_or(expr.len(), |end| end + start)
+}
+
+/// Parse address expression made of numbers, flag names, `$$` (current location) and `$` (end
+/// of current address space) joined by `+` or `-`, for example `$$-0x10` or `main+0x10`.
+pub fn str_to_addr(core: &Core, n: &str) -> Result<u64, String> {
+    let mut addr: u64 = 0;
+    let mut op = '+';
+    let mut rest = n;
+    loop {
+        let end = term_end(rest);
+        let operand = addr_term(core, &rest[..end])?;
+        addr = if op == '+' {
+            addr.checked_add(operand).ok_or("Address overflow.")?
+        } else {
+            addr.checked_sub(operand).ok_or("Address underflow.")?
+        };
+        if end == rest.len() {
+            return Ok(addr);
+        }
+        op = if rest[end..].starts_with('+') {
+            '+'
+        } else {
+            '-'
+        };
+        rest = &rest[end + 1..];
     }
-    n.parse::<u64>()
 }
 
 pub fn expect(core: &mut Core, args_len: u64, expect: u64) {
+    core.count_error();
     let (r, g, b) = core.env.read().get_color("color.4").unwrap();
     let error = "Arguments Error";
     let expected = format!("{expect}");
@@ -45,6 +107,7 @@ pub fn expect(core: &mut Core, args_len: u64, expect: u64) {
 
 pub fn expect_range(core: &mut Core, args_len: u64, min: u64, max: u64) {
     assert!(min < max);
+    core.count_error();
     let (r, g, b) = core.env.read().get_color("color.4").unwrap();
     let error = "Arguments Error";
     let min_str = format!("{min}");
@@ -62,6 +125,7 @@ pub fn expect_range(core: &mut Core, args_len: u64, min: u64, max: u64) {
 }
 
 pub fn error_msg(core: &mut Core, title: &str, msg: &str) {
+    core.count_error();
     let (r, g, b) = core.env.read().get_color("color.4").unwrap();
     writeln!(
         core.stderr,
@@ -120,6 +184,7 @@ mod test_helper {
     use super::*;
     use crate::writer::Writer;
     use core::fmt::Write;
+    use rair_io::IoMode;
     #[test]
     fn test_str_to_num() {
         assert_eq!(str_to_num("12345").unwrap(), 12345);
@@ -128,7 +193,122 @@ mod test_helper {
         assert_eq!(str_to_num("0x12345").unwrap(), 0x12345);
         assert_eq!(str_to_num("0X1F2f345").unwrap(), 0x1f2f345);
         assert_eq!(str_to_num("0").unwrap(), 0);
-        str_to_num("0x12345123451234512").unwrap_err();
+        assert_eq!(str_to_num("0o17").unwrap(), 0o17);
+        assert_eq!(str_to_num("0O17").unwrap(), 0o17);
+        assert_eq!(str_to_num("0x1_000").unwrap(), 0x1000);
+        assert_eq!(str_to_num("0b1010_1010").unwrap(), 0b1010_1010);
+        assert_eq!(str_to_num("1_000_000").unwrap(), 1_000_000);
+        assert_eq!(str_to_num("'A'").unwrap(), 0x41);
+        assert_eq!(str_to_num("'_'").unwrap(), 0x5f);
+        assert_eq!(str_to_num("'λ'").unwrap(), 0x3bb);
+        assert_eq!(
+            str_to_num("0x12345123451234512").unwrap_err().kind(),
+            &num::IntErrorKind::PosOverflow
+        );
+        assert_eq!(
+            str_to_num("18446744073709551616").unwrap_err().kind(),
+            &num::IntErrorKind::PosOverflow
+        );
+        assert_eq!(
+            str_to_num("0o8").unwrap_err().kind(),
+            &num::IntErrorKind::InvalidDigit
+        );
+        assert_eq!(
+            str_to_num("'AB'").unwrap_err().kind(),
+            &num::IntErrorKind::InvalidDigit
+        );
+        assert_eq!(
+            str_to_num("''").unwrap_err().kind(),
+            &num::IntErrorKind::InvalidDigit
+        );
+        assert_eq!(
+            str_to_num("_").unwrap_err().kind(),
+            &num::IntErrorKind::Empty
+        );
+    }
+
+    #[test]
+    fn test_str_to_num_suffix() {
+        assert_eq!(str_to_num("1K").unwrap(), 1024);
+        assert_eq!(str_to_num("1k").unwrap(), 1024);
+        assert_eq!(str_to_num("2M").unwrap(), 2 * 1024 * 1024);
+        assert_eq!(str_to_num("0x10k").unwrap(), 0x4000);
+        assert_eq!(str_to_num("16G").unwrap(), 16 << 30u8);
+        assert_eq!(str_to_num("3kb").unwrap(), 3000);
+        assert_eq!(str_to_num("0b11MB").unwrap(), 3_000_000);
+        assert_eq!(str_to_num("2gb").unwrap(), 2_000_000_000);
+        assert_eq!(str_to_num("16_777_215G").unwrap(), 0x3f_ffff_c000_0000);
+        assert_eq!(
+            str_to_num("17179869184G").unwrap_err().kind(),
+            &num::IntErrorKind::PosOverflow
+        );
+        assert_eq!(
+            str_to_num("0x10000000000000000K").unwrap_err().kind(),
+            &num::IntErrorKind::PosOverflow
+        );
+        for ambiguous in ["1KK", "1kM", "k", "1bk", "1K2"] {
+            assert!(matches!(
+                str_to_num(ambiguous).unwrap_err().kind(),
+                num::IntErrorKind::InvalidDigit | num::IntErrorKind::Empty
+            ));
+        }
+    }
+
+    #[test]
+    fn test_str_to_addr() {
+        let mut core = Core::new_no_colors();
+        core.set_loc(0x100);
+        assert_eq!(str_to_addr(&core, "0x50").unwrap(), 0x50);
+        assert_eq!(str_to_addr(&core, "$$").unwrap(), 0x100);
+        assert_eq!(str_to_addr(&core, "$").unwrap(), 0);
+        core.io
+            .open_at("malloc://0x500", IoMode::READ | IoMode::WRITE, 0x1000)
+            .unwrap();
+        core.io.map(0x1000, 0x8000, 0x200).unwrap();
+        assert_eq!(str_to_addr(&core, "$").unwrap(), 0x1500);
+        assert_eq!(str_to_addr(&core, "$-0x100").unwrap(), 0x1400);
+        assert_eq!(str_to_addr(&core, "$$-0x10").unwrap(), 0xf0);
+        assert_eq!(str_to_addr(&core, "$$+0x10").unwrap(), 0x110);
+        assert_eq!(str_to_addr(&core, "$-$$").unwrap(), 0x1400);
+        assert_eq!(str_to_addr(&core, "$$+0b11-010+5").unwrap(), 0x100);
+        assert_eq!(str_to_addr(&core, "0x10-0x10").unwrap(), 0);
+        // operators inside char literals are part of the literal.
+        assert_eq!(str_to_addr(&core, "'+'").unwrap(), 0x2b);
+        assert_eq!(str_to_addr(&core, "'-'+'+'").unwrap(), 0x58);
+        assert_eq!(str_to_addr(&core, "$$-'-'").unwrap(), 0xd3);
+        core.mode = AddrMode::Vir;
+        assert_eq!(str_to_addr(&core, "$").unwrap(), 0x8200);
+        assert_eq!(
+            str_to_addr(&core, "$$-0x101").unwrap_err(),
+            "Address underflow."
+        );
+        assert_eq!(
+            str_to_addr(&core, "$$+0xffffffffffffffff").unwrap_err(),
+            "Address overflow."
+        );
+        assert_eq!(str_to_addr(&core, "$$+").unwrap_err(), "Missing operand.");
+        assert_eq!(str_to_addr(&core, "").unwrap_err(), "Missing operand.");
+        assert_eq!(str_to_addr(&core, "+5").unwrap_err(), "Missing operand.");
+        assert_eq!(
+            str_to_addr(&core, "$$+0xz").unwrap_err(),
+            "invalid digit found in string"
+        );
+        core.flags.insert("main".to_owned(), 0x1200);
+        assert_eq!(str_to_addr(&core, "main").unwrap(), 0x1200);
+        assert_eq!(str_to_addr(&core, "main+0x10-$$").unwrap(), 0x1110);
+        assert_eq!(
+            str_to_addr(&core, "nothing").unwrap_err(),
+            "invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_flag_before_number() {
+        let mut core = Core::new_no_colors();
+        core.flags.insert("_1".to_owned(), 0x2000);
+        assert_eq!(str_to_addr(&core, "_1").unwrap(), 0x2000);
+        assert_eq!(str_to_addr(&core, "_1+1_0").unwrap(), 0x200a);
+        assert_eq!(str_to_addr(&core, "_2").unwrap(), 2);
     }
 
     #[test]
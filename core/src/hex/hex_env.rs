@@ -1,5 +1,5 @@
 use super::helper::one_byte;
-use crate::{is_color, Core, Writer};
+use crate::{is_color, AddrMode, Core, Writer};
 use std::io::Write;
 use yansi::Paint;
 
@@ -18,6 +18,8 @@ pub struct HexEnv {
     pub noprint: char,
     // separator between side by side views
     pub separator: String,
+    // (hndl, base, size) of the file used for relative addresses
+    pub rel: Option<(u64, u64, u64)>,
 }
 
 impl HexEnv {
@@ -87,6 +89,7 @@ impl HexEnv {
             gap: char::default(),
             noprint: char::default(),
             separator: String::new(),
+            rel: None,
         }
     }
     pub(super) fn get_env(&mut self, core: &mut Core) -> &Self {
@@ -112,6 +115,14 @@ impl HexEnv {
         env.get_str("hex.separator")
             .unwrap()
             .clone_into(&mut self.separator);
+        // relative addresses only make sense in the physical address space.
+        self.rel = match (core.mode, core.rel_hndl) {
+            (AddrMode::Phy, Some(hndl)) => core
+                .io
+                .hndl_to_desc(hndl)
+                .map(|desc| (hndl, desc.paddr_base(), desc.size())),
+            _ => None,
+        };
         self
     }
     pub fn print_banner_with_newline(&self, writer: &mut Writer, newline: bool) {
@@ -136,7 +147,12 @@ impl HexEnv {
         self.print_banner_with_newline(writer, true);
     }
     pub fn print_addr(&self, writer: &mut Writer, loc: u64) {
-        let loc = format!("0x{loc:08x}");
+        let loc = match self.rel {
+            Some((hndl, base, size)) if loc >= base && loc - base < size => {
+                format!("{hndl}+0x{:06x}", loc - base)
+            }
+            _ => format!("0x{loc:08x}"),
+        };
         let (r, g, b) = self.banner;
         let loc_colored = loc.rgb(r, g, b);
         write!(writer, "{loc_colored} ").unwrap();
@@ -161,11 +177,6 @@ impl HexEnv {
             write!(writer, "{hex}{space}").unwrap();
         }
     }
-
-    // print hex data all while taking care of extra white space
-    pub fn print_hex(&self, data: Option<u8>, writer: &mut Writer, space_after: bool) {
-        self.print_hex_with_highlight(data, writer, space_after, false);
-    }
     pub fn print_ascii_with_highlight(
         &self,
         data: Option<u8>,
@@ -189,11 +200,6 @@ impl HexEnv {
             write!(writer, "{ascii}").unwrap();
         }
     }
-
-    // print ascii data while taking care of non printable characters and coloring
-    pub fn print_ascii(&self, data: Option<u8>, writer: &mut Writer) {
-        self.print_ascii_with_highlight(data, writer, false);
-    }
     pub fn print_separator(&self, writer: &mut Writer) {
         let (r, g, b) = self.banner;
         write!(writer, "    {}    ", self.separator.rgb(r, g, b)).unwrap();
@@ -0,0 +1,317 @@
+//! commands for computing checksums of data.
+
+use super::print::block_size;
+use crate::core::Core;
+use crate::helper::{error_msg, expect, expect_range, str_to_num};
+use crate::Cmd;
+use core::cmp;
+use rair_io::IoError;
+use std::io::Write;
+
+const CHUNK_SIZE: u64 = 0x1000;
+
+/// CRC-32 (IEEE 802.3) hasher.
+pub struct Crc32 {
+    crc: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Crc32 { crc: !0 }
+    }
+}
+
+impl Crc32 {
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.crc ^= u32::from(*byte);
+            for _ in 0u8..8 {
+                let mask = (self.crc & 1).wrapping_neg();
+                self.crc = (self.crc >> 1u8) ^ (0xedb8_8320 & mask);
+            }
+        }
+    }
+    #[must_use]
+    pub fn finish(&self) -> u32 {
+        !self.crc
+    }
+}
+
+/// CRC32 of `size` bytes at `loc` using only the bytes that can be read.
+///
+/// Readable bytes are fed into the hasher in increasing address order and holes are skipped
+/// entirely rather than replaced by a fill value. As such the digest depends only on the
+/// sequence of readable bytes: two regions with the same readable bytes in the same order
+/// hash the same no matter where their holes are, and a region without holes hashes the
+/// same as reading it with [`Core::read`] and hashing the result. An empty range hashes to
+/// the CRC32 of no bytes.
+pub fn hash_sparse(core: &mut Core, loc: u64, size: u64) -> Result<u32, IoError> {
+    let mut hasher = Crc32::default();
+    if size == 0 {
+        return Ok(hasher.finish());
+    }
+    let data = core.read_sparce(loc, size)?;
+    for byte in data.values() {
+        hasher.update(&[*byte]);
+    }
+    Ok(hasher.finish())
+}
+
+#[derive(Default)]
+pub struct Cksum;
+
+impl Cksum {
+    // Checksum of `size` bytes at `loc` read in chunks.
+    fn record_crc(core: &mut Core, loc: u64, size: u64) -> Result<u32, String> {
+        if loc.checked_add(size).is_none() {
+            return Err("Address overflow.".to_owned());
+        }
+        let mut hasher = Crc32::default();
+        let mut buf = vec![0u8; cmp::min(size, CHUNK_SIZE) as usize];
+        let mut offset = 0u64;
+        while offset < size {
+            let len = cmp::min(CHUNK_SIZE, size - offset) as usize;
+            let addr = loc + offset;
+            core.read(addr, &mut buf[..len])
+                .map_err(|e| e.to_string())?;
+            hasher.update(&buf[..len]);
+            offset += len as u64;
+        }
+        Ok(hasher.finish())
+    }
+}
+
+impl Cmd for Cksum {
+    fn commands(&self) -> &'static [&'static str] {
+        &["cksum"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            (
+                "[recordsize] [count]",
+                "Print CRC32 of each of [count] consecutive records of [recordsize] bytes starting at current location.",
+            ),
+            (
+                "sparse",
+                "\t\tSame as below with `px.blockSize` as [size].",
+            ),
+            (
+                "sparse [size]",
+                "\tPrint CRC32 of readable bytes among [size] bytes starting at current location, skipping gaps.",
+            ),
+        ]
+    }
+
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.first().is_some_and(|arg| arg == "sparse") {
+            if args.len() > 2 {
+                expect_range(core, args.len() as u64, 1, 2);
+                return;
+            }
+            let size = match args.get(1).map(|arg| str_to_num(arg)) {
+                None => block_size(core),
+                Some(Ok(size)) => size,
+                Some(Err(e)) => {
+                    let err_str = format!("{e}");
+                    error_msg(core, "Failed to parse size", &err_str);
+                    return;
+                }
+            };
+            let loc = core.get_loc();
+            match hash_sparse(core, loc, size) {
+                Ok(crc) => writeln!(core.stdout, "0x{loc:08x} {crc:08x}").unwrap(),
+                Err(e) => error_msg(core, "Read Failed", &e.to_string()),
+            }
+            return;
+        }
+        if args.len() != 2 {
+            expect(core, args.len() as u64, 2);
+            return;
+        }
+        let size = match str_to_num(&args[0]) {
+            Ok(size) => size,
+            Err(e) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Failed to parse record size", &err_str);
+                return;
+            }
+        };
+        let count = match str_to_num(&args[1]) {
+            Ok(count) => count,
+            Err(e) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Failed to parse count", &err_str);
+                return;
+            }
+        };
+        if size == 0 {
+            return error_msg(
+                core,
+                "Failed to parse record size",
+                "Record size must be greater than 0.",
+            );
+        }
+        let loc = core.get_loc();
+        for i in 0..count {
+            let Some(addr) = size.checked_mul(i).and_then(|off| loc.checked_add(off)) else {
+                return error_msg(core, "Read Failed", "Address overflow.");
+            };
+            match Self::record_crc(core, addr, size) {
+                Ok(crc) => writeln!(core.stdout, "0x{addr:08x} {crc:08x}").unwrap(),
+                // unreadable records are reported but do not stop the remaining ones
+                Err(e) => error_msg(core, "Read Failed", &e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cksum {
+    use super::*;
+    use crate::{writer::Writer, AddrMode, CmdOps};
+    use rair_io::IoMode;
+
+    #[test]
+    fn test_help() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let cksum = Cksum;
+        cksum.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [cksum]\n\
+             Usage:\n\
+             cksum [recordsize] [count]\tPrint CRC32 of each of [count] consecutive records of [recordsize] bytes starting at current location.\n\
+             cksum sparse\t\t\tSame as below with `px.blockSize` as [size].\n\
+             cksum sparse [size]\t\tPrint CRC32 of readable bytes among [size] bytes starting at current location, skipping gaps.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_crc32() {
+        let mut hasher = Crc32::default();
+        assert_eq!(hasher.finish(), 0);
+        hasher.update(b"1234");
+        hasher.update(b"56789");
+        assert_eq!(hasher.finish(), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_cksum() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut cksum = Cksum;
+        core.io
+            .open("malloc://0x1c", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x0, b"123456789abc").unwrap();
+        core.io.pwrite(0x10, b"abc").unwrap();
+        cksum.run(&mut core, &["9".to_owned(), "4".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00000000 cbf43926\n\
+             0x00000009 5adb2a65\n\
+             0x00000012 83e7a9cf\n"
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Read Failed\n\
+             Cannot resolve address 0x1c.\n"
+        );
+    }
+
+    #[test]
+    fn test_hash_sparse() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut cksum = Cksum;
+        core.io
+            .open("malloc://0x9", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x0, b"123456789").unwrap();
+        core.mode = AddrMode::Vir;
+        // same bytes with and without holes in between.
+        core.io.map(0x0, 0x1000, 0x9).unwrap();
+        core.io.map(0x0, 0x2000, 0x4).unwrap();
+        core.io.map(0x4, 0x2010, 0x5).unwrap();
+        assert_eq!(hash_sparse(&mut core, 0x1000, 0x9).unwrap(), 0xcbf4_3926);
+        assert_eq!(hash_sparse(&mut core, 0x2000, 0x15).unwrap(), 0xcbf4_3926);
+        // different bytes or order gives different digest.
+        assert_ne!(hash_sparse(&mut core, 0x2010, 0x5).unwrap(), 0xcbf4_3926);
+        assert_eq!(hash_sparse(&mut core, 0x3000, 0x10).unwrap(), 0);
+        assert_eq!(hash_sparse(&mut core, 0x1000, 0).unwrap(), 0);
+        core.set_loc(0x2000);
+        cksum.run(&mut core, &["sparse".to_owned(), "0x15".to_owned()]);
+        cksum.run(&mut core, &["sparse".to_owned(), "0".to_owned()]);
+        cksum.run(&mut core, &["sparse".to_owned(), "x".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00002000 cbf43926\n0x00002000 00000000\n"
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to parse size\n\
+             invalid digit found in string\n"
+        );
+    }
+
+    #[test]
+    fn test_cksum_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut cksum = Cksum;
+        cksum.run(&mut core, &[]);
+        cksum.run(&mut core, &["x".to_owned(), "1".to_owned()]);
+        cksum.run(&mut core, &["1".to_owned(), "x".to_owned()]);
+        cksum.run(&mut core, &["0".to_owned(), "1".to_owned()]);
+        cksum.run(
+            &mut core,
+            &["sparse".to_owned(), "1".to_owned(), "2".to_owned()],
+        );
+        core.set_loc(u64::MAX - 0x8);
+        cksum.run(&mut core, &["0x10".to_owned(), "3".to_owned()]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected 2 argument(s), found 0.\n\
+             Error: Failed to parse record size\n\
+             invalid digit found in string\n\
+             Error: Failed to parse count\n\
+             invalid digit found in string\n\
+             Error: Failed to parse record size\n\
+             Record size must be greater than 0.\n\
+             Arguments Error: Expected between 1 and 2 arguments, found 3.\n\
+             Error: Read Failed\n\
+             Address overflow.\n\
+             Error: Read Failed\n\
+             Address overflow.\n"
+        );
+    }
+
+    #[test]
+    fn test_cksum_block_size() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x10, b"123456789").unwrap();
+        let env = core.env.clone();
+        env.write()
+            .set_u64("px.blockSize", 0x20, &mut core)
+            .unwrap();
+        core.run("cksum", &["sparse".to_owned()]);
+        core.run("cksum", &["sparse".to_owned(), "0x20".to_owned()]);
+        let out = core.stdout.utf8_string().unwrap();
+        let (default, explicit) = out.split_at(out.len() / 2);
+        assert_eq!(default, explicit);
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+}
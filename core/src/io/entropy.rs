@@ -0,0 +1,249 @@
+//! command for measuring entropy of data.
+
+use super::print::block_size;
+use crate::core::Core;
+use crate::helper::{error_msg, expect_range, str_to_num};
+use crate::Cmd;
+use alloc::collections::BTreeMap;
+use rair_env::Environment;
+use std::io::Write;
+
+fn is_window_size(_: &str, size: u64, _: &Environment<Core>, _: &mut Core) -> bool {
+    size != 0
+}
+
+// Shannon entropy in bits per byte of `size` bytes starting at `start`, bytes missing from
+// `data` are holes and all of them count as one extra symbol.
+fn entropy(data: &BTreeMap<u64, u8>, start: u64, size: u64) -> f64 {
+    let mut freq = [0u64; 257];
+    let mut readable = 0;
+    for byte in data.range(start..start + size).map(|(_, byte)| *byte) {
+        freq[byte as usize] += 1;
+        readable += 1;
+    }
+    freq[256] = size - readable;
+    let size = size as f64;
+    freq.iter()
+        .filter(|count| **count != 0)
+        .map(|count| {
+            let p = *count as f64 / size;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+pub struct Entropy;
+
+impl Entropy {
+    pub fn new(core: &mut Core) -> Self {
+        let env = core.env.clone();
+        env.write()
+            .add_u64_with_cb(
+                "ent.windowSize",
+                256,
+                "Number of bytes that each entropy value printed by `ent` command is computed over",
+                core,
+                is_window_size,
+            )
+            .unwrap();
+        env.write()
+            .add_u64(
+                "ent.step",
+                0,
+                "Number of bytes between the starts of consecutive windows of `ent` command, 0 means `ent.windowSize`",
+            )
+            .unwrap();
+        Self
+    }
+}
+
+impl Cmd for Entropy {
+    fn commands(&self) -> &'static [&'static str] {
+        &["ent"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("", "\tSame as below with `px.blockSize` as [size]."),
+            (
+                "[size]",
+                "Print Shannon entropy of each window of ent.windowSize bytes starting every ent.step bytes within [size] bytes at current location, unreadable bytes count as one value.",
+            ),
+        ]
+    }
+
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() > 1 {
+            expect_range(core, args.len() as u64, 0, 1);
+            return;
+        }
+        let size = match args.first().map(|arg| str_to_num(arg)) {
+            None => block_size(core),
+            Some(Ok(size)) => size,
+            Some(Err(e)) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Failed to parse size", &err_str);
+                return;
+            }
+        };
+        let env = core.env.read();
+        let window = env.get_u64("ent.windowSize").unwrap();
+        let step = match env.get_u64("ent.step").unwrap() {
+            0 => window,
+            step => step,
+        };
+        drop(env);
+        if size == 0 {
+            return;
+        }
+        let loc = core.get_loc();
+        if loc.checked_add(size).is_none() {
+            return error_msg(core, "Read Failed", "Address overflow.");
+        }
+        let data = match core.read_sparce(loc, size) {
+            Ok(data) => data,
+            Err(e) => return error_msg(core, "Read Failed", &e.to_string()),
+        };
+        // windows overlap when the step is smaller than the window, the last one ends at size.
+        let mut offset = 0;
+        while offset < size {
+            let len = window.min(size - offset);
+            let addr = loc + offset;
+            writeln!(core.stdout, "0x{addr:08x} {:.3}", entropy(&data, addr, len)).unwrap();
+            if len == size - offset {
+                break;
+            }
+            offset = offset.saturating_add(step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_entropy {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use rair_io::IoMode;
+
+    #[test]
+    fn test_help() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let ent = Entropy;
+        ent.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [ent]\n\
+             Usage:\n\
+             ent\t\tSame as below with `px.blockSize` as [size].\n\
+             ent [size]\tPrint Shannon entropy of each window of ent.windowSize bytes starting every ent.step bytes within [size] bytes at current location, unreadable bytes count as one value.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_entropy() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x300", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        // constant bytes, every byte value once and two values alternating.
+        let random: Vec<u8> = (0..=255u8).map(|i| i.wrapping_mul(167)).collect();
+        core.io.pwrite(0x100, &random).unwrap();
+        core.io.pwrite(0x200, &[0u8, 1].repeat(0x80)).unwrap();
+        core.run("ent", &["0x400".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00000000 0.000\n\
+             0x00000100 8.000\n\
+             0x00000200 1.000\n\
+             0x00000300 0.000\n"
+        );
+        core.stdout = Writer::new_buf();
+        let env = core.env.clone();
+        env.write()
+            .set_u64("ent.windowSize", 0x80, &mut core)
+            .unwrap();
+        core.set_loc(0x2c0);
+        core.run("ent", &["0x90".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x000002c0 1.500\n\
+             0x00000340 0.000\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_step() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x40, &[1; 0x40]).unwrap();
+        let env = core.env.clone();
+        env.write()
+            .set_u64("ent.windowSize", 0x40, &mut core)
+            .unwrap();
+        env.write().set_u64("ent.step", 0x20, &mut core).unwrap();
+        // overlapping windows, the last one ends at the end of the range.
+        core.run("ent", &["0x80".to_owned()]);
+        env.write().set_u64("ent.step", 0x80, &mut core).unwrap();
+        // bytes between windows are skipped.
+        core.run("ent", &["0x100".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00000000 0.000\n\
+             0x00000020 1.000\n\
+             0x00000040 0.000\n\
+             0x00000000 0.000\n\
+             0x00000080 0.000\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_block_size() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x300", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x40, &[0u8, 1].repeat(0x20)).unwrap();
+        let env = core.env.clone();
+        env.write()
+            .set_u64("px.blockSize", 0x80, &mut core)
+            .unwrap();
+        core.run("ent", &[]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "0x00000000 0.811\n");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run("ent", &["1".to_owned(), "2".to_owned()]);
+        core.run("ent", &["x".to_owned()]);
+        core.run("ent", &["0".to_owned()]);
+        core.set_loc(0x10);
+        core.run("ent", &["0xffffffffffffffff".to_owned()]);
+        let env = core.env.clone();
+        assert!(env.write().set_u64("ent.windowSize", 0, &mut core).is_err());
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected between 0 and 1 arguments, found 2.\n\
+             Error: Failed to parse size\n\
+             invalid digit found in string\n\
+             Error: Read Failed\n\
+             Address overflow.\n"
+        );
+    }
+}
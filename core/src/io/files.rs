@@ -2,10 +2,32 @@
 
 use crate::helper::{error_msg, expect, expect_range, is_color, str_to_num};
 use crate::{cmd::Cmd, core::Core};
-use rair_io::IoMode;
-use std::io::Write;
+use rair_io::{IoMode, RIODesc};
+use serde::Serialize;
 use yansi::Paint;
 
+#[derive(Serialize)]
+struct FileRow {
+    handle: u64,
+    paddr: u64,
+    size: u64,
+    perm: String,
+    plugin: String,
+    uri: String,
+}
+
+impl FileRow {
+    fn text(&self) -> String {
+        // short permissions and plugin names need one more tab to keep next columns aligned.
+        let tab = if self.perm.len() < 6 { "\t" } else { "" };
+        let plugin_tab = if self.plugin.len() < 8 { "\t" } else { "" };
+        format!(
+            "{}\t0x{:08x}\t0x{:08x}\t{}{tab}\t{}{plugin_tab}\t{}",
+            self.handle, self.paddr, self.size, self.perm, self.plugin, self.uri
+        )
+    }
+}
+
 #[derive(Default)]
 pub struct ListFiles;
 
@@ -41,29 +63,24 @@ impl Cmd for ListFiles {
         let env = core.env.read();
         let color = env.get_str("maps.headerColor").unwrap();
         let (r, g, b) = env.get_color(color).unwrap();
-
-        writeln!(
-            core.stdout,
+        drop(env);
+        let header = format!(
             "{}",
-            "Handle\tStart address\tsize\t\tPermissions\tURI".rgb(r, g, b)
-        )
-        .unwrap();
-        for file in core.io.uri_iter() {
-            let perm = format!("{}", file.perm());
-            write!(
-                core.stdout,
-                "{}\t0x{:08x}\t0x{:08x}\t{}",
-                file.hndl(),
-                file.paddr_base(),
-                file.size(),
-                perm
-            )
-            .unwrap();
-            if perm.len() < 6 {
-                write!(core.stdout, "\t").unwrap();
-            }
-            writeln!(core.stdout, "\t{}", file.name()).unwrap();
-        }
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI".rgb(r, g, b)
+        );
+        let rows: Vec<FileRow> = core
+            .io
+            .uri_iter()
+            .map(|file| FileRow {
+                handle: file.hndl(),
+                paddr: file.paddr_base(),
+                size: file.size(),
+                perm: file.perm().to_string(),
+                plugin: file.plugin_name().to_owned(),
+                uri: file.name().to_owned(),
+            })
+            .collect();
+        core.emit_table(&header, &rows, FileRow::text);
     }
 }
 
@@ -77,6 +94,7 @@ fn parse_perm(p: &str) -> Result<IoMode, String> {
             'r' => perm |= IoMode::READ,
             'w' => perm |= IoMode::WRITE,
             'c' => perm |= IoMode::COW,
+            'x' => perm |= IoMode::EXEC,
             _ => return Err(format!("Unknown Permission: `{c}`")),
         }
     }
@@ -87,9 +105,16 @@ impl Cmd for OpenFile {
         &["o", "open"]
     }
     fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
-        &[("<Perm> [URI] <Addr>", "Open given URI using given optional permission (default to readonly) at given optional address.")]
+        &[
+            ("<Perm> [URI] <Addr>", "Open given URI using given optional permission (default to readonly) at given optional address."),
+            ("--plugin [name] <Perm> [URI] <Addr>", "Same as above but URI is opened by IO plugin [name] even if it does not accept it."),
+        ]
     }
     fn run(&mut self, core: &mut Core, args: &[String]) {
+        let (plugin, args) = match args {
+            [flag, name, rest @ ..] if flag == "--plugin" => (Some(name.as_str()), rest),
+            _ => (None, args),
+        };
         if args.len() > 3 || args.is_empty() {
             expect_range(core, args.len() as u64, 1, 2);
             return;
@@ -126,13 +151,84 @@ impl Cmd for OpenFile {
             uri = &args[0];
         }
 
-        let result = match addr {
-            Some(addr) => core.io.open_at(uri, perm, addr),
-            None => core.io.open(uri, perm),
+        let result = match (plugin, addr) {
+            (Some(plugin), Some(addr)) => core.io.open_at_with(plugin, uri, perm, addr),
+            (Some(plugin), None) => core.io.open_with(plugin, uri, perm),
+            (None, Some(addr)) => core.io.open_at(uri, perm, addr),
+            (None, None) => core.io.open(uri, perm),
+        };
+        let hndl = match result {
+            Ok(hndl) => hndl,
+            Err(e) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Failed to open file", &err_str);
+                return;
+            }
+        };
+        map_suggested(core, hndl);
+    }
+}
+
+fn map_suggested(core: &mut Core, hndl: u64) {
+    let layout: Vec<(u64, u64, u64)> = core
+        .io
+        .hndl_to_desc(hndl)
+        .unwrap()
+        .suggested_maps()
+        .into_iter()
+        .map(|map| (map.paddr, map.vaddr, map.size))
+        .collect();
+    if let Err(e) = core.io.apply_map_layout(&layout) {
+        let err_str = format!("{e}");
+        error_msg(core, "Failed to map suggested segments", &err_str);
+    }
+}
+
+#[derive(Default)]
+pub struct OpenMany;
+
+impl Cmd for OpenMany {
+    fn commands(&self) -> &'static [&'static str] {
+        &["openMany"]
+    }
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[("<Perm> [Pattern]", "Open all files matching given glob pattern in sorted order right after each other, using given optional permission (default to readonly).")]
+    }
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.is_empty() || args.len() > 2 {
+            expect_range(core, args.len() as u64, 1, 2);
+            return;
+        }
+        let mut perm = IoMode::READ;
+        if args.len() == 2 {
+            perm = match parse_perm(&args[0]) {
+                Ok(perm) => perm,
+                Err(e) => return error_msg(core, "Failed to parse permission", &e),
+            };
+        }
+        let pattern = &args[args.len() - 1];
+        let (hndls, errors) = match core.io.open_many(pattern, perm) {
+            Ok(result) => result,
+            Err(e) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Failed to open files", &err_str);
+                return;
+            }
         };
-        if let Err(e) = result {
+        if hndls.is_empty() && errors.is_empty() {
+            error_msg(
+                core,
+                "Failed to open files",
+                &format!("No files match {pattern}"),
+            );
+            return;
+        }
+        for (uri, e) in errors {
             let err_str = format!("{e}");
-            error_msg(core, "Failed to open file", &err_str);
+            error_msg(core, &format!("Failed to open {uri}"), &err_str);
+        }
+        for hndl in hndls {
+            map_suggested(core, hndl);
         }
     }
 }
@@ -167,10 +263,78 @@ impl Cmd for CloseFile {
     }
 }
 
+#[derive(Default)]
+pub struct ReopenFile;
+
+impl Cmd for ReopenFile {
+    fn commands(&self) -> &'static [&'static str] {
+        &["reopen"]
+    }
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "<Perm> [hndl]",
+            "Reopen file with given hndl using given optional permission (default to current one) to pick up changes done to it on disk.",
+        )]
+    }
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() > 2 || args.is_empty() {
+            expect_range(core, args.len() as u64, 1, 2);
+            return;
+        }
+        let hndl = match str_to_num(&args[args.len() - 1]) {
+            Ok(hndl) => hndl,
+            Err(e) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Invalid hndl", &err_str);
+                return;
+            }
+        };
+        let perm = if args.len() == 2 {
+            match parse_perm(&args[0]) {
+                Ok(perm) => perm,
+                Err(e) => return error_msg(core, "Failed to parse permission", &e),
+            }
+        } else {
+            core.io
+                .hndl_to_desc(hndl)
+                .map_or(IoMode::READ, RIODesc::perm)
+        };
+        if let Err(e) = core.io.reopen(hndl, perm) {
+            let err_str = format!("{e}");
+            error_msg(core, "Failed to reopen file", &err_str);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct FlushFiles;
+
+impl Cmd for FlushFiles {
+    fn commands(&self) -> &'static [&'static str] {
+        &["flush"]
+    }
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[("", "Save changes done to all open files to disk.")]
+    }
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if !args.is_empty() {
+            expect(core, args.len() as u64, 0);
+            return;
+        }
+        if let Err(e) = core.io.flush() {
+            let err_str = format!("{e}");
+            error_msg(core, "Failed to flush files", &err_str);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_files {
     use super::*;
     use crate::{writer::Writer, CmdOps};
+    use std::fs;
+    use std::path::Path;
+    use test_file::{operate_on_dir, operate_on_file, DATA};
     #[test]
     fn test_docs() {
         let mut core = Core::new_no_colors();
@@ -189,6 +353,7 @@ mod test_files {
              Commands: [open | o]\n\
              Usage:\n\
              o <Perm> [URI] <Addr>\tOpen given URI using given optional permission (default to readonly) at given optional address.\n\
+             o --plugin [name] <Perm> [URI] <Addr>\tSame as above but URI is opened by IO plugin [name] even if it does not accept it.\n\
              Command: [close]\n\
              Usage:\n\
              close [hndl]\tClose file with given hndl.\n"
@@ -227,11 +392,11 @@ mod test_files {
         core.run("files", &[]);
         assert_eq!(
             core.stdout.utf8_string().unwrap(),
-            "Handle\tStart address\tsize\t\tPermissions\tURI\n\
-             0\t0x00000000\t0x0000002d\tREAD\t\tb64://../testing_binaries/rio/base64/no_padding.b64\n\
-             1\t0x0000002d\t0x00000050\tWRITE | READ\tmalloc://0x50\n\
-             2\t0x00005000\t0x00000005\tCOW\t\t../testing_binaries/rio/base64/one_pad.b64\n\
-             3\t0x0000a000\t0x0000002d\tREAD\t\tb64://../testing_binaries/rio/base64/no_padding.b64\n"
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             0\t0x00000000\t0x0000002d\tREAD\t\tBase64\t\tb64://../testing_binaries/rio/base64/no_padding.b64\n\
+             1\t0x0000002d\t0x00000050\tWRITE | READ\tMalloc\t\tmalloc://0x50\n\
+             2\t0x00005000\t0x00000005\tCOW\t\tFilePlugin\t../testing_binaries/rio/base64/one_pad.b64\n\
+             3\t0x0000a000\t0x0000002d\tREAD\t\tBase64\t\tb64://../testing_binaries/rio/base64/no_padding.b64\n"
         );
         assert_eq!(core.stderr.utf8_string().unwrap(), "");
         core.stderr = Writer::new_buf();
@@ -240,10 +405,81 @@ mod test_files {
         core.run("files", &[]);
         assert_eq!(
             core.stdout.utf8_string().unwrap(),
-            "Handle\tStart address\tsize\t\tPermissions\tURI\n\
-             0\t0x00000000\t0x0000002d\tREAD\t\tb64://../testing_binaries/rio/base64/no_padding.b64\n\
-             2\t0x00005000\t0x00000005\tCOW\t\t../testing_binaries/rio/base64/one_pad.b64\n\
-             3\t0x0000a000\t0x0000002d\tREAD\t\tb64://../testing_binaries/rio/base64/no_padding.b64\n"
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             0\t0x00000000\t0x0000002d\tREAD\t\tBase64\t\tb64://../testing_binaries/rio/base64/no_padding.b64\n\
+             2\t0x00005000\t0x00000005\tCOW\t\tFilePlugin\t../testing_binaries/rio/base64/one_pad.b64\n\
+             3\t0x0000a000\t0x0000002d\tREAD\t\tBase64\t\tb64://../testing_binaries/rio/base64/no_padding.b64\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    fn test_files_to_file_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_file(path).unwrap();
+        core.io
+            .open("malloc://0x50", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.run("files", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             0\t0x00000000\t0x00000050\tWRITE | READ\tMalloc\t\tmalloc://0x50\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_files_to_file() {
+        operate_on_file(&test_files_to_file_cb, DATA);
+    }
+
+    #[test]
+    fn test_files_json() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run("files", &[]);
+        core.io
+            .open("malloc://0x50", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.open("pattern://0x10?incr", IoMode::READ).unwrap();
+        core.run("files", &[]);
+        let env = core.env.clone();
+        env.write().set_bool("core.json", true, &mut core).unwrap();
+        core.run("files", &[]);
+        core.io.close_all();
+        core.run("files", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             0\t0x00000000\t0x00000050\tWRITE | READ\tMalloc\t\tmalloc://0x50\n\
+             1\t0x00000050\t0x00000010\tREAD\t\tPattern\t\tpattern://0x10?incr\n\
+             [{\"handle\":0,\"paddr\":0,\"size\":80,\"perm\":\"WRITE | READ\",\"plugin\":\"Malloc\",\"uri\":\"malloc://0x50\"},\
+             {\"handle\":1,\"paddr\":80,\"size\":16,\"perm\":\"READ\",\"plugin\":\"Pattern\",\"uri\":\"pattern://0x10?incr\"}]\n\
+             []\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_open_exec() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut open = OpenFile;
+        open.run(&mut core, &["rwx".to_owned(), "malloc://0x50".to_owned()]);
+        open.run(
+            &mut core,
+            &["RX".to_owned(), "pattern://0x10?incr".to_owned()],
+        );
+        core.run("files", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             0\t0x00000000\t0x00000050\tWRITE | READ | EXEC\tMalloc\t\tmalloc://0x50\n\
+             1\t0x00000050\t0x00000010\tREAD | EXEC\tPattern\t\tpattern://0x10?incr\n"
         );
         assert_eq!(core.stderr.utf8_string().unwrap(), "");
     }
@@ -318,4 +554,236 @@ mod test_files {
         // what in between is different between Windows and *Nix
         assert!(err.ends_with("Error: Failed to close file\nHandle Does not exist.\n"));
     }
+
+    fn test_reopen_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut reopen = ReopenFile;
+        let uri = path.to_str().unwrap();
+        core.io.open(uri, IoMode::READ).unwrap();
+        fs::write(path, [0xff; 0x10]).unwrap();
+        reopen.run(&mut core, &["0".to_owned()]);
+        let mut data = [0; 0x10];
+        core.io.pread(0, &mut data).unwrap();
+        assert_eq!(data, [0xff; 0x10]);
+        assert_eq!(core.io.hndl_to_desc(0).unwrap().size(), 0x10);
+        assert_eq!(core.io.hndl_to_desc(0).unwrap().perm(), IoMode::READ);
+        reopen.run(&mut core, &["rw".to_owned(), "0".to_owned()]);
+        assert_eq!(
+            core.io.hndl_to_desc(0).unwrap().perm(),
+            IoMode::READ | IoMode::WRITE
+        );
+        core.io.pwrite(0, &[0]).unwrap();
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+        assert_eq!(fs::read(path).unwrap()[..2], [0, 0xff]);
+    }
+
+    #[test]
+    fn test_reopen() {
+        operate_on_file(&test_reopen_cb, DATA);
+    }
+
+    fn test_open_elf_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut open = OpenFile;
+        open.run(
+            &mut core,
+            &[
+                format!("elf://{}", path.to_string_lossy()),
+                "0x1000".to_owned(),
+            ],
+        );
+        let mut data = [0; 8];
+        core.io.vread(0x40_0000, &mut data).unwrap();
+        assert_eq!(data, DATA[..8]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_open_elf() {
+        // ELF64 with one loadable segment of 8 bytes at offset 0x78 mapped at 0x400000.
+        let mut elf = vec![0; 0x80];
+        elf[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        elf[0x20] = 0x40; // e_phoff
+        elf[0x36] = 0x38; // e_phentsize
+        elf[0x38] = 1; // e_phnum
+        elf[0x40] = 1; // p_type = PT_LOAD
+        elf[0x48] = 0x78; // p_offset
+        elf[0x52] = 0x40; // p_vaddr
+        elf[0x60] = 8; // p_filesz
+        elf[0x78..].copy_from_slice(&DATA[..8]);
+        operate_on_file(&test_open_elf_cb, &elf);
+        operate_on_file(&test_open_plugin_cb, &elf);
+    }
+
+    fn test_open_plugin_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut open = OpenFile;
+        let plugin = "--plugin".to_owned();
+        open.run(
+            &mut core,
+            &[
+                plugin.clone(),
+                "elf".to_owned(),
+                path.to_string_lossy().to_string(),
+            ],
+        );
+        let mut data = [0; 8];
+        core.io.vread(0x40_0000, &mut data).unwrap();
+        assert_eq!(data, DATA[..8]);
+        open.run(
+            &mut core,
+            &[
+                plugin.clone(),
+                "Malloc".to_owned(),
+                "rw".to_owned(),
+                "malloc://0x10".to_owned(),
+                "0x1000".to_owned(),
+            ],
+        );
+        assert_eq!(core.io.hndl_to_desc(1).unwrap().paddr_base(), 0x1000);
+        open.run(
+            &mut core,
+            &[plugin, "nothing".to_owned(), "malloc://0x10".to_owned()],
+        );
+        assert_eq!(core.io.uri_iter().count(), 2);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to open file\n\
+             No loaded IO plugin is named nothing.\n"
+        );
+    }
+
+    fn test_flush_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut flush = FlushFiles;
+        flush.help(&mut core);
+        flush.run(&mut core, &["0".to_owned()]);
+        core.io
+            .open(path.to_str().unwrap(), IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0, &[0xff]).unwrap();
+        flush.run(&mut core, &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [flush]\n\
+             Usage:\n\
+             flush\tSave changes done to all open files to disk.\n"
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected 0 argument(s), found 1.\n"
+        );
+        assert_eq!(fs::read(path).unwrap()[..2], [0xff, DATA[1]]);
+    }
+
+    #[test]
+    fn test_flush() {
+        operate_on_file(&test_flush_cb, DATA);
+    }
+
+    #[test]
+    fn test_reopen_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut reopen = ReopenFile;
+        reopen.help(&mut core);
+        reopen.run(&mut core, &[]);
+        reopen.run(&mut core, &["x".to_owned()]);
+        reopen.run(&mut core, &["1".to_owned()]);
+        reopen.run(&mut core, &["rz".to_owned(), "1".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [reopen]\n\
+             Usage:\n\
+             reopen <Perm> [hndl]\tReopen file with given hndl using given optional permission (default to current one) to pick up changes done to it on disk.\n"
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected between 1 and 2 arguments, found 0.\n\
+             Error: Invalid hndl\n\
+             invalid digit found in string\n\
+             Error: Failed to reopen file\n\
+             Handle Does not exist.\n\
+             Error: Failed to parse permission\n\
+             Unknown Permission: `z`\n"
+        );
+    }
+
+    fn test_open_many_cb(dir: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut open = OpenMany;
+        let dir = dir.to_string_lossy();
+        open.run(&mut core, &["rw".to_owned(), format!("{dir}/*.bin")]);
+        let names: Vec<&str> = core.io.uri_iter().map(RIODesc::name).collect();
+        assert_eq!(names, [format!("{dir}/a.bin"), format!("{dir}/b.bin")]);
+        let mut data = [0; 4];
+        core.io.pread(0, &mut data).unwrap();
+        assert_eq!(data, [1, 1, 2, 2]);
+        assert_eq!(
+            core.io.hndl_to_desc(1).unwrap().perm(),
+            IoMode::READ | IoMode::WRITE
+        );
+        open.run(&mut core, &[format!("{dir}/*.txt")]);
+        open.run(&mut core, &[format!("malloc://{dir}/*.bin")]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            format!(
+                "Error: Failed to open files\n\
+                 No files match {dir}/*.txt\n\
+                 Error: Failed to open malloc://{dir}/a.bin\n\
+                 Memory based files must have write permission\n\
+                 Error: Failed to open malloc://{dir}/b.bin\n\
+                 Memory based files must have write permission\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_open_many() {
+        operate_on_dir(
+            &test_open_many_cb,
+            &[("b.bin", &[2; 2]), ("a.bin", &[1; 2])],
+        );
+    }
+
+    #[test]
+    fn test_open_many_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut open = OpenMany;
+        open.help(&mut core);
+        open.run(&mut core, &[]);
+        open.run(&mut core, &["z".to_owned(), "*".to_owned()]);
+        open.run(&mut core, &["[".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [openMany]\n\
+             Usage:\n\
+             openMany <Perm> [Pattern]\tOpen all files matching given glob pattern in sorted order right after each other, using given optional permission (default to readonly).\n"
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected between 1 and 2 arguments, found 0.\n\
+             Error: Failed to parse permission\n\
+             Unknown Permission: `z`\n\
+             Error: Failed to open files\n\
+             Invalid pattern [: Pattern syntax error near position 0: invalid range pattern.\n"
+        );
+    }
 }
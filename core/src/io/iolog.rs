@@ -0,0 +1,159 @@
+//! commands for tracing IO operations.
+
+use crate::helper::{error_msg, expect_range, str_to_num};
+use crate::{cmd::Cmd, core::Core};
+use std::io::Write;
+
+const DEFAULT_CAPACITY: u64 = 1024;
+
+#[derive(Default)]
+pub struct IoLog;
+
+impl IoLog {
+    fn print_log(core: &mut Core) {
+        if !core.io.is_io_log_enabled() {
+            error_msg(
+                core,
+                "IO log is disabled",
+                "Use `iolog enable` to start recording IO operations.",
+            );
+            return;
+        }
+        writeln!(core.stdout, "Operation\tAddress\t\tsize\t\tHandle\tResult").unwrap();
+        for record in core.io.io_log() {
+            let hndl = record
+                .hndl
+                .map_or_else(|| "-".to_owned(), |h| h.to_string());
+            let result = if record.ok { "Ok" } else { "Failed" };
+            writeln!(
+                core.stdout,
+                "{}\t\t0x{:08x}\t0x{:08x}\t{}\t{}",
+                record.op, record.addr, record.size, hndl, result
+            )
+            .unwrap();
+        }
+    }
+}
+
+impl Cmd for IoLog {
+    fn commands(&self) -> &'static [&'static str] {
+        &["iolog"]
+    }
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("", "\t\tPrint recorded IO operations."),
+            (
+                "enable <capacity>",
+                "Start recording at most <capacity> IO operations (default 1024).",
+            ),
+            ("disable", "\tStop recording IO operations."),
+        ]
+    }
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() > 2 {
+            expect_range(core, args.len() as u64, 0, 2);
+            return;
+        }
+        if args.is_empty() {
+            return Self::print_log(core);
+        }
+        match (&*args[0], args.len()) {
+            ("enable", _) => {
+                let capacity = match args.get(1).map(|c| str_to_num(c)) {
+                    None => DEFAULT_CAPACITY,
+                    Some(Ok(capacity)) => capacity,
+                    Some(Err(e)) => {
+                        let err_str = format!("{e}");
+                        error_msg(core, "Failed to parse capacity", &err_str);
+                        return;
+                    }
+                };
+                core.io.enable_io_log(capacity as usize);
+            }
+            ("disable", 1) => core.io.disable_io_log(),
+            _ => {
+                let msg = format!("Unknown arguments `{}`.", args.join(" "));
+                error_msg(core, "Failed to parse arguments", &msg);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_iolog {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use rair_io::IoMode;
+
+    #[test]
+    fn test_help() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let iolog = IoLog;
+        iolog.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [iolog]\n\
+             Usage:\n\
+             iolog\t\t\tPrint recorded IO operations.\n\
+             iolog enable <capacity>\tStart recording at most <capacity> IO operations (default 1024).\n\
+             iolog disable\t\tStop recording IO operations.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_iolog() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut iolog = IoLog;
+        iolog.run(&mut core, &["enable".to_owned()]);
+        core.io
+            .open("malloc://0x50", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x10, &[1, 2, 3]).unwrap();
+        let mut data = [0; 4];
+        core.io.pread(0x4e, &mut data).unwrap_err();
+        iolog.run(&mut core, &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Operation\tAddress\t\tsize\t\tHandle\tResult\n\
+             open\t\t0x00000000\t0x00000050\t0\tOk\n\
+             write\t\t0x00000010\t0x00000003\t0\tOk\n\
+             read\t\t0x0000004e\t0x00000004\t-\tFailed\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        iolog.run(&mut core, &["disable".to_owned()]);
+        iolog.run(&mut core, &[]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: IO log is disabled\n\
+             Use `iolog enable` to start recording IO operations.\n"
+        );
+    }
+
+    #[test]
+    fn test_iolog_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut iolog = IoLog;
+        iolog.run(&mut core, &["enable".to_owned(), "0x".to_owned()]);
+        iolog.run(&mut core, &["foo".to_owned()]);
+        iolog.run(&mut core, &["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to parse capacity\n\
+             cannot parse integer from empty string\n\
+             Error: Failed to parse arguments\n\
+             Unknown arguments `foo`.\n\
+             Arguments Error: Expected between 0 and 2 arguments, found 3.\n"
+        );
+    }
+}
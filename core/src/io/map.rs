@@ -2,7 +2,8 @@
 
 use crate::helper::{error_msg, expect, is_color, str_to_num};
 use crate::{cmd::Cmd, core::Core};
-use std::io::Write;
+use rair_io::RIOMap;
+use std::fs;
 use yansi::Paint;
 
 #[derive(Default)]
@@ -90,6 +91,70 @@ impl Cmd for UnMap {
     }
 }
 
+#[derive(Default)]
+pub struct MapLayout;
+
+impl MapLayout {
+    // Parse lines of `[phy] [vir] [size]` separated by white spaces, empty lines and lines
+    // starting with `#` are ignored.
+    fn parse_layout(layout: &str) -> Result<Vec<(u64, u64, u64)>, String> {
+        let mut entries = Vec::new();
+        for (i, line) in layout.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 3 {
+                return Err(format!(
+                    "Line {}: Expected 3 fields, found {}.",
+                    i + 1,
+                    fields.len()
+                ));
+            }
+            let mut nums = [0; 3];
+            for (num, field) in nums.iter_mut().zip(&fields) {
+                *num = str_to_num(field).map_err(|e| format!("Line {}: {e}.", i + 1))?;
+            }
+            if nums[2] == 0 {
+                return Err(format!("Line {}: Size can't be zero.", i + 1));
+            }
+            entries.push((nums[0], nums[1], nums[2]));
+        }
+        Ok(entries)
+    }
+}
+
+impl Cmd for MapLayout {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() != 1 {
+            expect(core, args.len() as u64, 1);
+            return;
+        }
+        let layout = match fs::read_to_string(&args[0]) {
+            Ok(layout) => layout,
+            Err(e) => return error_msg(core, "Failed to open file", &e.to_string()),
+        };
+        let entries = match Self::parse_layout(&layout) {
+            Ok(entries) => entries,
+            Err(e) => return error_msg(core, "Failed to parse layout", &e),
+        };
+        if let Err(e) = core.io.apply_map_layout(&entries) {
+            error_msg(core, "Failed to map memory", &e.to_string());
+        }
+    }
+    fn commands(&self) -> &'static [&'static str] {
+        &["mapLayout"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "[path]",
+            "Map all regions listed in [path] as [phy] [vir] [size] lines, either all or none get mapped.",
+        )]
+    }
+}
+
 #[derive(Default)]
 pub struct ListMap;
 
@@ -118,24 +183,22 @@ impl Cmd for ListMap {
         let env = core.env.read();
         let color = env.get_str("maps.headerColor").unwrap();
         let (r, g, b) = env.get_color(color).unwrap();
-        writeln!(
-            core.stdout,
+        drop(env);
+        let header = format!(
             "{: <20}{: <20}{}",
             "Virtual Address".rgb(r, g, b),
             "Physical Address".rgb(r, g, b),
             "Size".rgb(r, g, b)
-        )
-        .unwrap();
-        for map in core.io.map_iter() {
-            writeln!(
-                core.stdout,
+        );
+        let rows: Vec<RIOMap> = core.io.map_iter().map(|map| *map).collect();
+        core.emit_table(&header, &rows, |map| {
+            format!(
                 "{: <20}{: <20}0x{:x}",
                 format!("0x{:x}", map.vaddr),
                 format!("0x{:x}", map.paddr),
                 map.size
             )
-            .unwrap();
-        }
+        });
     }
     fn commands(&self) -> &'static [&'static str] {
         &["maps"]
@@ -148,10 +211,34 @@ impl Cmd for ListMap {
 mod test_mapping {
     use super::*;
     use crate::{writer::Writer, CmdOps};
+    use core::slice;
     use rair_io::*;
     use std::path::Path;
     use test_file::*;
     #[test]
+    fn test_maps_json() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.map(0x0, 0x500, 0x20).unwrap();
+        core.io.map(0x40, 0x1000, 0x10).unwrap();
+        core.run("maps", &[]);
+        let env = core.env.clone();
+        env.write().set_bool("core.json", true, &mut core).unwrap();
+        core.run("maps", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Virtual Address     Physical Address    Size\n\
+             0x500               0x0                 0x20\n\
+             0x1000              0x40                0x10\n\
+             [{\"paddr\":0,\"vaddr\":1280,\"size\":32},{\"paddr\":64,\"vaddr\":4096,\"size\":16}]\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    #[test]
     fn test_map_docs() {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
@@ -303,7 +390,7 @@ mod test_mapping {
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Error: Failed to map memory\nCannot resolve address.\n"
+            "Error: Failed to map memory\nCannot resolve address 0x0.\n"
         );
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
@@ -343,7 +430,69 @@ mod test_mapping {
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Error: Failed to unmap memory\nCannot resolve address.\n"
+            "Error: Failed to unmap memory\nCannot resolve address 0x500.\n"
+        );
+    }
+
+    #[test]
+    fn test_map_layout_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let map_layout = MapLayout;
+        map_layout.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [mapLayout]\n\
+             Usage:\n\
+             mapLayout [path]\tMap all regions listed in [path] as [phy] [vir] [size] lines, either all or none get mapped.\n"
         );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    fn test_map_layout_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut map_layout = MapLayout;
+        let path = path.to_str().unwrap().to_owned();
+        core.io
+            .open("malloc://0x300", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        fs::write(
+            &path,
+            "# phy\tvir\tsize\n0x0\t0x1000\t0x100\n\n0x100\t0x3000\t0x100\n",
+        )
+        .unwrap();
+        map_layout.run(&mut core, slice::from_ref(&path));
+        fs::write(&path, "0x200\t0x5000\t0x10\n0x200\t0x1000\t0x10\n").unwrap();
+        map_layout.run(&mut core, slice::from_ref(&path));
+        fs::write(&path, "0x200\t0x5000\n").unwrap();
+        map_layout.run(&mut core, slice::from_ref(&path));
+        fs::write(&path, "0x200 0x5000 0x10\n0x200 0x6000 x\n").unwrap();
+        map_layout.run(&mut core, slice::from_ref(&path));
+        fs::write(&path, "0x200 0x5000 0x10\n0x0 0x0 0\n").unwrap();
+        map_layout.run(&mut core, &[path]);
+        core.run("maps", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Virtual Address     Physical Address    Size\n\
+             0x1000              0x0                 0x100\n\
+             0x3000              0x100               0x100\n"
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to map memory\n\
+             Phyiscal addresses overlap.\n\
+             Error: Failed to parse layout\n\
+             Line 1: Expected 3 fields, found 2.\n\
+             Error: Failed to parse layout\n\
+             Line 2: invalid digit found in string.\n\
+             Error: Failed to parse layout\n\
+             Line 2: Size can't be zero.\n"
+        );
+    }
+    #[test]
+    fn test_map_layout() {
+        operate_on_file(&test_map_layout_cb, b"");
     }
 }
@@ -1,29 +1,58 @@
 //! commands handling IO.
 
+mod cksum;
+mod entropy;
 mod files;
+mod iolog;
 mod map;
+mod plugins;
 mod print;
+mod strings;
+mod watch;
 mod write;
 
-use self::files::{CloseFile, ListFiles, OpenFile};
-use self::map::{ListMap, Map, UnMap};
-use self::print::{PrintBase, PrintCSV, PrintHex, PrintSignedCSV};
-use self::write::{WriteHex, WriteToFile};
+use self::cksum::Cksum;
+pub use self::cksum::{hash_sparse, Crc32};
+use self::entropy::Entropy;
+use self::files::{CloseFile, FlushFiles, ListFiles, OpenFile, OpenMany, ReopenFile};
+use self::iolog::IoLog;
+use self::map::{ListMap, Map, MapLayout, UnMap};
+use self::plugins::ListPlugins;
+pub(crate) use self::print::encode_hex;
+use self::print::{BlockSize, PrintBase, PrintCSV, PrintHex, PrintSignedCSV};
+use self::strings::Strings;
+use self::watch::Watch;
+pub(crate) use self::watch::{report_watches, Watches};
+use self::write::{WriteFromFile, WriteHex, WriteToFile};
 use crate::core::Core;
 pub fn register_io(core: &mut Core) {
     let maps = ListMap::new(core);
     let files = ListFiles::new(core);
     let px = PrintHex::new(core);
+    let strings = Strings::new(core);
+    let ent = Entropy::new(core);
     core.add_command(Map);
     core.add_command(maps);
     core.add_command(px);
+    core.add_command(BlockSize);
     core.add_command(PrintBase);
     core.add_command(PrintCSV);
     core.add_command(PrintSignedCSV);
     core.add_command(UnMap);
+    core.add_command(MapLayout);
     core.add_command(files);
     core.add_command(OpenFile);
+    core.add_command(OpenMany);
     core.add_command(CloseFile);
+    core.add_command(ReopenFile);
+    core.add_command(FlushFiles);
+    core.add_command(ListPlugins);
     core.add_command(WriteHex);
     core.add_command(WriteToFile);
+    core.add_command(WriteFromFile);
+    core.add_command(Watch);
+    core.add_command(IoLog);
+    core.add_command(Cksum);
+    core.add_command(strings);
+    core.add_command(ent);
 }
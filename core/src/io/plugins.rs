@@ -0,0 +1,122 @@
+//! command for listing IO plugins.
+
+use crate::helper::expect;
+use crate::{cmd::Cmd, core::Core};
+use serde::Serialize;
+use yansi::Paint;
+
+#[derive(Serialize)]
+struct PluginRow {
+    name: &'static str,
+    version: &'static str,
+    author: &'static str,
+    license: &'static str,
+    desc: &'static str,
+}
+
+impl PluginRow {
+    fn text(&self) -> String {
+        // short names and authors need one more tab to keep next columns aligned.
+        let name_tab = if self.name.len() < 8 { "\t" } else { "" };
+        let author_tab = if self.author.len() < 8 { "\t" } else { "" };
+        format!(
+            "{}{name_tab}\t{}\t{}{author_tab}\t{}\t{}",
+            self.name, self.version, self.author, self.license, self.desc
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct ListPlugins;
+
+impl Cmd for ListPlugins {
+    fn commands(&self) -> &'static [&'static str] {
+        &["plugins", "Lp"]
+    }
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "",
+            "List loaded IO plugins in the order they are tried when opening URIs.",
+        )]
+    }
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if !args.is_empty() {
+            expect(core, args.len() as u64, 0);
+            return;
+        }
+        let env = core.env.read();
+        let color = env.get_str("maps.headerColor").unwrap();
+        let (r, g, b) = env.get_color(color).unwrap();
+        drop(env);
+        let header = format!(
+            "{}",
+            "Name\t\tVersion\tAuthor\t\tLicense\tDescription".rgb(r, g, b)
+        );
+        let rows: Vec<PluginRow> = core
+            .io
+            .plugins_iter()
+            .map(|plugin| PluginRow {
+                name: plugin.name,
+                version: plugin.version,
+                author: plugin.author,
+                license: plugin.license,
+                desc: plugin.desc,
+            })
+            .collect();
+        core.emit_table(&header, &rows, PluginRow::text);
+    }
+}
+
+#[cfg(test)]
+mod test_plugins {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+
+    #[test]
+    fn test_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        ListPlugins.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Commands: [plugins | Lp]\n\
+             Usage:\n\
+             Lp\tList loaded IO plugins in the order they are tried when opening URIs.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_plugins() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run("Lp", &[]);
+        let out = core.stdout.utf8_string().unwrap();
+        let mut lines = out.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "Name\t\tVersion\tAuthor\t\tLicense\tDescription"
+        );
+        assert!(lines.any(|line| line
+            == "FilePlugin\t0.0.1\tOddcoder\tLGPL\tThis IO plugin is used to open normal files."));
+        for name in ["IHex\t\t", "Base64\t\t", "Malloc\t\t"] {
+            assert!(out.lines().any(|line| line.starts_with(name)), "{name}");
+        }
+        core.stdout = Writer::new_buf();
+        let env = core.env.clone();
+        env.write().set_bool("core.json", true, &mut core).unwrap();
+        core.run("plugins", &[]);
+        core.run("plugins", &["ihex".to_owned()]);
+        let out = core.stdout.utf8_string().unwrap();
+        assert!(out.contains(
+            "{\"name\":\"Base64\",\"version\":\"0.0.1\",\"author\":\"Oddcoder\",\"license\":\"LGPL\",\
+             \"desc\":\"This plugin is used to open base64 encoded files.\"}"
+        ));
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected 0 argument(s), found 1.\n"
+        );
+    }
+}
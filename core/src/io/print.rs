@@ -1,20 +1,43 @@
 //! commands handling raw data printing.
 
 use crate::core::Core;
-use crate::helper::{error_msg, expect, str_to_num};
+use crate::helper::{error_msg, expect, expect_range, str_to_num};
 use crate::hex::HexWithoutEnv;
 use crate::writer::Writer;
 use crate::Cmd;
 use core::{cmp, fmt::Write as _};
+use rair_env::Environment;
 use std::io::Write;
 use yansi::Paint;
 
+// Largest value accepted for `px.blockSize`.
+const MAX_BLOCK_SIZE: u64 = 0x100_0000;
+
+fn is_block_size(_: &str, size: u64, _: &Environment<Core>, _: &mut Core) -> bool {
+    size != 0 && size <= MAX_BLOCK_SIZE
+}
+
+/// Size used by commands whose size argument was left out.
+pub(super) fn block_size(core: &Core) -> u64 {
+    core.env.read().get_u64("px.blockSize").unwrap()
+}
+
 pub struct PrintHex {
     inner: HexWithoutEnv,
 }
 
 impl PrintHex {
     pub fn new(core: &mut Core) -> Self {
+        let env = core.env.clone();
+        env.write()
+            .add_u64_with_cb(
+                "px.blockSize",
+                0x100,
+                "Number of bytes used by `px`, `pb`, `strings`, `ent` and `cksum sparse` when no size is given",
+                core,
+                is_block_size,
+            )
+            .unwrap();
         Self {
             inner: HexWithoutEnv::new(core),
         }
@@ -25,13 +48,14 @@ impl Cmd for PrintHex {
     fn run(&mut self, core: &mut Core, args: &[String]) {
         // we can always optimize by try and using pread an vread.
         // If they fail, only then we might want to attempt the sparce version.
-        if args.len() != 1 {
-            expect(core, args.len() as u64, 1);
+        if args.len() > 1 {
+            expect_range(core, args.len() as u64, 0, 1);
             return;
         }
-        let size = match str_to_num(&args[0]) {
-            Ok(s) => s,
-            Err(e) => {
+        let size = match args.first().map(|arg| str_to_num(arg)) {
+            None => block_size(core),
+            Some(Ok(s)) => s,
+            Some(Err(e)) => {
                 return error_msg(
                     core,
                     &e.to_string(),
@@ -50,24 +74,45 @@ impl Cmd for PrintHex {
             Ok(d) => d,
             Err(e) => return error_msg(core, "Read Failed", &e.to_string()),
         };
+        let original = match core.read_original_sparce(loc, size) {
+            Ok(d) => d,
+            Err(e) => return error_msg(core, "Read Failed", &e.to_string()),
+        };
+        let color = core.env.read().get_bool("color.enable").unwrap();
         let env = self.inner.get_env(core);
         env.print_banner(&mut core.stdout);
         for i in (0..size).step_by(16) {
             env.print_addr(&mut core.stdout, loc + i);
             let mut ascii = Writer::new_buf();
             let mut hex = Writer::new_buf();
-            for j in i..cmp::min(i + 16, size) {
+            // columns of bytes that differ from the original ones.
+            let mut changed = String::new();
+            let end = cmp::min(i + 16, size);
+            for j in i..end {
                 let byte = data.get(&(j + loc)).copied();
-                env.print_hex(byte, &mut hex, j % 2 != 0);
-                env.print_ascii(byte, &mut ascii);
+                let highlight = byte.is_some() && byte != original.get(&(j + loc)).copied();
+                env.print_hex_with_highlight(byte, &mut hex, j % 2 != 0, highlight);
+                env.print_ascii_with_highlight(byte, &mut ascii, highlight);
+                if highlight {
+                    write!(changed, " {:X}", j % 16).unwrap();
+                }
             }
-            writeln!(
+            // highlighting adds escape codes so hex can't be padded with the formatter.
+            let pad = 40 - (end - i) * 2 - (end - i) / 2;
+            write!(
                 core.stdout,
-                "{: <40} {}",
+                "{}{:pad$} {}",
                 hex.utf8_string().unwrap(),
-                ascii.utf8_string().unwrap()
+                "",
+                ascii.utf8_string().unwrap(),
+                pad = pad as usize
             )
             .unwrap();
+            // without colors changed bytes are listed by their column after the ascii part.
+            if !color && !changed.is_empty() {
+                write!(core.stdout, "  *{changed}").unwrap();
+            }
+            writeln!(core.stdout).unwrap();
         }
     }
 
@@ -76,7 +121,49 @@ impl Cmd for PrintHex {
     }
 
     fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
-        &[("[size]", "View data at current location in hex format.")]
+        &[
+            (
+                "",
+                "\tView `px.blockSize` bytes at current location in hex format.",
+            ),
+            ("[size]", "View data at current location in hex format."),
+        ]
+    }
+}
+
+#[derive(Default)]
+pub struct BlockSize;
+
+impl Cmd for BlockSize {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() > 1 {
+            expect_range(core, args.len() as u64, 0, 1);
+            return;
+        }
+        let Some(arg) = args.first() else {
+            let size = core.env.read().get_u64("px.blockSize").unwrap();
+            writeln!(core.stdout, "0x{size:x}").unwrap();
+            return;
+        };
+        let size = match str_to_num(arg) {
+            Ok(size) => size,
+            Err(e) => return error_msg(core, "Failed to parse size", &e.to_string()),
+        };
+        let env = core.env.clone();
+        if env.write().set_u64("px.blockSize", size, core).is_err() {
+            let msg = format!("Block size must be between 0x1 and 0x{MAX_BLOCK_SIZE:x}.");
+            error_msg(core, "Failed to set block size", &msg);
+        }
+    }
+    fn commands(&self) -> &'static [&'static str] {
+        &["blockSize", "b"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("", "\tShow `px.blockSize`."),
+            ("[size]", "Set `px.blockSize` to size."),
+        ]
     }
 }
 
@@ -90,7 +177,7 @@ fn encode_bin(data: &[u8]) -> String {
     }
     out
 }
-fn encode_hex(data: &[u8]) -> String {
+pub(crate) fn encode_hex(data: &[u8]) -> String {
     let mut out = String::with_capacity(data.len() * 2);
     for byte in data {
         write!(out, "{byte:02x}").unwrap();
@@ -99,13 +186,14 @@ fn encode_hex(data: &[u8]) -> String {
 }
 impl Cmd for PrintBase {
     fn run(&mut self, core: &mut Core, args: &[String]) {
-        if args.len() != 2 {
-            expect(core, args.len() as u64, 2);
+        if args.is_empty() || args.len() > 2 {
+            expect_range(core, args.len() as u64, 1, 2);
             return;
         }
-        let size = match str_to_num(&args[1]) {
-            Ok(size) => size as usize,
-            Err(e) => {
+        let size = match args.get(1).map(|arg| str_to_num(arg)) {
+            None => block_size(core) as usize,
+            Some(Ok(size)) => size as usize,
+            Some(Err(e)) => {
                 let err_str = format!("{e}");
                 error_msg(core, "Failed to parse size", &err_str);
                 return;
@@ -132,10 +220,16 @@ impl Cmd for PrintBase {
     }
 
     fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
-        &[(
-            "[base] [size]",
-            "Print data stream at current location in [base] format.  Supported bases: 2, 16.",
-        )]
+        &[
+            (
+                "[base]",
+                "\tPrint `px.blockSize` bytes at current location in [base] format.",
+            ),
+            (
+                "[base] [size]",
+                "Print data stream at current location in [base] format.  Supported bases: 2, 16.",
+            ),
+        ]
     }
 }
 
@@ -527,9 +621,11 @@ mod test_print_hex {
             core.stdout.utf8_string().unwrap(),
             "Commands: [printHex | px]\n\
              Usage:\n\
+             px\t\tView `px.blockSize` bytes at current location in hex format.\n\
              px [size]\tView data at current location in hex format.\n\
              Commands: [printBase | pb]\n\
              Usage:\n\
+             pb [base]\t\tPrint `px.blockSize` bytes at current location in [base] format.\n\
              pb [base] [size]\tPrint data stream at current location in [base] format.  Supported bases: 2, 16.\n\
              Commands: [printCSV | pcsv]\n\
              Usage:\n\
@@ -963,16 +1059,151 @@ mod test_print_hex {
         operate_on_file(&test_px_vir_cb, DATA);
     }
 
+    fn test_px_changes_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io.open(&path.to_string_lossy(), IoMode::COW).unwrap();
+        core.io.pwrite(0x11, &[0xff, DATA[0x12], 0xff]).unwrap();
+        core.io.pwrite(0x2f, &[0x41]).unwrap();
+        core.set_loc(0x10);
+        core.run("px", &["0x28".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "- offset -  0 1  2 3  4 5  6 7  8 9  A B  C D  E F  0123456789ABCDEF\n\
+             0x00000010 dbff 18ff 6dc2 2ff1 2011 3142 73b5 28dd  ....m./...1Bs.(.  * 1 3\n\
+             0x00000020 05e2 e7c9 b079 29a2 cb6d 38a5 dd82 5f41  .....y)..m8..._A  * F\n\
+             0x00000030 4021 6182 e365 48ad                      @!a..eH.\n"
+        );
+        // the same bytes seen through virtual addresses.
+        core.stdout = Writer::new_buf();
+        core.mode = AddrMode::Vir;
+        core.io.map(0x10, 0x1000, 0x8).unwrap();
+        core.set_loc(0x1000);
+        core.run("px", &["0xa".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "- offset -  0 1  2 3  4 5  6 7  8 9  A B  C D  E F  0123456789ABCDEF\n\
+             0x00001000 dbff 18ff 6dc2 2ff1 ####                 ....m./.##  * 1 3\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    #[test]
+    fn test_px_changes() {
+        operate_on_file(&test_px_changes_cb, DATA);
+    }
+
     #[test]
-    fn test_px_err() {
+    fn test_px_block_size() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x40", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x10, b"Hello\x00World!\xff").unwrap();
+        let env = core.env.clone();
+        env.write()
+            .set_u64("px.blockSize", 0x20, &mut core)
+            .unwrap();
+        core.run("px", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "- offset -  0 1  2 3  4 5  6 7  8 9  A B  C D  E F  0123456789ABCDEF\n\
+             0x00000000 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+             0x00000010 4865 6c6c 6f00 576f 726c 6421 ff00 0000  Hello.World!....\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_pb_block_size() {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x40", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0, b"\x12\x34\xab").unwrap();
+        let env = core.env.clone();
+        env.write().set_u64("px.blockSize", 4, &mut core).unwrap();
+        core.run("pb", &["16".to_owned()]);
+        core.run("pb", &["2".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "1234ab00\n00010010001101001010101100000000\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_block_size() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.run("b", &[]);
+        core.run("b", &["0x30".to_owned()]);
+        core.run("b", &[]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "0x100\n0x30\n");
+        core.stdout = Writer::new_buf();
         core.run("px", &[]);
+        let dump = core.stdout.utf8_string().unwrap();
+        // banner followed by 16 bytes per line.
+        assert_eq!(dump.lines().count(), 4);
+        assert!(dump.lines().last().unwrap().starts_with("0x00000020 "));
+        core.stdout = Writer::new_buf();
+        core.run("b", &["0".to_owned()]);
+        core.run("b", &["0x1000001".to_owned()]);
+        core.run("b", &["x".to_owned()]);
+        core.run("b", &["1".to_owned(), "2".to_owned()]);
+        core.run("b", &[]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "0x30\n");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to set block size\nBlock size must be between 0x1 and 0x1000000.\n\
+             Error: Failed to set block size\nBlock size must be between 0x1 and 0x1000000.\n\
+             Error: Failed to parse size\ninvalid digit found in string\n\
+             Arguments Error: Expected between 0 and 1 arguments, found 2.\n"
+        );
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let env = core.env.clone();
+        assert!(env.write().set_u64("px.blockSize", 0, &mut core).is_err());
+        env.write()
+            .set_u64("px.blockSize", 0x1000000, &mut core)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_block_size_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        BlockSize.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Commands: [blockSize | b]\n\
+             Usage:\n\
+             b\t\tShow `px.blockSize`.\n\
+             b [size]\tSet `px.blockSize` to size.\n\
+             "
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_px_err() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run("px", &["0x10".to_owned(), "0x10".to_owned()]);
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Arguments Error: Expected 1 argument(s), found 0.\n"
+            "Arguments Error: Expected between 0 and 1 arguments, found 2.\n"
         );
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
@@ -1064,11 +1295,11 @@ mod test_print_hex {
                 IoMode::READ,
             )
             .unwrap();
-        pb.run(&mut core, &["16".to_owned()]);
+        pb.run(&mut core, &[]);
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Arguments Error: Expected 2 argument(s), found 1.\n"
+            "Arguments Error: Expected between 1 and 2 arguments, found 0.\n"
         );
 
         core.stderr = Writer::new_buf();
@@ -1084,10 +1315,12 @@ mod test_print_hex {
         core.stdout = Writer::new_buf();
         pb.run(&mut core, &["16".to_owned(), "0x5000".to_owned()]);
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
-        assert_eq!(
-            core.stderr.utf8_string().unwrap(),
-            "Error: Read Failed\nCannot resolve address.\n"
-        );
+        // first unresolved address depends on the size of the testing binary.
+        assert!(core
+            .stderr
+            .utf8_string()
+            .unwrap()
+            .starts_with("Error: Read Failed\nCannot resolve address 0x"));
 
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
@@ -1392,10 +1625,12 @@ mod test_print_hex {
         core.stdout = Writer::new_buf();
         pcsv.run(&mut core, &["512".to_owned(), "500000".to_owned()]);
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
-        assert_eq!(
-            core.stderr.utf8_string().unwrap(),
-            "Error: Read Failed\nCannot resolve address.\n"
-        );
+        // first unresolved address depends on the size of the testing binary.
+        assert!(core
+            .stderr
+            .utf8_string()
+            .unwrap()
+            .starts_with("Error: Read Failed\nCannot resolve address 0x"));
     }
 
     #[test]
@@ -1448,10 +1683,12 @@ mod test_print_hex {
         core.stdout = Writer::new_buf();
         pscsv.run(&mut core, &["128".to_owned(), "500000".to_owned()]);
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
-        assert_eq!(
-            core.stderr.utf8_string().unwrap(),
-            "Error: Read Failed\nCannot resolve address.\n"
-        );
+        // first unresolved address depends on the size of the testing binary.
+        assert!(core
+            .stderr
+            .utf8_string()
+            .unwrap()
+            .starts_with("Error: Read Failed\nCannot resolve address 0x"));
     }
 
     #[test]
@@ -0,0 +1,382 @@
+//! command for extracting printable strings.
+
+use super::print::block_size;
+use crate::core::Core;
+use crate::helper::{error_msg, expect_range, str_to_num};
+use crate::Cmd;
+use core::{char, fmt, str};
+use rair_env::Environment;
+use std::io::Write;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Encoding {
+    Ascii,
+    Utf8,
+    Utf16Le,
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encoding::Ascii => write!(f, "ascii"),
+            Encoding::Utf8 => write!(f, "utf8"),
+            Encoding::Utf16Le => write!(f, "utf16le"),
+        }
+    }
+}
+
+fn is_printable_ascii(c: u8) -> bool {
+    c.is_ascii_graphic() || c == b' '
+}
+
+// Scan printable ASCII at the start of `data`, returns the string and its size in bytes.
+fn scan_ascii(data: &[u8]) -> (String, usize) {
+    let len = data.iter().take_while(|c| is_printable_ascii(**c)).count();
+    (String::from_utf8_lossy(&data[..len]).into_owned(), len)
+}
+
+// Scan printable UTF-8 at the start of `data`, returns the string, its size in bytes and
+// whether it has any multi-byte character.
+fn scan_utf8(data: &[u8]) -> (String, usize, bool) {
+    let mut i = 0;
+    let mut multibyte = false;
+    while i < data.len() {
+        let width = match data[i] {
+            c if is_printable_ascii(c) => 1,
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => break,
+        };
+        let Some(bytes) = data.get(i..i + width) else {
+            break;
+        };
+        let Ok(s) = str::from_utf8(bytes) else {
+            break;
+        };
+        if s.chars().any(char::is_control) {
+            break;
+        }
+        multibyte |= width > 1;
+        i += width;
+    }
+    (str::from_utf8(&data[..i]).unwrap().to_owned(), i, multibyte)
+}
+
+// Scan printable UTF-16LE at the start of `data`, returns the string and its size in bytes.
+// Basic latin characters must be printable ASCII followed by 0, other characters must not
+// have a printable ASCII high byte so that plain ASCII text or UTF-16 text read at the wrong
+// alignment is not mistaken for UTF-16.
+fn scan_utf16le(data: &[u8]) -> (String, usize) {
+    let units = data
+        .chunks_exact(2)
+        .map(|pair| u16::from(pair[0]) | (u16::from(pair[1]) << 8u8));
+    let mut s = String::new();
+    let mut len = 0;
+    for c in char::decode_utf16(units) {
+        let Ok(c) = c else {
+            break;
+        };
+        let width = c.len_utf16() * 2;
+        let (lo, hi) = (data[len], data[len + 1]);
+        let valid = if hi == 0 {
+            is_printable_ascii(lo)
+        } else {
+            !c.is_control() && !is_printable_ascii(hi)
+        };
+        if !valid {
+            break;
+        }
+        s.push(c);
+        len += width;
+    }
+    (s, len)
+}
+
+fn is_min_length(_: &str, len: u64, _: &Environment<Core>, _: &mut Core) -> bool {
+    len != 0
+}
+
+// Find all strings of at least `min_len` characters in `data`, empty strings are never found
+// so that scanning always moves forward.
+fn find_strings(
+    data: &[u8],
+    min_len: usize,
+    u8: bool,
+    u16: bool,
+) -> Vec<(usize, Encoding, String)> {
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < data.len() {
+        if u8 {
+            let (s, len, multibyte) = scan_utf8(&data[i..]);
+            if multibyte && len > 0 && s.chars().count() >= min_len {
+                found.push((i, Encoding::Utf8, s));
+                i += len;
+                continue;
+            }
+        }
+        if u16 {
+            let (s, len) = scan_utf16le(&data[i..]);
+            if len > 0 && s.chars().count() >= min_len {
+                found.push((i, Encoding::Utf16Le, s));
+                i += len;
+                continue;
+            }
+        }
+        let (s, len) = scan_ascii(&data[i..]);
+        if len > 0 && len >= min_len {
+            found.push((i, Encoding::Ascii, s));
+            i += len;
+            continue;
+        }
+        i += len.max(1);
+    }
+    found
+}
+
+pub struct Strings;
+
+impl Strings {
+    pub fn new(core: &mut Core) -> Self {
+        let env = core.env.clone();
+        env.write()
+            .add_u64_with_cb(
+                "strings.minLength",
+                4,
+                "Minimum number of characters in strings found by `strings` command",
+                core,
+                is_min_length,
+            )
+            .unwrap();
+        Self
+    }
+}
+
+impl Cmd for Strings {
+    fn commands(&self) -> &'static [&'static str] {
+        &["strings"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            (
+                "[-u8] [-u16]",
+                "\tSame as below with `px.blockSize` as [size].",
+            ),
+            (
+                "[-u8] [-u16] [size]",
+                "Print printable ASCII strings within [size] bytes at current location, -u8 and -u16 also look for UTF-8 and UTF-16LE strings.",
+            ),
+        ]
+    }
+
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() > 3 {
+            expect_range(core, args.len() as u64, 0, 3);
+            return;
+        }
+        // the size is the only argument that is not a flag and it always comes last.
+        let (flags, size) = match args.split_last() {
+            Some((last, flags)) if !last.starts_with('-') => (flags, Some(last)),
+            _ => (args, None),
+        };
+        let (mut u8, mut u16) = (false, false);
+        for flag in flags {
+            match &**flag {
+                "-u8" => u8 = true,
+                "-u16" => u16 = true,
+                _ => {
+                    let msg = format!("Unknown flag `{flag}`.");
+                    return error_msg(core, "Failed to parse arguments", &msg);
+                }
+            }
+        }
+        let size = match size.map(|arg| str_to_num(arg)) {
+            None => block_size(core),
+            Some(Ok(size)) => size,
+            Some(Err(e)) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Failed to parse size", &err_str);
+                return;
+            }
+        };
+        if size == 0 {
+            return;
+        }
+        let min_len = core.env.read().get_u64("strings.minLength").unwrap() as usize;
+        let loc = core.get_loc();
+        if loc.checked_add(size).is_none() {
+            return error_msg(core, "Read Failed", "Address overflow.");
+        }
+        let data = match core.read_sparce(loc, size) {
+            Ok(data) => data,
+            Err(e) => return error_msg(core, "Read Failed", &e.to_string()),
+        };
+        // strings never span gaps so each contiguous chunk is scanned on its own.
+        let mut chunks: Vec<(u64, Vec<u8>)> = Vec::new();
+        for (addr, byte) in data {
+            match chunks.last_mut() {
+                Some((start, bytes)) if *start + bytes.len() as u64 == addr => bytes.push(byte),
+                _ => chunks.push((addr, vec![byte])),
+            }
+        }
+        for (start, bytes) in chunks {
+            for (offset, encoding, s) in find_strings(&bytes, min_len, u8, u16) {
+                let addr = start + offset as u64;
+                writeln!(core.stdout, "0x{addr:08x} {encoding}\t{s}").unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_strings {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use rair_io::IoMode;
+
+    #[test]
+    fn test_help() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let strings = Strings;
+        strings.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [strings]\n\
+             Usage:\n\
+             strings [-u8] [-u16]\t\tSame as below with `px.blockSize` as [size].\n\
+             strings [-u8] [-u16] [size]\tPrint printable ASCII strings within [size] bytes at current location, -u8 and -u16 also look for UTF-8 and UTF-16LE strings.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    fn seed(core: &mut Core) {
+        core.io
+            .open("malloc://0x100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x10, b"Hello World\0").unwrap();
+        let utf16: Vec<u8> = "Wide \u{3b1}\u{3b2}"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        core.io.pwrite(0x30, &utf16).unwrap();
+        core.io
+            .pwrite(0x60, "caf\u{e9} \u{fc}ber".as_bytes())
+            .unwrap();
+        core.io.pwrite(0x80, b"abc\0").unwrap();
+    }
+
+    #[test]
+    fn test_strings() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        seed(&mut core);
+        core.run("strings", &["0x100".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00000010 ascii\tHello World\n"
+        );
+        core.stdout = Writer::new_buf();
+        core.run(
+            "strings",
+            &["-u16".to_owned(), "-u8".to_owned(), "0x100".to_owned()],
+        );
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00000010 ascii\tHello World\n\
+             0x00000030 utf16le\tWide \u{3b1}\u{3b2}\n\
+             0x00000060 utf8\tcaf\u{e9} \u{fc}ber\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_strings_block_size() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        seed(&mut core);
+        let env = core.env.clone();
+        env.write()
+            .set_u64("px.blockSize", 0x40, &mut core)
+            .unwrap();
+        core.run("strings", &[]);
+        core.run("strings", &["-u16".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00000010 ascii\tHello World\n\
+             0x00000010 ascii\tHello World\n\
+             0x00000030 utf16le\tWide \u{3b1}\u{3b2}\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_short_min_length() {
+        let data = b"a\0bc\0\0";
+        let expected = vec![
+            (0, Encoding::Ascii, "a".to_owned()),
+            (2, Encoding::Ascii, "bc".to_owned()),
+        ];
+        assert_eq!(find_strings(data, 0, false, false), expected);
+        assert_eq!(find_strings(data, 1, false, false), expected);
+        assert_eq!(find_strings(b"\0\0\0\0", 0, true, true), vec![]);
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        seed(&mut core);
+        let env = core.env.clone();
+        assert!(env
+            .write()
+            .set_u64("strings.minLength", 0, &mut core)
+            .is_err());
+        env.write()
+            .set_u64("strings.minLength", 1, &mut core)
+            .unwrap();
+        core.run_at("strings", &["0x8".to_owned()], 0x80);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "0x00000080 ascii\tabc\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_strings_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run(
+            "strings",
+            &[
+                "1".to_owned(),
+                "2".to_owned(),
+                "3".to_owned(),
+                "4".to_owned(),
+            ],
+        );
+        core.run("strings", &["-u32".to_owned(), "0x10".to_owned()]);
+        core.run("strings", &["-u32".to_owned()]);
+        core.run("strings", &["x".to_owned()]);
+        core.run("strings", &["-u8".to_owned(), "0".to_owned()]);
+        core.set_loc(u64::MAX - 0x10);
+        core.run("strings", &["0x20".to_owned()]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected between 0 and 3 arguments, found 4.\n\
+             Error: Failed to parse arguments\n\
+             Unknown flag `-u32`.\n\
+             Error: Failed to parse arguments\n\
+             Unknown flag `-u32`.\n\
+             Error: Failed to parse size\n\
+             invalid digit found in string\n\
+             Error: Read Failed\n\
+             Address overflow.\n"
+        );
+    }
+}
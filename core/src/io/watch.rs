@@ -0,0 +1,369 @@
+//! commands for watching address ranges for writes.
+
+use crate::core::Core;
+use crate::helper::{error_msg, expect_range, str_to_num, AddrMode};
+use crate::Cmd;
+use alloc::sync::Arc;
+use core::mem;
+use parking_lot::Mutex;
+use rair_io::HookOp;
+use serde::Serialize;
+use std::io::Write;
+use yansi::Paint;
+
+#[derive(Serialize)]
+struct WatchRow {
+    addr: u64,
+    size: u64,
+    mode: String,
+}
+
+#[derive(Clone)]
+struct Watchpoint {
+    mode: AddrMode,
+    addr: u64,
+    // physical ranges backing the watched range, reported writes are compared against them.
+    ranges: Vec<(u64, u64)>,
+    bytes: Vec<u8>,
+}
+
+/// Address ranges watched for writes and the writes not yet checked against them.
+#[derive(Default)]
+pub(crate) struct Watches {
+    list: Vec<Watchpoint>,
+    hits: Arc<Mutex<Vec<(HookOp, u64, u64)>>>,
+}
+
+impl Watches {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    hex.join(" ")
+}
+
+/// Print a notice for every watch touched by the writes done since last call.
+pub(crate) fn report_watches(core: &mut Core) {
+    let hits = mem::take(&mut *core.watches.hits.lock());
+    if hits.is_empty() {
+        return;
+    }
+    // virtual writes are reported per map, so each of them is backed by one physical range.
+    let hits: Vec<(u64, u64)> = hits
+        .into_iter()
+        .filter_map(|(op, addr, size)| match op {
+            HookOp::VirWrite => core
+                .io
+                .vir_to_phy(addr, size)?
+                .first()
+                .map(|map| (map.paddr, size)),
+            HookOp::PhyWrite => Some((addr, size)),
+            HookOp::PhyRead | HookOp::VirRead => None,
+        })
+        .collect();
+    for i in 0..core.watches.list.len() {
+        let watch = core.watches.list[i].clone();
+        let touched = watch.ranges.iter().any(|&(paddr, size)| {
+            hits.iter()
+                .any(|&(hit, hit_size)| hit < paddr + size && paddr < hit + hit_size)
+        });
+        if !touched {
+            continue;
+        }
+        let mut bytes = vec![0; watch.bytes.len()];
+        let old_mode = mem::replace(&mut core.mode, watch.mode);
+        let read = core.read(watch.addr, &mut bytes);
+        core.mode = old_mode;
+        if let Err(e) = read {
+            error_msg(core, "Failed to read watched bytes", &e.to_string());
+            continue;
+        }
+        writeln!(
+            core.stdout,
+            "Watch 0x{:08x} (0x{:x} bytes) written: {} -> {}",
+            watch.addr,
+            bytes.len(),
+            hex_bytes(&watch.bytes),
+            hex_bytes(&bytes)
+        )
+        .unwrap();
+        core.watches.list[i].bytes = bytes;
+    }
+}
+
+#[derive(Default)]
+pub struct Watch;
+
+impl Watch {
+    fn add(core: &mut Core, size: u64) -> Result<(), String> {
+        // the hook is installed with the first watch, don't replace a hook set by someone else.
+        if core.watches.is_empty() && core.io.has_write_hook() {
+            return Err("Another write hook is already set.".to_owned());
+        }
+        let addr = core.get_loc();
+        if addr.checked_add(size).is_none() {
+            return Err("Address overflow.".to_owned());
+        }
+        let ranges = match core.mode {
+            AddrMode::Phy => vec![(addr, size)],
+            AddrMode::Vir => core
+                .io
+                .vir_to_phy(addr, size)
+                .ok_or_else(|| {
+                    format!("Range 0x{addr:x}-0x{:x} is not fully mapped.", addr + size)
+                })?
+                .iter()
+                .map(|map| (map.paddr, map.size))
+                .collect(),
+        };
+        let len = usize::try_from(size).map_err(|e| e.to_string())?;
+        let mut bytes = vec![0; len];
+        core.read(addr, &mut bytes).map_err(|e| e.to_string())?;
+        if core.watches.is_empty() {
+            let hits = core.watches.hits.clone();
+            core.io.set_write_hook(Box::new(move |op, addr, size| {
+                hits.lock().push((op, addr, size));
+            }));
+        }
+        core.watches.list.push(Watchpoint {
+            mode: core.mode,
+            addr,
+            ranges,
+            bytes,
+        });
+        Ok(())
+    }
+
+    fn clear(core: &mut Core) {
+        // the write hook is ours only while there are watches.
+        if !core.watches.is_empty() {
+            core.io.clear_write_hook();
+        }
+        core.watches = Watches::default();
+    }
+
+    fn list(core: &mut Core) {
+        let env = core.env.read();
+        let color = env.get_str("maps.headerColor").unwrap();
+        let (r, g, b) = env.get_color(color).unwrap();
+        drop(env);
+        let header = format!("{}", "Address\t\tSize\tMode".rgb(r, g, b));
+        let rows: Vec<WatchRow> = core
+            .watches
+            .list
+            .iter()
+            .map(|watch| WatchRow {
+                addr: watch.addr,
+                size: watch.bytes.len() as u64,
+                mode: watch.mode.to_string(),
+            })
+            .collect();
+        core.emit_table(&header, &rows, |watch| {
+            format!("0x{:08x}\t0x{:x}\t{}", watch.addr, watch.size, watch.mode)
+        });
+    }
+}
+
+impl Cmd for Watch {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() > 1 {
+            expect_range(core, args.len() as u64, 0, 1);
+            return;
+        }
+        let Some(arg) = args.first() else {
+            return Self::list(core);
+        };
+        if arg == "-" {
+            return Self::clear(core);
+        }
+        let size = match str_to_num(arg) {
+            Ok(0) => return error_msg(core, "Failed to set watch", "Size can't be zero."),
+            Ok(size) => size,
+            Err(e) => return error_msg(core, "Failed to parse size", &e.to_string()),
+        };
+        if let Err(e) = Self::add(core, size) {
+            error_msg(core, "Failed to set watch", &e);
+        }
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["watch"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("", "\tList watched address ranges."),
+            (
+                "[size]",
+                "Report writes touching [size] bytes at current location.",
+            ),
+            ("-", "\tRemove all watches."),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test_watch {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use rair_io::IoMode;
+    use std::path::Path;
+    use test_file::{operate_on_file, DATA};
+
+    #[test]
+    fn test_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        Watch.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Command: [watch]\n\
+             Usage:\n\
+             watch\t\tList watched address ranges.\n\
+             watch [size]\tReport writes touching [size] bytes at current location.\n\
+             watch -\t\tRemove all watches.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    fn test_watch_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open(&path.to_string_lossy(), IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.run_at("watch", &["4".to_owned()], 0x10);
+        core.run("watch", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Address\t\tSize\tMode\n0x00000010\t0x4\tPhy\n"
+        );
+        core.stdout = Writer::new_buf();
+        // writes outside the watched range are silent.
+        core.run_at("wx", &["ffff".to_owned()], 0xe);
+        core.run_at("wx", &["ffff".to_owned()], 0x14);
+        core.run_at("wx", &["aabbcc".to_owned()], 0xf);
+        core.run_at("wx", &["dd".to_owned()], 0x13);
+        let expected = format!(
+            "Watch 0x00000010 (0x4 bytes) written: {:02x} {:02x} {:02x} {:02x} -> bb cc {:02x} {:02x}\n\
+             Watch 0x00000010 (0x4 bytes) written: bb cc {:02x} {:02x} -> bb cc {:02x} dd\n",
+            DATA[0x10], DATA[0x11], DATA[0x12], DATA[0x13], DATA[0x12], DATA[0x13], DATA[0x12],
+            DATA[0x13], DATA[0x12]
+        );
+        assert_eq!(core.stdout.utf8_string().unwrap(), expected);
+        core.stdout = Writer::new_buf();
+        core.run("watch", &["-".to_owned()]);
+        core.run_at("wx", &["00".to_owned()], 0x10);
+        let env = core.env.clone();
+        env.write().set_bool("core.json", true, &mut core).unwrap();
+        core.run("watch", &[]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "[]\n");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_watch() {
+        operate_on_file(&test_watch_cb, DATA);
+    }
+
+    fn test_watch_vir_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open(&path.to_string_lossy(), IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.map(0x10, 0x1000, 0x10).unwrap();
+        core.mode = AddrMode::Vir;
+        core.run_at("watch", &["2".to_owned()], 0x1000);
+        core.run_at("watch", &["0x20".to_owned()], 0x1000);
+        // physical writes are reported against virtual watches.
+        core.mode = AddrMode::Phy;
+        core.run_at("wx", &["1234".to_owned()], 0x11);
+        // and virtual writes are reported too.
+        core.mode = AddrMode::Vir;
+        core.run_at("wx", &["56".to_owned()], 0x1001);
+        core.run("watch", &["1".to_owned(), "2".to_owned()]);
+        core.run("watch", &["0".to_owned()]);
+        core.run("watch", &["x".to_owned()]);
+        core.run_at("watch", &["0x20".to_owned()], u64::MAX - 0x10);
+        core.run("watch", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            format!(
+                "Watch 0x00001000 (0x2 bytes) written: {:02x} {:02x} -> {:02x} 12\n\
+                 Watch 0x00001000 (0x2 bytes) written: {:02x} 12 -> {:02x} 56\n\
+                 Address\t\tSize\tMode\n0x00001000\t0x2\tVir\n",
+                DATA[0x10], DATA[0x11], DATA[0x10], DATA[0x10], DATA[0x10]
+            )
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to set watch\n\
+             Range 0x1000-0x1020 is not fully mapped.\n\
+             Arguments Error: Expected between 0 and 1 arguments, found 2.\n\
+             Error: Failed to set watch\n\
+             Size can't be zero.\n\
+             Error: Failed to parse size\n\
+             invalid digit found in string\n\
+             Error: Failed to set watch\n\
+             Address overflow.\n"
+        );
+    }
+
+    #[test]
+    fn test_watch_vir() {
+        operate_on_file(&test_watch_vir_cb, DATA);
+    }
+
+    fn test_watch_hooks_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open(&path.to_string_lossy(), IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        let accesses = Arc::new(Mutex::new(Vec::new()));
+        let reads = accesses.clone();
+        core.io.set_read_hook(Box::new(move |op, addr, size| {
+            reads.lock().push((op, addr, size));
+        }));
+        // removing watches keeps the read hook.
+        core.run_at("watch", &["4".to_owned()], 0x10);
+        core.run("watch", &["-".to_owned()]);
+        mem::take(&mut *accesses.lock());
+        core.io.pread(0x20, &mut [0; 1]).unwrap();
+        assert_eq!(
+            mem::take(&mut *accesses.lock()),
+            [(HookOp::PhyRead, 0x20, 1)]
+        );
+        // a write hook set by someone else is neither replaced nor removed.
+        let writes = accesses.clone();
+        core.io.set_write_hook(Box::new(move |op, addr, size| {
+            writes.lock().push((op, addr, size));
+        }));
+        core.run_at("watch", &["4".to_owned()], 0x10);
+        core.run("watch", &["-".to_owned()]);
+        core.run_at("wx", &["00".to_owned()], 0x10);
+        assert_eq!(*accesses.lock(), [(HookOp::PhyWrite, 0x10, 1)]);
+        core.run("watch", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Address\t\tSize\tMode\n"
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to set watch\nAnother write hook is already set.\n"
+        );
+    }
+
+    #[test]
+    fn test_watch_hooks() {
+        operate_on_file(&test_watch_hooks_cb, DATA);
+    }
+}
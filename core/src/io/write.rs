@@ -3,7 +3,7 @@
 use crate::core::Core;
 use crate::helper::{error_msg, expect, str_to_num};
 use crate::Cmd;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::prelude::*;
 
 #[derive(Default)]
@@ -102,13 +102,53 @@ impl Cmd for WriteToFile {
     }
 }
 
+#[derive(Default)]
+pub struct WriteFromFile;
+
+impl Cmd for WriteFromFile {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() != 1 {
+            expect(core, args.len() as u64, 1);
+            return;
+        }
+        let data = match fs::read(&args[0]) {
+            Ok(data) => data,
+            Err(e) => {
+                let err_str = format!("{e}.");
+                error_msg(core, "Failed to read file", &err_str);
+                return;
+            }
+        };
+        if data.is_empty() {
+            return;
+        }
+        // nothing is written unless every byte of the destination can be written.
+        let loc = core.get_loc();
+        if let Err(e) = core.write(loc, &data) {
+            error_msg(core, "Failed to write data", &e.to_string());
+        }
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["writeFromFile", "wff"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "[filepath]",
+            "write content of file identified by [filepath] at current location.",
+        )]
+    }
+}
+
 #[cfg(test)]
 
 mod test_write {
     use super::*;
     use crate::{writer::Writer, AddrMode, CmdOps};
     use rair_io::*;
-    use std::fs;
+    use std::path::Path;
+    use test_file::*;
     #[test]
     fn test_help() {
         let mut core = Core::new_no_colors();
@@ -116,8 +156,10 @@ mod test_write {
         core.stdout = Writer::new_buf();
         let wx = WriteHex;
         let wtf = WriteToFile;
+        let wff = WriteFromFile;
         wx.help(&mut core);
         wtf.help(&mut core);
+        wff.help(&mut core);
         assert_eq!(
             core.stdout.utf8_string().unwrap(),
             "Commands: [writetHex | wx]\n\
@@ -125,7 +167,10 @@ mod test_write {
              wx [hexpairs]\twrite given hexpairs data into the current address.\n\
              Commands: [writeToFile | wtf]\n\
              Usage:\n\
-             wtf [size] [filepath]\twrite data of size [size] at current location to file identified by [filepath].\n"
+             wtf [size] [filepath]\twrite data of size [size] at current location to file identified by [filepath].\n\
+             Commands: [writeFromFile | wff]\n\
+             Usage:\n\
+             wff [filepath]\twrite content of file identified by [filepath] at current location.\n"
         );
         assert_eq!(core.stderr.utf8_string().unwrap(), "");
     }
@@ -222,7 +267,7 @@ mod test_write {
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Error: Read Failed\nCannot resolve address.\n"
+            "Error: Read Failed\nCannot resolve address 0x500.\n"
         );
     }
 
@@ -263,7 +308,99 @@ mod test_write {
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
             core.stderr.utf8_string().unwrap(),
-            "Error: Failed to read data\nCannot resolve address.\n"
+            "Error: Failed to read data\nCannot resolve address 0x50.\n"
+        );
+    }
+
+    fn test_wff_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let args = [path.to_string_lossy().to_string()];
+        core.io
+            .open("malloc://0x100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.pwrite(0x10, &DATA[..0x20]).unwrap();
+        core.set_loc(0x10);
+        core.run("wtf", &["0x20".to_owned(), args[0].clone()]);
+        assert_eq!(fs::read(path).unwrap(), &DATA[..0x20]);
+        core.set_loc(0x80);
+        core.run("wff", &args);
+        let mut data = [0; 0x22];
+        core.io.pread(0x7f, &mut data).unwrap();
+        assert_eq!(data[0], 0);
+        assert_eq!(data[1..0x21], DATA[..0x20]);
+        assert_eq!(data[0x21], 0);
+        // through virtual addresses.
+        core.io.map(0x0, 0x1000, 0x40).unwrap();
+        core.mode = AddrMode::Vir;
+        core.set_loc(0x1020);
+        core.run("wff", &args);
+        core.io.pread(0x20, &mut data[..0x20]).unwrap();
+        assert_eq!(data[..0x20], DATA[..0x20]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    #[test]
+    fn test_wff() {
+        operate_on_file(&test_wff_cb, &[]);
+    }
+
+    fn test_wff_error_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let args = [path.to_string_lossy().to_string()];
+        core.io
+            .open("malloc://0x40", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io.map(0x0, 0x1000, 0x20).unwrap();
+        core.io.map(0x30, 0x1030, 0x10).unwrap();
+        core.run("wff", &[]);
+        core.run("wff", &["file_that_does_not_exist".to_owned()]);
+        // the file is larger than the space left at current location.
+        core.set_loc(0x30);
+        core.run("wff", &args);
+        // some of the destination is not mapped.
+        core.mode = AddrMode::Vir;
+        core.set_loc(0x1010);
+        core.run("wff", &args);
+        let mut data = [0; 0x40];
+        core.io.pread(0x0, &mut data).unwrap();
+        assert_eq!(data, [0; 0x40]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected 1 argument(s), found 0.\n\
+             Error: Failed to read file\n\
+             No such file or directory (os error 2).\n\
+             Error: Failed to write data\n\
+             Cannot resolve address 0x40.\n\
+             Error: Failed to write data\n\
+             Cannot resolve address 0x1020.\n"
         );
     }
+    #[test]
+    fn test_wff_error() {
+        operate_on_file(&test_wff_error_cb, &DATA[..0x20]);
+    }
+
+    fn test_wff_empty_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.io
+            .open("malloc://0x10", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.run("wff", &[path.to_string_lossy().to_string()]);
+        let mut data = [0xff; 0x10];
+        core.io.pread(0x0, &mut data).unwrap();
+        assert_eq!(data, [0; 0x10]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    #[test]
+    fn test_wff_empty() {
+        operate_on_file(&test_wff_empty_cb, &[]);
+    }
 }
@@ -17,4 +17,5 @@ pub use self::core::*;
 pub use self::diff::*;
 pub use self::helper::*;
 pub use self::io::*;
+pub use self::utils::{autoload, autosave, Autosaver};
 pub use self::writer::*;
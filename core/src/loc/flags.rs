@@ -0,0 +1,352 @@
+//! commands for naming addresses.
+
+use super::history::History;
+use crate::core::Core;
+use crate::helper::{error_msg, expect, expect_range, str_to_addr};
+use crate::Cmd;
+use serde::Serialize;
+use yansi::Paint;
+
+// flag names must never be confused with numbers or operators in address expressions.
+fn is_flag_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+#[derive(Serialize)]
+struct FlagRow<'a> {
+    name: &'a str,
+    addr: u64,
+}
+
+#[derive(Default)]
+pub struct Flag;
+
+impl Flag {
+    fn remove(core: &mut Core, name: &str) {
+        if core.flags.remove(name).is_none() {
+            let err_str = format!("Flag `{name}` does not exist.");
+            error_msg(core, "Failed to remove flag", &err_str);
+        }
+    }
+}
+
+impl Cmd for Flag {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.is_empty() || args.len() > 2 {
+            expect_range(core, args.len() as u64, 1, 2);
+            return;
+        }
+        if args[0] == "-" && args.len() == 2 {
+            return Self::remove(core, &args[1]);
+        }
+        if let Some(name) = args[0].strip_prefix('-') {
+            if args.len() == 2 {
+                expect(core, args.len() as u64, 1);
+                return;
+            }
+            return Self::remove(core, name);
+        }
+        let name = &args[0];
+        if !is_flag_name(name) {
+            let err_str = format!(
+                "`{name}` must start with a letter or `_` followed by letters, digits, `_` or `.`."
+            );
+            return error_msg(core, "Invalid flag name", &err_str);
+        }
+        let addr = match args.get(1) {
+            Some(addr) => match str_to_addr(core, addr) {
+                Ok(addr) => addr,
+                Err(e) => return error_msg(core, "Failed to evaluate address", &e),
+            },
+            None => core.get_loc(),
+        };
+        core.flags.insert(name.clone(), addr);
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["flag", "f"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("[name]", "\tSet flag [name] at current location."),
+            ("[name] [addr]", "Set flag [name] at [addr]."),
+            ("-[name]", "\tRemove flag [name]."),
+        ]
+    }
+}
+
+#[derive(Default)]
+pub struct SeekFlag;
+
+impl Cmd for SeekFlag {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() != 1 {
+            expect(core, args.len() as u64, 1);
+            return;
+        }
+        if let Some(addr) = core.flags.get(&args[0]).copied() {
+            History::add(core);
+            core.set_loc(addr);
+        } else {
+            let err_str = format!("Flag `{}` does not exist.", args[0]);
+            error_msg(core, "Seek Error", &err_str);
+        }
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["fs"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[("[name]", "Set current location to address of flag [name].")]
+    }
+}
+
+#[derive(Default)]
+pub struct SeekNearFlag;
+
+impl SeekNearFlag {
+    // flag address to seek to, ties between flags before and after `loc` go to the one after.
+    fn target(core: &Core, arg: Option<&str>) -> Result<u64, &'static str> {
+        let loc = core.get_loc();
+        let addrs = core.flags.values().copied();
+        match arg {
+            None => addrs
+                .min_by_key(|&addr| (addr.abs_diff(loc), addr < loc))
+                .ok_or("No flags are set."),
+            Some("+") => addrs
+                .filter(|&addr| addr > loc)
+                .min()
+                .ok_or("No flag after current location."),
+            Some(_) => addrs
+                .filter(|&addr| addr < loc)
+                .max()
+                .ok_or("No flag before current location."),
+        }
+    }
+}
+
+impl Cmd for SeekNearFlag {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() > 1 {
+            expect_range(core, args.len() as u64, 0, 1);
+            return;
+        }
+        let arg = args.first().map(String::as_str);
+        if let Some(arg) = arg.filter(|arg| *arg != "+" && *arg != "-") {
+            let err_str = format!("Expected `+` or `-`, found `{arg}`.");
+            return error_msg(core, "Invalid argument", &err_str);
+        }
+        match Self::target(core, arg) {
+            Ok(addr) => {
+                History::add(core);
+                core.set_loc(addr);
+            }
+            Err(e) => error_msg(core, "Seek Error", e),
+        }
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["sf"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("", "Set current location to address of the nearest flag."),
+            ("+", "Set current location to address of the next flag."),
+            ("-", "Set current location to address of the previous flag."),
+        ]
+    }
+}
+
+#[derive(Default)]
+pub struct ListFlags;
+
+impl Cmd for ListFlags {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if !args.is_empty() {
+            expect(core, args.len() as u64, 0);
+            return;
+        }
+        let env = core.env.read();
+        let color = env.get_str("maps.headerColor").unwrap();
+        let (r, g, b) = env.get_color(color).unwrap();
+        drop(env);
+        let header = format!("{}", "Address\t\tName".rgb(r, g, b));
+        let flags = core.flags.clone();
+        let rows: Vec<FlagRow> = flags
+            .iter()
+            .map(|(name, addr)| FlagRow { name, addr: *addr })
+            .collect();
+        core.emit_table(&header, &rows, |flag| {
+            format!("0x{:08x}\t{}", flag.addr, flag.name)
+        });
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["fl"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[("", "List all flags sorted by name.")]
+    }
+}
+
+#[cfg(test)]
+mod test_flags {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+
+    #[test]
+    fn test_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        Flag.help(&mut core);
+        SeekFlag.help(&mut core);
+        SeekNearFlag.help(&mut core);
+        ListFlags.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Commands: [flag | f]\n\
+             Usage:\n\
+             f [name]\t\tSet flag [name] at current location.\n\
+             f [name] [addr]\tSet flag [name] at [addr].\n\
+             f -[name]\t\tRemove flag [name].\n\
+             Command: [fs]\n\
+             Usage:\n\
+             fs [name]\tSet current location to address of flag [name].\n\
+             Command: [sf]\n\
+             Usage:\n\
+             sf\tSet current location to address of the nearest flag.\n\
+             sf +\tSet current location to address of the next flag.\n\
+             sf -\tSet current location to address of the previous flag.\n\
+             Command: [fl]\n\
+             Usage:\n\
+             fl\tList all flags sorted by name.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_flags() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.set_loc(0x100);
+        core.run("f", &["main".to_owned()]);
+        core.run("f", &["sym.entry".to_owned(), "main+0x20".to_owned()]);
+        core.run("f", &["_start".to_owned(), "0x10".to_owned()]);
+        core.run("fl", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Address\t\tName\n\
+             0x00000010\t_start\n\
+             0x00000100\tmain\n\
+             0x00000120\tsym.entry\n"
+        );
+        core.stdout = Writer::new_buf();
+        core.run("fs", &["_start".to_owned()]);
+        assert_eq!(core.get_loc(), 0x10);
+        core.run("s", &["sym.entry".to_owned()]);
+        assert_eq!(core.get_loc(), 0x120);
+        core.run("s", &["-".to_owned()]);
+        core.run("s", &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x100);
+        // moving a flag.
+        core.run("f", &["main".to_owned(), "$$+1".to_owned()]);
+        core.run("f", &["-_start".to_owned()]);
+        core.run("f", &["-".to_owned(), "sym.entry".to_owned()]);
+        let env = core.env.clone();
+        env.write().set_bool("core.json", true, &mut core).unwrap();
+        core.run("fl", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "[{\"name\":\"main\",\"addr\":257}]\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_seek_near_flag() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run("sf", &[]);
+        core.flags.insert("a".to_owned(), 0x100);
+        core.flags.insert("b".to_owned(), 0x200);
+        core.flags.insert("c".to_owned(), 0x300);
+        core.set_loc(0x170);
+        core.run("sf", &[]);
+        assert_eq!(core.get_loc(), 0x100);
+        // ties go to the flag after current location.
+        core.set_loc(0x180);
+        core.run("sf", &[]);
+        assert_eq!(core.get_loc(), 0x200);
+        core.run("sf", &["+".to_owned()]);
+        assert_eq!(core.get_loc(), 0x300);
+        core.run("sf", &["+".to_owned()]);
+        assert_eq!(core.get_loc(), 0x300);
+        core.run("sf", &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x200);
+        core.run("sf", &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x100);
+        core.run("sf", &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x100);
+        core.run("s", &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x200);
+        core.run("sf", &["x".to_owned()]);
+        core.run("sf", &["+".to_owned(), "-".to_owned()]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Seek Error\n\
+             No flags are set.\n\
+             Error: Seek Error\n\
+             No flag after current location.\n\
+             Error: Seek Error\n\
+             No flag before current location.\n\
+             Error: Invalid argument\n\
+             Expected `+` or `-`, found `x`.\n\
+             Arguments Error: Expected between 0 and 1 arguments, found 2.\n"
+        );
+    }
+
+    #[test]
+    fn test_flags_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run("f", &[]);
+        core.run("f", &["0main".to_owned()]);
+        core.run("f", &["main+1".to_owned()]);
+        core.run("f", &["main".to_owned(), "nothing".to_owned()]);
+        core.run("f", &["-main".to_owned()]);
+        core.run("f", &["-main".to_owned(), "0x10".to_owned()]);
+        core.run("fs", &["main".to_owned()]);
+        core.run("fl", &["main".to_owned()]);
+        assert!(core.flags.is_empty());
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Arguments Error: Expected between 1 and 2 arguments, found 0.\n\
+             Error: Invalid flag name\n\
+             `0main` must start with a letter or `_` followed by letters, digits, `_` or `.`.\n\
+             Error: Invalid flag name\n\
+             `main+1` must start with a letter or `_` followed by letters, digits, `_` or `.`.\n\
+             Error: Failed to evaluate address\n\
+             invalid digit found in string\n\
+             Error: Failed to remove flag\n\
+             Flag `main` does not exist.\n\
+             Arguments Error: Expected 1 argument(s), found 2.\n\
+             Error: Seek Error\n\
+             Flag `main` does not exist.\n\
+             Arguments Error: Expected 0 argument(s), found 1.\n"
+        );
+    }
+}
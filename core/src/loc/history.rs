@@ -1,68 +1,383 @@
 //! history managment for seek and mode.
 
 use crate::core::Core;
-use crate::helper::AddrMode;
-#[derive(Default)]
+use crate::helper::{error_msg, expect_range, str_to_num, AddrMode};
+use crate::Cmd;
+use alloc::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct History {
-    back: Vec<(AddrMode, u64)>,
+    back: VecDeque<(AddrMode, u64)>,
     front: Vec<(AddrMode, u64)>,
 }
 
 impl History {
-    pub fn backward(&mut self, core: &Core) -> Option<(AddrMode, u64)> {
-        let (mode, addr) = self.back.pop()?;
-        self.front.push((core.mode, core.get_loc()));
+    pub fn backward(core: &mut Core) -> Option<(AddrMode, u64)> {
+        let current = (core.mode, core.get_loc());
+        let history = &mut core.history;
+        let (mode, addr) = history.back.pop_back()?;
+        history.front.push(current);
         Some((mode, addr))
     }
-    pub fn forward(&mut self, core: &Core) -> Option<(AddrMode, u64)> {
-        let (mode, addr) = self.front.pop()?;
-        self.back.push((core.mode, core.get_loc()));
+    pub fn forward(core: &mut Core) -> Option<(AddrMode, u64)> {
+        let current = (core.mode, core.get_loc());
+        let history = &mut core.history;
+        let (mode, addr) = history.front.pop()?;
+        history.back.push_back(current);
         Some((mode, addr))
     }
-    pub fn add(&mut self, core: &Core) {
-        self.front.clear();
-        self.back.push((core.mode, core.get_loc()));
+    pub fn add(core: &mut Core) {
+        let current = (core.mode, core.get_loc());
+        let capacity = core.env.read().get_u64("core.historySize").unwrap() as usize;
+        let history = &mut core.history;
+        history.front.clear();
+        history.back.push_back(current);
+        // drop oldest locations once we exceed the capacity
+        while history.back.len() > capacity {
+            history.back.pop_front();
+        }
+    }
+    /// Save history into `path` as JSON.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)
+    }
+    /// Load history saved by [`History::save`], a missing file is loaded as empty history.
+    pub fn load(path: &Path) -> io::Result<History> {
+        match fs::read(path) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(e) => Err(e),
+        }
+    }
+    /// Save history into the path stored in `core.historyFile` if it is set.
+    pub fn autosave(core: &mut Core) {
+        let path = core
+            .env
+            .read()
+            .get_str("core.historyFile")
+            .unwrap()
+            .to_owned();
+        if path.is_empty() {
+            return;
+        }
+        if let Err(e) = core.history.save(Path::new(&path)) {
+            error_msg(core, "Failed to save seek history", &e.to_string());
+        }
+    }
+    /// Replace history with the one saved in `core.historyFile` if it is set.
+    pub fn autoload(core: &mut Core) {
+        let env = core.env.read();
+        let path = env.get_str("core.historyFile").unwrap().to_owned();
+        let capacity = env.get_u64("core.historySize").unwrap() as usize;
+        drop(env);
+        if path.is_empty() {
+            return;
+        }
+        match History::load(Path::new(&path)) {
+            Ok(mut history) => {
+                // keep the newest locations in case core.historySize got smaller.
+                while history.back.len() > capacity {
+                    history.back.pop_front();
+                }
+                core.history = history;
+            }
+            Err(e) => error_msg(core, "Failed to load seek history", &e.to_string()),
+        }
+    }
+    /// All locations in history from oldest to newest, along with the index of the current
+    /// location.
+    pub fn entries(core: &Core) -> (Vec<(AddrMode, u64)>, usize) {
+        let history = &core.history;
+        let mut entries: Vec<_> = history.back.iter().copied().collect();
+        entries.push((core.mode, core.get_loc()));
+        entries.extend(history.front.iter().rev());
+        (entries, history.back.len())
+    }
+    /// Walk backward or forward until the nth entry of [`History::entries`] becomes the current
+    /// location and return it, or return [None] if there is no such entry.
+    pub fn jump(core: &mut Core, n: usize) -> Option<(AddrMode, u64)> {
+        let mut current = (core.mode, core.get_loc());
+        let history = &mut core.history;
+        if n > history.back.len() + history.front.len() {
+            return None;
+        }
+        while history.back.len() > n {
+            history.front.push(current);
+            current = history.back.pop_back()?;
+        }
+        while history.back.len() < n {
+            history.back.push_back(current);
+            current = history.front.pop()?;
+        }
+        Some(current)
+    }
+}
+
+#[derive(Default)]
+pub struct ListHistory;
+
+impl ListHistory {
+    fn list(core: &mut Core) {
+        let (entries, current) = History::entries(core);
+        for (i, (mode, addr)) in entries.into_iter().enumerate() {
+            let marker = if i == current { "*" } else { " " };
+            writeln!(core.stdout, "{marker}{i}\t{mode}\t0x{addr:08x}").unwrap();
+        }
+    }
+    fn jump(core: &mut Core, n: &str) {
+        let n = match str_to_num(n) {
+            Ok(n) => n,
+            Err(e) => return error_msg(core, "History Error", &e.to_string()),
+        };
+        if let Some((mode, addr)) = History::jump(core, n as usize) {
+            core.mode = mode;
+            core.set_loc(addr);
+        } else {
+            error_msg(core, "History Error", &format!("Entry {n} does not exist."));
+        }
+    }
+}
+
+impl Cmd for ListHistory {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        match args {
+            [] => Self::list(core),
+            [n] => Self::jump(core, n),
+            _ => expect_range(core, args.len() as u64, 0, 1),
+        }
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["history", "h"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("", "List seek history, current location is marked with *."),
+            ("[n]", "Jump to the nth location in seek history."),
+        ]
     }
 }
 
 #[cfg(test)]
 mod test_history {
     use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use test_file::operate_on_dir;
     #[test]
     fn test_history() {
-        let mut history = History::default();
         let mut core = Core::new_no_colors();
-        assert_eq!(history.backward(&core), None);
-        assert_eq!(history.backward(&core), None);
-        history.add(&core);
+        assert_eq!(History::backward(&mut core), None);
+        assert_eq!(History::backward(&mut core), None);
+        History::add(&mut core);
         core.set_loc(0x50);
-        history.add(&core);
+        History::add(&mut core);
         core.set_loc(0x100);
         core.mode = AddrMode::Vir;
-        history.add(&core);
+        History::add(&mut core);
         core.set_loc(0x150);
         core.mode = AddrMode::Phy;
-        history.add(&core);
-        assert_eq!(history.backward(&core).unwrap(), (AddrMode::Phy, 0x150));
+        History::add(&mut core);
+        assert_eq!(
+            History::backward(&mut core).unwrap(),
+            (AddrMode::Phy, 0x150)
+        );
         core.set_loc(0x150);
-        assert_eq!(history.backward(&core).unwrap(), (AddrMode::Vir, 0x100));
+        assert_eq!(
+            History::backward(&mut core).unwrap(),
+            (AddrMode::Vir, 0x100)
+        );
         core.set_loc(0x100);
         core.mode = AddrMode::Vir;
-        assert_eq!(history.backward(&core).unwrap(), (AddrMode::Phy, 0x50));
+        assert_eq!(History::backward(&mut core).unwrap(), (AddrMode::Phy, 0x50));
         core.set_loc(0x50);
         core.mode = AddrMode::Phy;
-        assert_eq!(history.forward(&core).unwrap(), (AddrMode::Vir, 0x100));
+        assert_eq!(History::forward(&mut core).unwrap(), (AddrMode::Vir, 0x100));
         core.set_loc(0x100);
         core.mode = AddrMode::Vir;
-        assert_eq!(history.backward(&core).unwrap(), (AddrMode::Phy, 0x50));
+        assert_eq!(History::backward(&mut core).unwrap(), (AddrMode::Phy, 0x50));
         core.set_loc(0x50);
         core.mode = AddrMode::Phy;
-        assert_eq!(history.backward(&core).unwrap(), (AddrMode::Phy, 0x0));
+        assert_eq!(History::backward(&mut core).unwrap(), (AddrMode::Phy, 0x0));
         core.set_loc(0x0);
         core.mode = AddrMode::Phy;
-        assert_eq!(history.backward(&core), None);
-        assert_eq!(history.forward(&core).unwrap(), (AddrMode::Phy, 0x50));
-        assert_eq!(history.forward(&core).unwrap(), (AddrMode::Vir, 0x100));
-        assert_eq!(history.forward(&core).unwrap(), (AddrMode::Phy, 0x150));
+        assert_eq!(History::backward(&mut core), None);
+        assert_eq!(History::forward(&mut core).unwrap(), (AddrMode::Phy, 0x50));
+        assert_eq!(History::forward(&mut core).unwrap(), (AddrMode::Vir, 0x100));
+        assert_eq!(History::forward(&mut core).unwrap(), (AddrMode::Phy, 0x150));
+    }
+
+    #[test]
+    fn test_history_capacity() {
+        let mut core = Core::new_no_colors();
+        let locked_env = core.env.clone();
+        locked_env
+            .write()
+            .set_u64("core.historySize", 2, &mut core)
+            .unwrap();
+        for loc in 0..5 {
+            core.set_loc(loc);
+            History::add(&mut core);
+        }
+        assert_eq!(History::backward(&mut core).unwrap(), (AddrMode::Phy, 4));
+        assert_eq!(History::backward(&mut core).unwrap(), (AddrMode::Phy, 3));
+        assert_eq!(History::backward(&mut core), None);
+    }
+
+    #[test]
+    fn test_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        ListHistory.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Commands: [history | h]\n\
+             Usage:\n\
+             h\tList seek history, current location is marked with *.\n\
+             h [n]\tJump to the nth location in seek history.\n\
+             "
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_list_history() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut history = ListHistory;
+        history.run(&mut core, &[]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "*0\tPhy\t0x00000000\n");
+        core.stdout = Writer::new_buf();
+        for loc in [0x50, 0x100, 0x150] {
+            History::add(&mut core);
+            core.set_loc(loc);
+        }
+        core.mode = AddrMode::Vir;
+        History::backward(&mut core);
+        core.mode = AddrMode::Phy;
+        core.set_loc(0x100);
+        history.run(&mut core, &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            " 0\tPhy\t0x00000000\n \
+             1\tPhy\t0x00000050\n\
+             *2\tPhy\t0x00000100\n \
+             3\tVir\t0x00000150\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_jump() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut history = ListHistory;
+        for loc in [0x50, 0x100, 0x150] {
+            History::add(&mut core);
+            core.set_loc(loc);
+        }
+        core.mode = AddrMode::Vir;
+        history.run(&mut core, &["1".to_owned()]);
+        assert_eq!((core.mode, core.get_loc()), (AddrMode::Phy, 0x50));
+        history.run(&mut core, &["0x3".to_owned()]);
+        assert_eq!((core.mode, core.get_loc()), (AddrMode::Vir, 0x150));
+        history.run(&mut core, &["0".to_owned()]);
+        assert_eq!((core.mode, core.get_loc()), (AddrMode::Phy, 0x0));
+        // jumping keeps the rest of the history intact.
+        assert_eq!(History::forward(&mut core).unwrap(), (AddrMode::Phy, 0x50));
+        core.set_loc(0x50);
+        history.run(&mut core, &["2".to_owned()]);
+        assert_eq!((core.mode, core.get_loc()), (AddrMode::Phy, 0x100));
+        assert_eq!(History::backward(&mut core).unwrap(), (AddrMode::Phy, 0x50));
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        history.run(&mut core, &["4".to_owned()]);
+        history.run(&mut core, &["x".to_owned()]);
+        history.run(&mut core, &["1".to_owned(), "2".to_owned()]);
+        assert_eq!(core.get_loc(), 0x100);
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: History Error\nEntry 4 does not exist.\n\
+             Error: History Error\ninvalid digit found in string\n\
+             Arguments Error: Expected between 0 and 1 arguments, found 2.\n"
+        );
+    }
+
+    fn save_load_cb(dir: &Path) {
+        let path = dir.join("history");
+        let mut core = Core::new_no_colors();
+        for loc in [0x50, 0x100, 0x150] {
+            History::add(&mut core);
+            core.set_loc(loc);
+            core.mode = AddrMode::Vir;
+        }
+        History::backward(&mut core);
+        core.history.save(&path).unwrap();
+        let history = History::load(&path).unwrap();
+        assert_eq!(history.back, core.history.back);
+        assert_eq!(history.front, core.history.front);
+        assert_eq!(history.back, [(AddrMode::Phy, 0x0), (AddrMode::Vir, 0x50)]);
+        assert_eq!(history.front, [(AddrMode::Vir, 0x150)]);
+        // missing file is just empty history.
+        let history = History::load(&dir.join("missing")).unwrap();
+        assert!(history.back.is_empty() && history.front.is_empty());
+        fs::write(&path, "garbage").unwrap();
+        assert_eq!(
+            History::load(&path).err().unwrap().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+    #[test]
+    fn test_save_load() {
+        operate_on_dir(&save_load_cb, &[]);
+    }
+
+    fn autoload_cb(dir: &Path) {
+        let path = dir.join("history").to_string_lossy().to_string();
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        let env = core.env.clone();
+        env.write()
+            .set_str("core.historyFile", &path, &mut core)
+            .unwrap();
+        // nothing saved yet.
+        History::autoload(&mut core);
+        assert_eq!(History::backward(&mut core), None);
+        for loc in 0..5 {
+            core.set_loc(loc);
+            History::add(&mut core);
+        }
+        History::autosave(&mut core);
+        let mut core2 = Core::new_no_colors();
+        let env = core2.env.clone();
+        env.write()
+            .set_str("core.historyFile", &path, &mut core2)
+            .unwrap();
+        env.write()
+            .set_u64("core.historySize", 2, &mut core2)
+            .unwrap();
+        History::autoload(&mut core2);
+        assert_eq!(History::backward(&mut core2).unwrap(), (AddrMode::Phy, 4));
+        assert_eq!(History::backward(&mut core2).unwrap(), (AddrMode::Phy, 3));
+        assert_eq!(History::backward(&mut core2), None);
+        fs::write(&path, "garbage").unwrap();
+        History::autoload(&mut core);
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to load seek history\nexpected value at line 1 column 1\n"
+        );
+    }
+    #[test]
+    fn test_autoload() {
+        operate_on_dir(&autoload_cb, &[]);
     }
 }
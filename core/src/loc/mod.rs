@@ -1,17 +1,29 @@
 //! commands handling file location.
 
+mod flags;
 mod history;
 mod mode;
+mod relative;
 mod seek;
-use self::history::History;
+mod tab;
+use self::flags::{Flag, ListFlags, SeekFlag, SeekNearFlag};
+pub(crate) use self::history::History;
+use self::history::ListHistory;
 use self::mode::Mode;
+use self::relative::Relative;
 use self::seek::Seek;
+use self::tab::{NextFile, PrevFile};
 use crate::core::Core;
-use alloc::sync::Arc;
-use parking_lot::Mutex;
 
 pub fn register_loc(core: &mut Core) {
-    let history = Arc::new(Mutex::new(History::default()));
-    core.add_command(Mode::with_history(history.clone()));
-    core.add_command(Seek::with_history(history));
+    core.add_command(Mode);
+    core.add_command(Seek);
+    core.add_command(Relative);
+    core.add_command(NextFile);
+    core.add_command(PrevFile);
+    core.add_command(ListHistory);
+    core.add_command(Flag);
+    core.add_command(SeekFlag);
+    core.add_command(SeekNearFlag);
+    core.add_command(ListFlags);
 }
@@ -2,19 +2,11 @@
 
 use super::history::History;
 use crate::core::Core;
-use crate::helper::{error_msg, expect, AddrMode, MRc};
+use crate::helper::{error_msg, expect, AddrMode};
 use crate::Cmd;
 use yansi::Paint;
 #[derive(Default)]
-pub struct Mode {
-    history: MRc<History>,
-}
-
-impl Mode {
-    pub(super) fn with_history(history: MRc<History>) -> Self {
-        Mode { history }
-    }
-}
+pub struct Mode;
 
 impl Cmd for Mode {
     fn run(&mut self, core: &mut Core, args: &[String]) {
@@ -23,7 +15,7 @@ impl Cmd for Mode {
             return;
         }
         if args[0] == "vir" {
-            self.history.lock().add(core);
+            History::add(core);
             if core.mode == AddrMode::Phy {
                 let vir = core.io.phy_to_vir(core.get_loc());
                 if !vir.is_empty() {
@@ -32,7 +24,7 @@ impl Cmd for Mode {
             }
             core.mode = AddrMode::Vir;
         } else if args[0] == "phy" {
-            self.history.lock().add(core);
+            History::add(core);
             if core.mode == AddrMode::Vir {
                 if let Some(vir) = core.io.vir_to_phy(core.get_loc(), 1) {
                     core.set_loc(vir[0].paddr);
@@ -74,7 +66,7 @@ mod test_mode {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
-        let mode = Mode::default();
+        let mode = Mode;
         mode.help(&mut core);
         assert_eq!(
             core.stdout.utf8_string().unwrap(),
@@ -90,7 +82,7 @@ mod test_mode {
     fn test_mode_cb(path: &Path) {
         let mut core = Core::new_no_colors();
         let len = DATA.len() as u64;
-        let mut mode = Mode::default();
+        let mut mode = Mode;
         core.io.open(&path.to_string_lossy(), IoMode::READ).unwrap();
         core.io.map(0x0, 0x5000, len).unwrap();
         assert_eq!(core.get_loc(), 0x0);
@@ -119,7 +111,7 @@ mod test_mode {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
-        let mut mode = Mode::default();
+        let mut mode = Mode;
         mode.run(&mut core, &[]);
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
         assert_eq!(
@@ -0,0 +1,170 @@
+//! commands handling relative address display.
+
+use crate::core::Core;
+use crate::helper::{error_msg, expect_range, str_to_num, AddrMode};
+use crate::Cmd;
+use rair_io::RIODesc;
+
+#[derive(Default)]
+pub struct Relative;
+
+impl Relative {
+    // find the handle of the file backing the current location.
+    pub(super) fn infer_hndl(core: &Core) -> Option<u64> {
+        let loc = core.get_loc();
+        let paddr = match core.mode {
+            AddrMode::Phy => loc,
+            AddrMode::Vir => core.io.vir_to_phy(loc, 1)?[0].paddr,
+        };
+        core.io
+            .uri_iter()
+            .find(|desc| desc.has_paddr(paddr))
+            .map(RIODesc::hndl)
+    }
+}
+
+impl Cmd for Relative {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if args.len() > 1 {
+            expect_range(core, args.len() as u64, 0, 1);
+            return;
+        }
+        if args.is_empty() {
+            match Self::infer_hndl(core) {
+                Some(hndl) => core.rel_hndl = Some(hndl),
+                None => error_msg(
+                    core,
+                    "Failed to infer handle",
+                    "No file is opened at current location.",
+                ),
+            }
+            return;
+        }
+        if args[0] == "abs" {
+            core.rel_hndl = None;
+            return;
+        }
+        let hndl = match str_to_num(&args[0]) {
+            Ok(hndl) => hndl,
+            Err(e) => {
+                let err_str = format!("{e}");
+                error_msg(core, "Invalid hndl", &err_str);
+                return;
+            }
+        };
+        if core.io.hndl_to_desc(hndl).is_none() {
+            return error_msg(core, "Invalid hndl", "Handle Does not exist.");
+        }
+        core.rel_hndl = Some(hndl);
+    }
+    fn commands(&self) -> &'static [&'static str] {
+        &["relative", "rel"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            (
+                "",
+                "\tDisplay physical addresses relative to the file at current location.",
+            ),
+            (
+                "[hndl]",
+                "Display physical addresses relative to the file with given [hndl].",
+            ),
+            ("abs", "\tDisplay absolute addresses."),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test_relative {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use rair_io::*;
+
+    #[test]
+    fn test_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let rel = Relative;
+        rel.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Commands: [relative | rel]\n\
+             Usage:\n\
+             rel\t\tDisplay physical addresses relative to the file at current location.\n\
+             rel [hndl]\tDisplay physical addresses relative to the file with given [hndl].\n\
+             rel abs\t\tDisplay absolute addresses.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_relative() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut rel = Relative;
+        core.io
+            .open("malloc://0x20", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.io
+            .open_at("malloc://0x20", IoMode::READ | IoMode::WRITE, 0x100)
+            .unwrap();
+        core.set_loc(0x108);
+        rel.run(&mut core, &[]);
+        assert_eq!(core.rel_hndl, Some(1));
+        core.run("px", &["0x20".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "- offset -  0 1  2 3  4 5  6 7  8 9  A B  C D  E F  0123456789ABCDEF\n\
+             1+0x000008 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+             1+0x000018 0000 0000 0000 0000 #### #### #### ####  ........########\n"
+        );
+        core.stdout = Writer::new_buf();
+        rel.run(&mut core, &["0".to_owned()]);
+        assert_eq!(core.rel_hndl, Some(0));
+        core.set_loc(0x10);
+        core.run("px", &["0x10".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "- offset -  0 1  2 3  4 5  6 7  8 9  A B  C D  E F  0123456789ABCDEF\n\
+             0+0x000010 0000 0000 0000 0000 0000 0000 0000 0000  ................\n"
+        );
+        core.stdout = Writer::new_buf();
+        rel.run(&mut core, &["abs".to_owned()]);
+        assert_eq!(core.rel_hndl, None);
+        core.run("px", &["0x10".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "- offset -  0 1  2 3  4 5  6 7  8 9  A B  C D  E F  0123456789ABCDEF\n\
+             0x00000010 0000 0000 0000 0000 0000 0000 0000 0000  ................\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_relative_errors() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut rel = Relative;
+        rel.run(&mut core, &[]);
+        rel.run(&mut core, &["x".to_owned()]);
+        rel.run(&mut core, &["5".to_owned()]);
+        rel.run(&mut core, &["1".to_owned(), "2".to_owned()]);
+        assert_eq!(core.rel_hndl, None);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to infer handle\n\
+             No file is opened at current location.\n\
+             Error: Invalid hndl\n\
+             invalid digit found in string\n\
+             Error: Invalid hndl\n\
+             Handle Does not exist.\n\
+             Arguments Error: Expected between 0 and 1 arguments, found 2.\n"
+        );
+    }
+}
@@ -2,51 +2,46 @@
 
 use super::history::History;
 use crate::core::Core;
-use crate::helper::{error_msg, expect, str_to_num, MRc};
+use crate::helper::{error_msg, expect, str_to_addr, str_to_num};
 use crate::Cmd;
 
 #[derive(Default)]
-pub struct Seek {
-    history: MRc<History>,
-}
+pub struct Seek;
 
 impl Seek {
-    pub(super) fn with_history(history: MRc<History>) -> Self {
-        Seek { history }
-    }
-    fn backward(&mut self, core: &mut Core) {
-        if let Some((mode, addr)) = self.history.lock().backward(core) {
+    fn backward(core: &mut Core) {
+        if let Some((mode, addr)) = History::backward(core) {
             core.mode = mode;
             core.set_loc(addr);
         } else {
             error_msg(core, "Seek Error", "History is empty.");
         }
     }
-    fn forward(&mut self, core: &mut Core) {
-        if let Some((mode, addr)) = self.history.lock().forward(core) {
+    fn forward(core: &mut Core) {
+        if let Some((mode, addr)) = History::forward(core) {
             core.mode = mode;
             core.set_loc(addr);
         } else {
             error_msg(core, "Seek Error", "History is empty.");
         }
     }
-    fn add_loc(&mut self, core: &mut Core, offset: u64) {
+    fn add_loc(core: &mut Core, offset: u64) {
         if let Some(loc) = core.get_loc().checked_add(offset) {
-            self.set_loc(core, loc);
+            Self::set_loc(core, loc);
         } else {
             error_msg(core, "Seek Error", "Attempt to add with overflow.");
         }
     }
-    fn sub_loc(&mut self, core: &mut Core, offset: u64) {
+    fn sub_loc(core: &mut Core, offset: u64) {
         if let Some(loc) = core.get_loc().checked_sub(offset) {
-            self.set_loc(core, loc);
+            Self::set_loc(core, loc);
         } else {
             error_msg(core, "Seek Error", "Attempt to subtract with overflow.");
         }
     }
     #[inline]
-    fn set_loc(&mut self, core: &mut Core, offset: u64) {
-        self.history.lock().add(core);
+    fn set_loc(core: &mut Core, offset: u64) {
+        History::add(core);
         core.set_loc(offset);
     }
 }
@@ -58,23 +53,23 @@ impl Cmd for Seek {
             return;
         }
         if args[0] == "-" {
-            self.backward(core);
+            Self::backward(core);
         } else if args[0] == "+" {
-            self.forward(core);
+            Self::forward(core);
         } else if args[0].starts_with('+') {
             match str_to_num(&args[0][1..]) {
-                Ok(offset) => self.add_loc(core, offset),
+                Ok(offset) => Self::add_loc(core, offset),
                 Err(e) => error_msg(core, "Seek Error", &e.to_string()),
             }
         } else if args[0].starts_with('-') {
             match str_to_num(&args[0][1..]) {
-                Ok(offset) => self.sub_loc(core, offset),
+                Ok(offset) => Self::sub_loc(core, offset),
                 Err(e) => error_msg(core, "Seek Error", &e.to_string()),
             }
         } else {
-            match str_to_num(&args[0]) {
-                Ok(offset) => self.set_loc(core, offset),
-                Err(e) => error_msg(core, "Seek Error", &e.to_string()),
+            match str_to_addr(core, &args[0]) {
+                Ok(offset) => Self::set_loc(core, offset),
+                Err(e) => error_msg(core, "Seek Error", &e),
             }
         }
     }
@@ -90,6 +85,10 @@ impl Cmd for Seek {
             ("+[offset]", "Increase current loc by offset."),
             ("-[offset]", "Decrease current loc by offset."),
             ("[offset]", "Set current location to offset."),
+            (
+                "[expr]",
+                "Set current location to address expression using $$ (current location), $ (end of address space), + and -.",
+            ),
         ]
     }
 }
@@ -99,12 +98,13 @@ impl Cmd for Seek {
 mod test_seek {
     use super::*;
     use crate::{writer::Writer, AddrMode, CmdOps};
+    use rair_io::IoMode;
     #[test]
     fn test_docs() {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
-        let seek = Seek::default();
+        let seek = Seek;
         seek.help(&mut core);
         assert_eq!(
             core.stdout.utf8_string().unwrap(),
@@ -115,6 +115,7 @@ mod test_seek {
              s +[offset]\tIncrease current loc by offset.\n\
              s -[offset]\tDecrease current loc by offset.\n\
              s [offset]\tSet current location to offset.\n\
+             s [expr]\tSet current location to address expression using $$ (current location), $ (end of address space), + and -.\n\
              "
         );
         assert_eq!(core.stderr.utf8_string().unwrap(), "");
@@ -124,7 +125,7 @@ mod test_seek {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
-        let mut seek = Seek::default();
+        let mut seek = Seek;
         assert_eq!(core.mode, AddrMode::Phy);
         assert_eq!(core.get_loc(), 0x0);
         assert_eq!(core.stdout.utf8_string().unwrap(), "");
@@ -206,7 +207,7 @@ mod test_seek {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
-        let mut seek = Seek::default();
+        let mut seek = Seek;
         assert_eq!(core.mode, AddrMode::Phy);
         assert_eq!(core.get_loc(), 0x0);
         seek.run(&mut core, &["-0x5".to_owned()]);
@@ -243,7 +244,7 @@ mod test_seek {
         let mut core = Core::new_no_colors();
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
-        let mut seek = Seek::default();
+        let mut seek = Seek;
         assert_eq!(core.mode, AddrMode::Phy);
         assert_eq!(core.get_loc(), 0x0);
 
@@ -291,4 +292,34 @@ mod test_seek {
         core.stderr = Writer::new_buf();
         core.stdout = Writer::new_buf();
     }
+
+    #[test]
+    fn test_seek_expr() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut seek = Seek;
+        core.io
+            .open("malloc://0x500", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        seek.run(&mut core, &["$-0x100".to_owned()]);
+        assert_eq!(core.get_loc(), 0x400);
+        seek.run(&mut core, &["$$-0x10".to_owned()]);
+        assert_eq!(core.get_loc(), 0x3f0);
+        seek.run(&mut core, &["$$+$".to_owned()]);
+        assert_eq!(core.get_loc(), 0x8f0);
+        seek.run(&mut core, &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x3f0);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        seek.run(&mut core, &["$$-0x400".to_owned()]);
+        assert_eq!(core.get_loc(), 0x3f0);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Seek Error\nAddress underflow.\n"
+        );
+    }
 }
@@ -0,0 +1,176 @@
+//! commands for cycling through opened files.
+
+use super::history::History;
+use super::relative::Relative;
+use crate::core::Core;
+use crate::helper::{error_msg, expect, AddrMode};
+use crate::Cmd;
+use rair_io::RIODesc;
+use std::io::Write;
+
+// Make the file after (or before) the current handle the current one and seek to its base.
+fn switch_file(core: &mut Core, forward: bool) {
+    let hndls: Vec<u64> = core.io.uri_iter().map(RIODesc::hndl).collect();
+    if hndls.is_empty() {
+        writeln!(core.stdout, "No files are opened.").unwrap();
+        return;
+    }
+    if hndls.len() == 1 {
+        return;
+    }
+    let current = core
+        .rel_hndl
+        .filter(|hndl| core.io.hndl_to_desc(*hndl).is_some())
+        .or_else(|| Relative::infer_hndl(core))
+        .and_then(|hndl| hndls.iter().position(|h| *h == hndl));
+    let next = match (current, forward) {
+        (Some(i), true) => (i + 1) % hndls.len(),
+        (Some(i), false) => (i + hndls.len() - 1) % hndls.len(),
+        (None, true) => 0,
+        (None, false) => hndls.len() - 1,
+    };
+    let hndl = hndls[next];
+    let base = core.io.hndl_to_desc(hndl).unwrap().paddr_base();
+    let loc = match core.mode {
+        AddrMode::Phy => base,
+        AddrMode::Vir => {
+            let Some(vaddr) = core.io.phy_to_vir(base).first().copied() else {
+                let msg = format!("Base of file with handle {hndl} is not mapped.");
+                return error_msg(core, "Failed to switch file", &msg);
+            };
+            vaddr
+        }
+    };
+    History::add(core);
+    core.rel_hndl = Some(hndl);
+    core.set_loc(loc);
+}
+
+#[derive(Default)]
+pub struct NextFile;
+
+impl Cmd for NextFile {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if !args.is_empty() {
+            expect(core, args.len() as u64, 0);
+            return;
+        }
+        switch_file(core, true);
+    }
+    fn commands(&self) -> &'static [&'static str] {
+        &["nextFile", "tab"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "",
+            "Switch current handle to the next opened file and seek to its base.",
+        )]
+    }
+}
+
+#[derive(Default)]
+pub struct PrevFile;
+
+impl Cmd for PrevFile {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        if !args.is_empty() {
+            expect(core, args.len() as u64, 0);
+            return;
+        }
+        switch_file(core, false);
+    }
+    fn commands(&self) -> &'static [&'static str] {
+        &["prevFile"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "",
+            "Switch current handle to the previous opened file and seek to its base.",
+        )]
+    }
+}
+
+#[cfg(test)]
+mod test_tab {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+    use rair_io::*;
+
+    #[test]
+    fn test_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        NextFile.help(&mut core);
+        PrevFile.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Commands: [nextFile | tab]\n\
+             Usage:\n\
+             tab\tSwitch current handle to the next opened file and seek to its base.\n\
+             Command: [prevFile]\n\
+             Usage:\n\
+             prevFile\tSwitch current handle to the previous opened file and seek to its base.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut next = NextFile;
+        let mut prev = PrevFile;
+        next.run(&mut core, &[]);
+        let perm = IoMode::READ | IoMode::WRITE;
+        core.io.open_at("malloc://0x10", perm, 0x100).unwrap();
+        // single file is a no-op.
+        next.run(&mut core, &[]);
+        assert_eq!(core.get_loc(), 0);
+        assert_eq!(core.rel_hndl, None);
+        core.io.open_at("malloc://0x10", perm, 0x200).unwrap();
+        core.io.open_at("malloc://0x10", perm, 0x300).unwrap();
+        core.set_loc(0x105);
+        next.run(&mut core, &[]);
+        assert_eq!((core.rel_hndl, core.get_loc()), (Some(1), 0x200));
+        next.run(&mut core, &[]);
+        assert_eq!((core.rel_hndl, core.get_loc()), (Some(2), 0x300));
+        next.run(&mut core, &[]);
+        assert_eq!((core.rel_hndl, core.get_loc()), (Some(0), 0x100));
+        prev.run(&mut core, &[]);
+        assert_eq!((core.rel_hndl, core.get_loc()), (Some(2), 0x300));
+        prev.run(&mut core, &[]);
+        assert_eq!((core.rel_hndl, core.get_loc()), (Some(1), 0x200));
+        core.run("seek", &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x300);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "No files are opened.\n");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_cycle_vir() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut next = NextFile;
+        let perm = IoMode::READ | IoMode::WRITE;
+        core.io.open_at("malloc://0x10", perm, 0x100).unwrap();
+        core.io.open_at("malloc://0x10", perm, 0x200).unwrap();
+        core.io.map(0x100, 0x5000, 0x10).unwrap();
+        core.mode = AddrMode::Vir;
+        core.set_loc(0x5000);
+        next.run(&mut core, &[]);
+        assert_eq!(core.get_loc(), 0x5000);
+        next.run(&mut core, &["x".to_owned()]);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to switch file\n\
+             Base of file with handle 1 is not mapped.\n\
+             Arguments Error: Expected 0 argument(s), found 1.\n"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! evaluate arithmetic expressions.
+
+use crate::core::Core;
+use crate::helper::{addr_term, error_msg};
+use crate::Cmd;
+use std::io::Write;
+
+// binary operators ordered from the longest so that `<<` is never read as `<`.
+const OPERATORS: &[&str] = &["<<", ">>", "+", "-", "*", "/", "%", "&", "|", "^"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token<'a> {
+    Num(u64),
+    Op(&'a str),
+    Open,
+    Close,
+}
+
+fn precedence(op: &str) -> u8 {
+    match op {
+        "|" => 1,
+        "^" => 2,
+        "&" => 3,
+        "<<" | ">>" => 4,
+        "+" | "-" => 5,
+        _ => 6,
+    }
+}
+
+fn apply(op: &str, lhs: u64, rhs: u64) -> Result<u64, String> {
+    let result = match op {
+        "+" => lhs.checked_add(rhs),
+        "-" => lhs.checked_sub(rhs),
+        "*" => lhs.checked_mul(rhs),
+        "/" | "%" if rhs == 0 => return Err("Division by zero.".to_owned()),
+        "/" => Some(lhs / rhs),
+        "%" => Some(lhs % rhs),
+        "<<" => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs)),
+        ">>" => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shr(rhs)),
+        "&" => Some(lhs & rhs),
+        "|" => Some(lhs | rhs),
+        _ => Some(lhs ^ rhs),
+    };
+    result.ok_or_else(|| format!("Arithmetic overflow in `{lhs} {op} {rhs}`."))
+}
+
+fn tokenize<'a>(core: &Core, expr: &'a str) -> Result<Vec<Token<'a>>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = expr.trim_start();
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('(') {
+            tokens.push(Token::Open);
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix(')') {
+            tokens.push(Token::Close);
+            rest = r;
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(&rest[..op.len()]));
+            rest = &rest[op.len()..];
+        } else {
+            // operands are numbers, flags, `$$` or `$`, they end at the next operator.
+            let end = rest
+                .find(|c: char| c.is_whitespace() || "()<>+-*/%&|^".contains(c))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Num(addr_term(core, &rest[..end])?));
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<Token<'_>> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token
+    }
+
+    fn primary(&mut self) -> Result<u64, String> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(n),
+            Some(Token::Open) => {
+                let n = self.expr(1)?;
+                match self.next() {
+                    Some(Token::Close) => Ok(n),
+                    _ => Err("Missing `)`.".to_owned()),
+                }
+            }
+            _ => Err("Missing operand.".to_owned()),
+        }
+    }
+
+    // precedence climbing, all operators are left associative.
+    fn expr(&mut self, min_precedence: u8) -> Result<u64, String> {
+        let mut lhs = self.primary()?;
+        while let Some(Token::Op(op)) = self.tokens.get(self.pos).copied() {
+            let prec = precedence(op);
+            if prec < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.expr(prec + 1)?;
+            lhs = apply(op, lhs, rhs)?;
+        }
+        Ok(lhs)
+    }
+}
+
+/// Evaluate expression of numbers, flags, `$$` and `$` joined by `+ - * / % << >> & | ^` and
+/// grouped by parentheses, operators have the same precedence as in C.
+pub fn str_to_expr(core: &Core, expr: &str) -> Result<u64, String> {
+    let mut parser = Parser {
+        tokens: tokenize(core, expr)?,
+        pos: 0,
+    };
+    let result = parser.expr(1)?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(result),
+        Some(Token::Close) => Err("Unexpected `)`.".to_owned()),
+        Some(_) => Err("Missing operator.".to_owned()),
+    }
+}
+
+#[derive(Default)]
+pub struct Calc;
+
+impl Cmd for Calc {
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        match str_to_expr(core, &args.join(" ")) {
+            Ok(n) => writeln!(
+                core.stdout,
+                "hex\t0x{n:x}\ndec\t{n}\noct\t0o{n:o}\nbin\t0b{n:b}"
+            )
+            .unwrap(),
+            Err(e) => error_msg(core, "Failed to evaluate expression", &e),
+        }
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["eval", "calc"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "[expr]",
+            "Print [expr] made of numbers, flags, $$, $, + - * / % << >> & | ^ and parentheses in hex, decimal, octal and binary.",
+        )]
+    }
+}
+
+#[cfg(test)]
+mod test_calc {
+    use super::*;
+    use crate::{writer::Writer, CmdOps};
+
+    #[test]
+    fn test_docs() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        Calc.help(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Commands: [calc | eval]\n\
+             Usage:\n\
+             eval [expr]\tPrint [expr] made of numbers, flags, $$, $, + - * / % << >> & | ^ and parentheses in hex, decimal, octal and binary.\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_str_to_expr() {
+        let mut core = Core::new_no_colors();
+        assert_eq!(str_to_expr(&core, "0x1000 + 0x20 * 2").unwrap(), 0x1040);
+        assert_eq!(str_to_expr(&core, "(0x1000 + 0x20) * 2").unwrap(), 0x2040);
+        assert_eq!(str_to_expr(&core, "10-4-3").unwrap(), 3);
+        assert_eq!(str_to_expr(&core, "100/10/5").unwrap(), 2);
+        assert_eq!(str_to_expr(&core, "1 << 4 | 1").unwrap(), 0x11);
+        assert_eq!(str_to_expr(&core, "0xff & 0xf0 ^ 0x30 >> 4").unwrap(), 0xf3);
+        assert_eq!(str_to_expr(&core, "1+2*3%4").unwrap(), 3);
+        assert_eq!(str_to_expr(&core, "((2))").unwrap(), 2);
+        assert_eq!(str_to_expr(&core, "2k + 'a'").unwrap(), 0x861);
+        core.set_loc(0x100);
+        core.flags.insert("main".to_owned(), 0x4000);
+        assert_eq!(str_to_expr(&core, "main+($$-0x10)*2").unwrap(), 0x41e0);
+    }
+
+    #[test]
+    fn test_str_to_expr_errors() {
+        let core = Core::new_no_colors();
+        let errors = [
+            ("", "Missing operand."),
+            ("1 +", "Missing operand."),
+            ("* 2", "Missing operand."),
+            ("(1 + 2", "Missing `)`."),
+            ("1 + 2)", "Unexpected `)`."),
+            ("1 (2)", "Missing operator."),
+            ("1 / (2 - 2)", "Division by zero."),
+            ("1 % 0", "Division by zero."),
+            ("1 - 2", "Arithmetic overflow in `1 - 2`."),
+            ("1 << 64", "Arithmetic overflow in `1 << 64`."),
+            (
+                "0xffffffffffffffff * 2",
+                "Arithmetic overflow in `18446744073709551615 * 2`.",
+            ),
+            ("main", "invalid digit found in string"),
+        ];
+        for (expr, err) in errors {
+            assert_eq!(str_to_expr(&core, expr).unwrap_err(), err, "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_calc() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core.run(
+            "eval",
+            &["0x10".to_owned(), "+".to_owned(), "2*3".to_owned()],
+        );
+        core.run("calc", &["1/0".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "hex\t0x16\ndec\t22\noct\t0o26\nbin\t0b10110\n"
+        );
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to evaluate expression\nDivision by zero.\n"
+        );
+    }
+}
@@ -1,16 +1,21 @@
 //! Utility commands.
+mod calc;
 mod env;
 mod project;
 mod quit;
 
+use self::calc::Calc;
 use self::env::{Environment, EnvironmentHelp, EnvironmentReset};
+pub use self::project::{autoload, autosave, Autosaver};
 use self::project::{Load, Save};
 pub use self::quit::Quit;
 use crate::core::Core;
 
 pub fn register_utils(core: &mut Core) {
     core.add_command(Quit);
-    core.add_command(Save);
+    core.add_command(Calc);
+    let save = Save::new(core);
+    core.add_command(save);
     core.add_command(Load);
     core.add_command(Environment);
     core.add_command(EnvironmentReset);
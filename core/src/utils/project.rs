@@ -2,36 +2,227 @@
 
 use crate::core::Core;
 use crate::helper::{error_msg, expect};
+use crate::loc::History;
 use crate::Cmd;
+use alloc::sync::Arc;
 use core::mem;
+use core::time::Duration;
 use flate2::write::{ZlibDecoder, ZlibEncoder};
 use flate2::Compression;
+use parking_lot::Mutex;
 use serde::Deserialize;
 use std::fs::{self, File};
 use std::io::prelude::*;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
 
 #[derive(Default)]
 pub struct Save;
 
+impl Save {
+    pub fn new(core: &mut Core) -> Self {
+        let env = core.env.clone();
+        let mut env = env.write();
+        env.add_str(
+            "prj.autosave",
+            "",
+            "Path where the project is saved on exit, empty string disables autosave",
+        )
+        .unwrap();
+        env.add_u64(
+            "prj.autosaveInterval",
+            0,
+            "Also autosave in the background once this many seconds passed since last autosave, 0 disables it",
+        )
+        .unwrap();
+        env.add_bool(
+            "prj.autoload",
+            false,
+            "Load the autosaved project on startup instead of only reporting that it exists",
+        )
+        .unwrap();
+        Self
+    }
+}
+
+type SaveError = (&'static str, String);
+
+fn serialize_project(core: &Core) -> Result<Vec<u8>, SaveError> {
+    serde_cbor::to_vec(&core).map_err(|e| ("Failed to serialize project", e.to_string()))
+}
+
+fn write_project(path: &str, data: &[u8]) -> Result<(), SaveError> {
+    let mut file = File::create(path).map_err(|e| ("Failed to open file", e.to_string()))?;
+    let mut compressor = ZlibEncoder::new(Vec::new(), Compression::default());
+    compressor.write_all(data).unwrap();
+    let compressed_data = compressor.finish().unwrap();
+    file.write_all(&compressed_data)
+        .map_err(|e| ("Failed to save project", e.to_string()))
+}
+
+fn save_project(core: &Core, path: &str) -> Result<(), SaveError> {
+    write_project(path, &serialize_project(core)?)
+}
+
+// Project and seek history taken from the core, to be written by the autosave thread.
+struct Snapshot {
+    project: Option<(String, Vec<u8>)>,
+    history: Option<(String, History)>,
+    interval: Duration,
+}
+
+impl Snapshot {
+    fn save(self) -> Vec<SaveError> {
+        let mut errors = Vec::new();
+        if let Some((path, data)) = self.project {
+            errors.extend(write_project(&path, &data).err());
+        }
+        if let Some((path, history)) = self.history {
+            if let Err(e) = history.save(Path::new(&path)) {
+                errors.push(("Failed to save seek history", e.to_string()));
+            }
+        }
+        errors
+    }
+}
+
+// Keep the latest snapshot received and save it once its interval passed since the last save.
+fn autosave_loop(receiver: &Receiver<Snapshot>, errors: &Mutex<Vec<SaveError>>) {
+    let mut pending: Option<Snapshot> = None;
+    let mut last_save = Instant::now();
+    loop {
+        let received = match &pending {
+            Some(snapshot) => {
+                receiver.recv_timeout(snapshot.interval.saturating_sub(last_save.elapsed()))
+            }
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(snapshot) => pending = Some(snapshot),
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+        if let Some(snapshot) = pending.take_if(|s| last_save.elapsed() >= s.interval) {
+            errors.lock().extend(snapshot.save());
+            last_save = Instant::now();
+        }
+    }
+}
+
+/// Periodic autosave every `prj.autosaveInterval` seconds. Compressing and writing the project
+/// happen on a background thread, so the only work left to the thread that owns [`Core`] is
+/// serializing it in [`Autosaver::update`]. The latest snapshot is saved once the interval
+/// passes even if no command runs in the meantime.
+pub struct Autosaver {
+    sender: Option<Sender<Snapshot>>,
+    errors: Arc<Mutex<Vec<SaveError>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Default for Autosaver {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let errors = Arc::default();
+        let thread_errors = Arc::clone(&errors);
+        let thread = thread::spawn(move || autosave_loop(&receiver, &thread_errors));
+        Autosaver {
+            sender: Some(sender),
+            errors,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Autosaver {
+    /// Report errors of earlier saves then hand a snapshot of `core` over to the background
+    /// thread, nothing is saved if `prj.autosaveInterval` is 0.
+    pub fn update(&mut self, core: &mut Core) {
+        for (title, msg) in mem::take(&mut *self.errors.lock()) {
+            error_msg(core, title, &msg);
+        }
+        let env = core.env.read();
+        let interval = env.get_u64("prj.autosaveInterval").unwrap();
+        let path = env.get_str("prj.autosave").unwrap().to_owned();
+        let history_path = env.get_str("core.historyFile").unwrap().to_owned();
+        drop(env);
+        if interval == 0 || path.is_empty() && history_path.is_empty() {
+            return;
+        }
+        let project = if path.is_empty() {
+            None
+        } else {
+            match serialize_project(core) {
+                Ok(data) => Some((path, data)),
+                Err((title, msg)) => return error_msg(core, title, &msg),
+            }
+        };
+        let history = (!history_path.is_empty()).then(|| (history_path, core.history.clone()));
+        let snapshot = Snapshot {
+            project,
+            history,
+            interval: Duration::from_secs(interval),
+        };
+        if let Some(sender) = &self.sender {
+            sender.send(snapshot).unwrap();
+        }
+    }
+}
+
+impl Drop for Autosaver {
+    // wait for the save in progress if any, snapshots that are not saved yet are dropped.
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            thread.join().unwrap();
+        }
+    }
+}
+
+/// Save the project into the path stored in `prj.autosave` if it is set, along with seek
+/// history if `core.historyFile` is set.
+pub fn autosave(core: &mut Core) {
+    History::autosave(core);
+    let path = core.env.read().get_str("prj.autosave").unwrap().to_owned();
+    if path.is_empty() {
+        return;
+    }
+    if let Err((title, msg)) = save_project(core, &path) {
+        error_msg(core, title, &msg);
+    }
+}
+
+/// Load seek history from `core.historyFile`, then load the project saved in `prj.autosave`
+/// if it exists and `prj.autoload` is set, otherwise just let the user know that it exists.
+pub fn autoload(core: &mut Core) {
+    History::autoload(core);
+    let env = core.env.read();
+    let path = env.get_str("prj.autosave").unwrap().to_owned();
+    let load = env.get_bool("prj.autoload").unwrap();
+    drop(env);
+    if path.is_empty() || !Path::new(&path).exists() {
+        return;
+    }
+    if load {
+        core.run("load", &[path]);
+    } else {
+        writeln!(
+            core.stdout,
+            "Autosaved project found, use `load {path}` to restore it."
+        )
+        .unwrap();
+    }
+}
+
 impl Cmd for Save {
     fn run(&mut self, core: &mut Core, args: &[String]) {
         if args.len() != 1 {
             expect(core, args.len() as u64, 1);
             return;
         }
-        let data = match serde_cbor::to_vec(&core) {
-            Ok(data) => data,
-            Err(e) => return error_msg(core, "Failed to serialize project", &e.to_string()),
-        };
-        let mut file = match File::create(&args[0]) {
-            Ok(file) => file,
-            Err(e) => return error_msg(core, "Failed to open file", &e.to_string()),
-        };
-        let mut compressor = ZlibEncoder::new(Vec::new(), Compression::default());
-        compressor.write_all(&data).unwrap();
-        let compressed_data = compressor.finish().unwrap();
-        if let Err(e) = file.write_all(&compressed_data) {
-            error_msg(core, "Failed to save project", &e.to_string());
+        if let Err((title, msg)) = save_project(core, &args[0]) {
+            error_msg(core, title, &msg);
         }
     }
     fn commands(&self) -> &'static [&'static str] {
@@ -73,6 +264,7 @@ impl Cmd for Load {
         mem::swap(&mut core.stdout, &mut core2.stdout);
         mem::swap(&mut core.stderr, &mut core2.stderr);
         mem::swap(&mut core.env, &mut core2.env);
+        core2.errors = core.errors;
         core2.set_commands(core.commands());
         *core = core2;
     }
@@ -92,6 +284,7 @@ mod test_project {
     use crate::{writer::*, CmdOps};
     use rair_io::*;
     use std::fs;
+    use test_file::{operate_on_dir, operate_on_file, operate_on_files};
     #[test]
     fn test_project_help() {
         let mut core = Core::new_no_colors();
@@ -133,13 +326,207 @@ mod test_project {
         core.run("maps", &[]);
         assert_eq!(
             core.stdout.utf8_string().unwrap(),
-            "Handle\tStart address\tsize\t\tPermissions\tURI\n\
-             0\t0x00000000\t0x00000500\tWRITE | READ\tmalloc://0x500\n\
-             1\t0x00031000\t0x00001337\tWRITE | READ\tmalloc://0x1337\n\
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             0\t0x00000000\t0x00000500\tWRITE | READ\tMalloc\t\tmalloc://0x500\n\
+             1\t0x00031000\t0x00001337\tWRITE | READ\tMalloc\t\tmalloc://0x1337\n\
              Virtual Address     Physical Address    Size\n\
              0xfff31000          0x31000             0x337\n"
         );
         assert_eq!(core.stderr.utf8_string().unwrap(), "");
         fs::remove_file("rair_project").unwrap();
     }
+
+    #[test]
+    fn test_project_history() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut load = Load;
+        let mut save = Save;
+        core.run("seek", &["0x10".to_owned()]);
+        core.run("seek", &["0x20".to_owned()]);
+        save.run(&mut core, &["rair_project_history".to_owned()]);
+        core.run("seek", &["0x30".to_owned()]);
+        load.run(&mut core, &["rair_project_history".to_owned()]);
+        assert_eq!(core.get_loc(), 0x20);
+        core.run("seek", &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x10);
+        core.run("seek", &["-".to_owned()]);
+        assert_eq!(core.get_loc(), 0x0);
+        core.run("seek", &["+".to_owned()]);
+        assert_eq!(core.get_loc(), 0x10);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+        fs::remove_file("rair_project_history").unwrap();
+    }
+
+    #[test]
+    fn test_project_flags() {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let mut load = Load;
+        let mut save = Save;
+        core.run("f", &["main".to_owned(), "0x10".to_owned()]);
+        save.run(&mut core, &["rair_project_flags".to_owned()]);
+        core.run("f", &["-main".to_owned()]);
+        load.run(&mut core, &["rair_project_flags".to_owned()]);
+        core.run("s", &["main".to_owned()]);
+        assert_eq!(core.get_loc(), 0x10);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+        fs::remove_file("rair_project_flags").unwrap();
+    }
+
+    fn test_project_plugin_cb(paths: &[&Path]) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let uri = paths[0].to_string_lossy().to_string();
+        let project = [paths[1].to_string_lossy().to_string()];
+        // no plugin accepts a plain path to base64 data on its own.
+        core.run(
+            "o",
+            &["--plugin".to_owned(), "base64".to_owned(), uri.clone()],
+        );
+        core.run("save", &project);
+        core.io.close_all();
+        core.run("load", &project);
+        core.run("files", &[]);
+        core.run("pb", &["16".to_owned(), "3".to_owned()]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            format!(
+                "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+                 0\t0x00000000\t0x00000003\tREAD\t\tBase64\t\t{uri}\n\
+                 546865\n"
+            )
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    #[test]
+    fn test_project_plugin() {
+        operate_on_files(&test_project_plugin_cb, &[b"VGhl", b""]);
+    }
+
+    fn test_autosave_cb(path: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let uri = path.to_str().unwrap().to_owned();
+        // autosave is disabled by default
+        autosave(&mut core);
+        assert_eq!(fs::read(path).unwrap(), b"");
+        let env = core.env.clone();
+        env.write()
+            .set_str("prj.autosave", &uri, &mut core)
+            .unwrap();
+        core.io
+            .open("malloc://0x500", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.set_loc(0x50);
+        autosave(&mut core);
+        core.io.close_all();
+        core.set_loc(0);
+        autoload(&mut core);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            format!("Autosaved project found, use `load {uri}` to restore it.\n")
+        );
+        core.stdout = Writer::new_buf();
+        env.write()
+            .set_bool("prj.autoload", true, &mut core)
+            .unwrap();
+        autoload(&mut core);
+        assert_eq!(core.get_loc(), 0x50);
+        core.run("files", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            "Handle\tStart address\tsize\t\tPermissions\tPlugin\t\tURI\n\
+             0\t0x00000000\t0x00000500\tWRITE | READ\tMalloc\t\tmalloc://0x500\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    #[test]
+    fn test_autosave() {
+        operate_on_file(&test_autosave_cb, b"");
+    }
+
+    // wait for the autosave thread to do something, it is given plenty of time.
+    fn wait_until(done: impl Fn() -> bool) {
+        let start = Instant::now();
+        while !done() {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    fn test_autosaver_cb(dir: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let project = dir.join("project");
+        let history = dir.join("history");
+        let env = core.env.clone();
+        let mut env_lock = env.write();
+        env_lock
+            .set_str("prj.autosave", &project.to_string_lossy(), &mut core)
+            .unwrap();
+        env_lock
+            .set_str("core.historyFile", &history.to_string_lossy(), &mut core)
+            .unwrap();
+        env_lock
+            .set_u64("prj.autosaveInterval", 1, &mut core)
+            .unwrap();
+        drop(env_lock);
+        core.io
+            .open("malloc://0x500", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.set_loc(0x50);
+        let mut autosaver = Autosaver::default();
+        autosaver.update(&mut core);
+        // nothing is saved before the interval passes, then it is saved without more updates.
+        assert!(!project.exists());
+        wait_until(|| project.exists() && history.exists());
+        drop(autosaver);
+        core.io.close_all();
+        core.set_loc(0);
+        core.run("load", &[project.to_string_lossy().to_string()]);
+        assert_eq!(core.get_loc(), 0x50);
+        assert_eq!(core.io.uri_iter().count(), 1);
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+    #[test]
+    fn test_autosaver() {
+        operate_on_dir(&test_autosaver_cb, &[]);
+    }
+
+    fn test_autosaver_errors_cb(dir: &Path) {
+        let mut core = Core::new_no_colors();
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        let project = dir.join("missing").join("project");
+        let env = core.env.clone();
+        env.write()
+            .set_str("prj.autosave", &project.to_string_lossy(), &mut core)
+            .unwrap();
+        let mut autosaver = Autosaver::default();
+        // disabled unless there is an interval.
+        autosaver.update(&mut core);
+        env.write()
+            .set_u64("prj.autosaveInterval", 1, &mut core)
+            .unwrap();
+        autosaver.update(&mut core);
+        wait_until(|| !autosaver.errors.lock().is_empty());
+        // errors are reported on the next update.
+        autosaver.update(&mut core);
+        assert_eq!(
+            core.stderr.utf8_string().unwrap(),
+            "Error: Failed to open file\nNo such file or directory (os error 2)\n"
+        );
+    }
+    #[test]
+    fn test_autosaver_errors() {
+        operate_on_dir(&test_autosaver_errors_cb, &[]);
+    }
 }
@@ -1,5 +1,6 @@
 //! Quit the current project.
 
+use super::project::autosave;
 use crate::{core::Core, Cmd};
 use std::process;
 
@@ -7,7 +8,8 @@ use std::process;
 pub struct Quit;
 
 impl Cmd for Quit {
-    fn run(&mut self, _core: &mut Core, _args: &[String]) {
+    fn run(&mut self, core: &mut Core, _args: &[String]) {
+        autosave(core);
         process::exit(0);
     }
     fn commands(&self) -> &'static [&'static str] {
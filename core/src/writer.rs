@@ -1,7 +1,9 @@
 //! Abstract implementation for `Io::Write` stream
 
+use std::fs::{self, File};
 use std::io;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
 /// This union acts as thin abstraction layer over over input streams.
 /// Its goal is to allow allow seamingless redirection of output to
@@ -12,6 +14,10 @@ pub enum Writer {
     Write(Box<dyn Write + Sync + Send>),
     #[doc(hidden)]
     Bytes(Vec<u8>),
+    #[doc(hidden)]
+    File(File, PathBuf),
+    #[doc(hidden)]
+    Tee(Box<Writer>, Box<Writer>),
 }
 
 impl Write for Writer {
@@ -19,12 +25,23 @@ impl Write for Writer {
         match self {
             Writer::Write(writer) => writer.write(buf),
             Writer::Bytes(bytes) => bytes.write(buf),
+            Writer::File(file, _) => file.write(buf),
+            Writer::Tee(first, second) => {
+                first.write_all(buf)?;
+                second.write_all(buf)?;
+                Ok(buf.len())
+            }
         }
     }
     fn flush(&mut self) -> io::Result<()> {
         match self {
             Writer::Write(writer) => writer.flush(),
             Writer::Bytes(bytes) => bytes.flush(),
+            Writer::File(file, _) => file.flush(),
+            Writer::Tee(first, second) => {
+                first.flush()?;
+                second.flush()
+            }
         }
     }
 }
@@ -45,30 +62,41 @@ impl Writer {
     pub fn new_buf() -> Self {
         Writer::Bytes(Vec::new())
     }
+    /// Returns a new [Writer] that writes to the file at `path`, the file
+    /// is created if it does not exist and truncated if it does.
+    pub fn new_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        Ok(Writer::File(File::create(path)?, path.to_path_buf()))
+    }
+
+    /// Returns a new [Writer] that writes everything to both `first` and
+    /// `second`, for example to log a session to a file while printing it.
+    #[must_use]
+    pub fn new_tee(first: Writer, second: Writer) -> Self {
+        Writer::Tee(Box::new(first), Box::new(second))
+    }
+
     /// This function consumes the [Writer] object, it returns the
-    /// data stored there if the object is buffer based.
+    /// data stored there if the object is buffer or file based. For
+    /// tee [Writer]s the data stored in the first one is returned.
     #[must_use]
     pub fn bytes(self) -> Option<Vec<u8>> {
-        if let Writer::Bytes(b) = self {
-            Some(b)
-        } else {
-            None
+        match self {
+            Writer::Bytes(b) => Some(b),
+            Writer::File(mut file, path) => {
+                file.flush().ok()?;
+                fs::read(path).ok()
+            }
+            Writer::Tee(first, _) => first.bytes(),
+            Writer::Write(_) => None,
         }
     }
     /// This function consumes the [Writer] object, it returns UTF-8
-    /// String representation of the data stored there if it is buffer.
-    /// based and the buffer holds UTF-8 data.
+    /// String representation of the data stored there if it is buffer
+    /// or file based and the data is valid UTF-8.
     #[must_use]
     pub fn utf8_string(self) -> Option<String> {
-        if let Writer::Bytes(b) = self {
-            if let Ok(s) = String::from_utf8(b) {
-                Some(s)
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        String::from_utf8(self.bytes()?).ok()
     }
     /// This function returns a reference to the data stored
     /// in respective [Writer] if the object is buffer based.
@@ -96,6 +124,7 @@ impl Writer {
 #[cfg(test)]
 mod writer_test {
     use super::*;
+    use test_file::{operate_on_file, DATA};
     #[test]
     fn test_writer_buffer() {
         let mut w = Writer::new_buf();
@@ -119,4 +148,40 @@ mod writer_test {
         w = Writer::new_write(Box::new(io::stdout()));
         assert_eq!(w.bytes(), None);
     }
+
+    fn writer_file_cb(path: &Path) {
+        let mut w = Writer::new_file(path).unwrap();
+        write!(w, "Testing write file").unwrap();
+        assert_eq!(w.bytes_ref(), None);
+        assert_eq!(w.bytes_mut(), None);
+        assert_eq!(w.utf8_string().unwrap(), "Testing write file");
+        w = Writer::new_file(path).unwrap();
+        w.write_all(&[0xff, 0xfe]).unwrap();
+        assert_eq!(w.bytes().unwrap(), [0xff, 0xfe]);
+        w = Writer::new_file(path).unwrap();
+        w.write_all(&[0xff, 0xfe]).unwrap();
+        assert_eq!(w.utf8_string(), None);
+    }
+    #[test]
+    fn test_writer_file() {
+        operate_on_file(&writer_file_cb, DATA);
+        assert!(Writer::new_file("/").is_err());
+    }
+
+    fn writer_tee_cb(path: &Path) {
+        let mut w = Writer::new_tee(Writer::new_file(path).unwrap(), Writer::new_buf());
+        write!(w, "Testing write tee").unwrap();
+        w.flush().unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "Testing write tee");
+        if let Writer::Tee(_, second) = &w {
+            assert_eq!(second.bytes_ref().unwrap(), b"Testing write tee");
+        }
+        assert_eq!(w.utf8_string().unwrap(), "Testing write tee");
+        w = Writer::new_tee(Writer::new_write(Box::new(io::stdout())), Writer::new_buf());
+        assert_eq!(w.bytes(), None);
+    }
+    #[test]
+    fn test_writer_tee() {
+        operate_on_file(&writer_tee_cb, DATA);
+    }
 }
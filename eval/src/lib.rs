@@ -1,6 +1,9 @@
+mod source;
+
 use core::mem;
-use rair_cmd::{Argument, Cmd, ParseTree, RedPipe};
-use rair_core::{Core, Writer};
+use rair_cmd::{Argument, Cmd, Loc, ParseTree, RedPipe};
+use rair_core::{error_msg, str_to_addr, Core, Writer};
+pub use source::Source;
 use std::{
     fs::{File, OpenOptions},
     io::{prelude::*, Write},
@@ -8,55 +11,116 @@ use std::{
 };
 
 pub fn rair_eval(core: &mut Core, line: &str) {
-    match ParseTree::construct(line) {
-        Ok(tree) => evaluate(core, tree),
-        Err(e) => writeln!(core.stderr, "{e}").unwrap(),
+    eval_line(core, line, 0);
+}
+
+/// Register commands that need to evaluate command lines on their own.
+pub fn register_eval(core: &mut Core) {
+    core.add_command(Source);
+}
+
+// Report error `e` that is not printed by `error_msg` so it still counts as a failure.
+fn report_error(core: &mut Core, e: &str) {
+    core.count_error();
+    writeln!(core.stderr, "{e}").unwrap();
+}
+
+// Evaluate all statements in `line` as part of a script nested `depth` levels deep, returns
+// *false* if any of them failed.
+fn eval_line(core: &mut Core, line: &str, depth: usize) -> bool {
+    let trees = match ParseTree::construct_many(line) {
+        Ok(trees) => trees,
+        Err(e) => {
+            report_error(core, &e.to_string());
+            return false;
+        }
+    };
+    let stop_on_error = core.env.read().get_bool("core.stopOnError").unwrap();
+    let mut success = true;
+    for tree in trees {
+        // a command failed if it reported any error, warnings written to stderr don't count.
+        let errors = core.error_count();
+        evaluate(core, tree, depth);
+        success &= core.error_count() == errors;
+        if stop_on_error && !success {
+            break;
+        }
     }
+    success
 }
 
-fn evaluate(core: &mut Core, tree: ParseTree) {
+fn evaluate(core: &mut Core, tree: ParseTree, depth: usize) {
     match tree {
         ParseTree::Help(help) => core.help(&help.command),
-        ParseTree::Cmd(cmd) => run_cmd(core, cmd),
+        ParseTree::Cmd(cmd) => run_cmd(core, cmd, depth),
         ParseTree::HelpAll => core.help_all(),
         ParseTree::NewLine | ParseTree::Comment => (),
     }
 }
 
-fn run_cmd(core: &mut Core, cmd: Cmd) {
+// Run `command` the same way [`Core::run`] does.
+fn dispatch(core: &mut Core, command: &str, args: &[String], depth: usize) {
+    // the `source` command running the current script is locked by `Core::run` until the
+    // script ends, so scripts sourced from scripts are run here directly.
+    if depth > 0 && Source::is_source(command) {
+        Source::source(core, args, depth + 1);
+    } else {
+        core.run(command, args);
+    }
+}
+
+fn run_cmd(core: &mut Core, cmd: Cmd, depth: usize) {
     let mut args = Vec::new();
     //process args
     for arg in cmd.args {
-        match eval_arg(core, arg) {
+        match eval_arg(core, arg, depth) {
             Ok(arg) => args.push(arg),
-            Err(e) => return writeln!(core.stderr, "{e}").unwrap(),
+            Err(e) => return report_error(core, &e),
         }
     }
+    // process location
+    let loc = match cmd.loc {
+        Some(Loc::Addr(at)) => Some(at),
+        Some(Loc::Expr(expr)) => match str_to_addr(core, &expr) {
+            Ok(at) => Some(at),
+            Err(e) => return error_msg(core, "Failed to evaluate address", &e),
+        },
+        None => None,
+        Some(loc) => {
+            let err_str = format!("Unsupported location `{loc:?}`.");
+            return error_msg(core, "Failed to evaluate address", &err_str);
+        }
+    };
     // process redirections or pipes
     let mut stdout: Option<Writer> = None;
     let mut child: Option<Child> = None;
     match *cmd.red_pipe {
-        RedPipe::Redirect(arg) => match create_redirect(core, *arg) {
+        RedPipe::Redirect(arg) => match create_redirect(core, *arg, depth) {
             Ok(out) => stdout = Some(mem::replace(&mut core.stdout, out)),
-            Err(e) => return writeln!(core.stderr, "{e}").unwrap(),
+            Err(e) => return report_error(core, &e),
         },
-        RedPipe::RedirectCat(arg) => match create_redirect_cat(core, *arg) {
+        RedPipe::RedirectCat(arg) => match create_redirect_cat(core, *arg, depth) {
             Ok(out) => stdout = Some(mem::replace(&mut core.stdout, out)),
-            Err(e) => return writeln!(core.stderr, "{e}").unwrap(),
+            Err(e) => return report_error(core, &e),
         },
-        RedPipe::Pipe(arg) => match create_pipe(core, arg) {
+        RedPipe::Pipe(arg) => match create_pipe(core, arg, depth) {
             Ok((process, writer)) => {
                 child = Some(process);
                 stdout = Some(mem::replace(&mut core.stdout, writer));
             }
-            Err(e) => return writeln!(core.stderr, "{e}").unwrap(),
+            Err(e) => return report_error(core, &e),
         },
         RedPipe::None => (),
     }
     // execute
-    match cmd.loc {
-        Some(at) => core.run_at(&cmd.command, &args, at),
-        None => core.run(&cmd.command, &args),
+    match loc {
+        Some(at) => {
+            let old_loc = core.get_loc();
+            core.set_loc(at);
+            dispatch(core, &cmd.command, &args, depth);
+            core.set_loc(old_loc);
+        }
+        None => dispatch(core, &cmd.command, &args, depth),
     }
     //if we have a pipe feed into the pipe ..
     if let Some(process) = child {
@@ -75,16 +139,16 @@ fn run_cmd(core: &mut Core, cmd: Cmd) {
     }
 }
 
-fn create_redirect(core: &mut Core, arg: Argument) -> Result<Writer, String> {
-    let file_name = eval_arg(core, arg)?;
+fn create_redirect(core: &mut Core, arg: Argument, depth: usize) -> Result<Writer, String> {
+    let file_name = eval_arg(core, arg, depth)?;
     match File::create(file_name) {
         Ok(f) => Ok(Writer::new_write(Box::new(f))),
         Err(e) => Err(e.to_string()),
     }
 }
 
-fn create_redirect_cat(core: &mut Core, arg: Argument) -> Result<Writer, String> {
-    let file_name = eval_arg(core, arg)?;
+fn create_redirect_cat(core: &mut Core, arg: Argument, depth: usize) -> Result<Writer, String> {
+    let file_name = eval_arg(core, arg, depth)?;
     match OpenOptions::new().append(true).open(file_name) {
         Ok(f) => Ok(Writer::new_write(Box::new(f))),
         Err(e) => Err(e.to_string()),
@@ -94,10 +158,11 @@ fn create_redirect_cat(core: &mut Core, arg: Argument) -> Result<Writer, String>
 fn create_pipe(
     core: &mut Core,
     unprocessed_args: Vec<Argument>,
+    depth: usize,
 ) -> Result<(Child, Writer), String> {
     let mut args = Vec::with_capacity(unprocessed_args.len());
     for arg in unprocessed_args {
-        args.push(eval_arg(core, arg)?);
+        args.push(eval_arg(core, arg, depth)?);
     }
     match Command::new(&args[0])
         .args(&args[1..])
@@ -110,23 +175,23 @@ fn create_pipe(
     }
 }
 
-fn eval_arg(core: &mut Core, arg: Argument) -> Result<String, String> {
+fn eval_arg(core: &mut Core, arg: Argument, depth: usize) -> Result<String, String> {
     match arg {
         Argument::Literal(s) => Ok(s),
         Argument::Err(e) => Err(e.to_string()),
-        Argument::NonLiteral(c) => eval_non_literal_arg(core, c),
+        Argument::NonLiteral(c) => eval_non_literal_arg(core, c, depth),
         _ => unreachable!(),
     }
 }
 
-fn eval_non_literal_arg(core: &mut Core, cmd: Cmd) -> Result<String, String> {
+fn eval_non_literal_arg(core: &mut Core, cmd: Cmd, depth: usize) -> Result<String, String> {
     // change stderr and stdout
     let mut stderr = Writer::new_buf();
     let mut stdout = Writer::new_buf();
     mem::swap(&mut core.stderr, &mut stderr);
     mem::swap(&mut core.stdout, &mut stdout);
     // run command
-    run_cmd(core, cmd);
+    run_cmd(core, cmd, depth);
     // restore stderr and stdout
     mem::swap(&mut core.stderr, &mut stderr);
     mem::swap(&mut core.stdout, &mut stdout);
@@ -0,0 +1,59 @@
+//! command for running rair scripts.
+
+use super::eval_line;
+use rair_core::{error_msg, expect, Cmd, Core};
+use std::fs;
+
+// Scripts sourced from scripts more than this many levels deep are refused.
+const MAX_SCRIPT_DEPTH: usize = 16;
+
+const NAMES: &[&str] = &["source", "es"];
+
+pub struct Source;
+
+impl Source {
+    pub(super) fn is_source(command: &str) -> bool {
+        NAMES.contains(&command)
+    }
+
+    // Run the script given in `args` as script number `depth` in the chain of sourced scripts.
+    pub(super) fn source(core: &mut Core, args: &[String], depth: usize) {
+        if args.len() != 1 {
+            expect(core, args.len() as u64, 1);
+            return;
+        }
+        let path = &args[0];
+        if depth > MAX_SCRIPT_DEPTH {
+            let err_str =
+                format!("Scripts can't be nested more than {MAX_SCRIPT_DEPTH} levels deep.");
+            return error_msg(core, "Failed to run script", &err_str);
+        }
+        let script = match fs::read_to_string(path) {
+            Ok(script) => script,
+            Err(e) => return error_msg(core, "Failed to run script", &e.to_string()),
+        };
+        for (i, line) in script.lines().enumerate() {
+            if !eval_line(core, line, depth) {
+                let err_str = format!("Line {} of {path} failed.", i + 1);
+                return error_msg(core, "Script failed", &err_str);
+            }
+        }
+    }
+}
+
+impl Cmd for Source {
+    fn commands(&self) -> &'static [&'static str] {
+        NAMES
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "[path]",
+            "Run rair commands written in file at [path] line by line, stop at first failing line.",
+        )]
+    }
+
+    fn run(&mut self, core: &mut Core, args: &[String]) {
+        Self::source(core, args, 1);
+    }
+}
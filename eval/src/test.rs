@@ -1,6 +1,10 @@
-use rair_core::{Core, Writer};
+use rair_core::{Cmd, Core, Writer};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use test_file::*;
 
-use super::rair_eval;
+use super::{rair_eval, register_eval};
 
 #[test]
 fn test_newline() {
@@ -71,3 +75,240 @@ fn test_good_command_good_arg() {
     assert!(core.stdout.bytes().unwrap().is_empty());
     assert!(core.stderr.bytes().unwrap().is_empty());
 }
+
+#[test]
+fn test_loc_expr() {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    rair_eval(&mut core, "o rw malloc://0x100");
+    rair_eval(&mut core, "s 0x80");
+    rair_eval(&mut core, "wx ff @ $$-0x10");
+    rair_eval(&mut core, "wx aa @ $-1");
+    assert_eq!(core.get_loc(), 0x80);
+    let mut data = [0; 1];
+    core.io.pread(0x70, &mut data).unwrap();
+    assert_eq!(data, [0xff]);
+    core.io.pread(0xff, &mut data).unwrap();
+    assert_eq!(data, [0xaa]);
+    assert!(core.stdout.bytes().unwrap().is_empty());
+    assert!(core.stderr.bytes().unwrap().is_empty());
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    rair_eval(&mut core, "wx ff @ $$-0x100");
+    assert!(core.stdout.bytes().unwrap().is_empty());
+    assert_eq!(
+        core.stderr.utf8_string().unwrap(),
+        "Error: Failed to evaluate address\nAddress underflow.\n"
+    );
+}
+
+#[test]
+fn test_flag_loc() {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    rair_eval(
+        &mut core,
+        "o rw malloc://0x100; f main 0x80; wx ff @ main+1",
+    );
+    rair_eval(&mut core, "s main");
+    assert_eq!(core.get_loc(), 0x80);
+    let mut data = [0; 2];
+    core.io.pread(0x80, &mut data).unwrap();
+    assert_eq!(data, [0, 0xff]);
+    assert!(core.stdout.bytes().unwrap().is_empty());
+    assert!(core.stderr.bytes().unwrap().is_empty());
+}
+
+#[test]
+fn test_quoted_operators() {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    rair_eval(&mut core, "eval \"(1 << 4 | 1) >> 1\" # ? 1 > 2");
+    assert_eq!(
+        core.stdout.utf8_string().unwrap(),
+        "hex\t0x8\ndec\t8\noct\t0o10\nbin\t0b1000\n"
+    );
+    assert!(core.stderr.bytes().unwrap().is_empty());
+}
+
+#[test]
+fn test_many_commands() {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    rair_eval(
+        &mut core,
+        "o rw malloc://0x20; wx 0102 @ 0x10; s 0x10; px 2 # s 0x0; px 2",
+    );
+    assert_eq!(core.get_loc(), 0x10);
+    assert_eq!(
+        core.stdout.utf8_string().unwrap(),
+        "- offset -  0 1  2 3  4 5  6 7  8 9  A B  C D  E F  0123456789ABCDEF\n\
+         0x00000010 0102                                     ..\n"
+    );
+    assert!(core.stderr.bytes().unwrap().is_empty());
+}
+
+#[test]
+fn test_many_commands_errors() {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    rair_eval(&mut core, "s 0x10; nothing; s +0x10");
+    assert_eq!(core.get_loc(), 0x20);
+    assert!(!core.stderr.bytes().unwrap().is_empty());
+    core.stderr = Writer::new_buf();
+    let env = core.env.clone();
+    env.write()
+        .set_bool("core.stopOnError", true, &mut core)
+        .unwrap();
+    rair_eval(&mut core, "s 0x10; nothing; s +0x10");
+    assert_eq!(core.get_loc(), 0x10);
+    assert_eq!(
+        core.stderr.utf8_string().unwrap(),
+        "Error: Execution failed\nUnknown command 'nothing'.\n"
+    );
+    assert!(core.stdout.bytes().unwrap().is_empty());
+}
+
+// Command that writes a warning to stderr after printing how much was written to stderr before.
+struct Warn;
+
+impl Cmd for Warn {
+    fn commands(&self) -> &'static [&'static str] {
+        &["warn"]
+    }
+
+    fn help_messages(&self) -> &'static [(&'static str, &'static str)] {
+        &[("", "Print a warning.")]
+    }
+
+    fn run(&mut self, core: &mut Core, _: &[String]) {
+        let len = core.stderr.bytes_ref().unwrap().len();
+        writeln!(core.stdout, "{len}").unwrap();
+        writeln!(core.stderr, "Warning: careful.").unwrap();
+    }
+}
+
+#[test]
+fn test_warnings_are_not_errors() {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    core.add_command(Warn);
+    let env = core.env.clone();
+    env.write()
+        .set_bool("core.stopOnError", true, &mut core)
+        .unwrap();
+    rair_eval(&mut core, "warn; s 0x10");
+    assert_eq!(core.get_loc(), 0x10);
+    // errors of earlier commands reach stderr before the next command runs.
+    env.write()
+        .set_bool("core.stopOnError", false, &mut core)
+        .unwrap();
+    rair_eval(&mut core, "nothing; warn");
+    assert_eq!(core.stdout.utf8_string().unwrap(), "0\n69\n");
+    assert_eq!(
+        core.stderr.utf8_string().unwrap(),
+        "Warning: careful.\n\
+         Error: Execution failed\n\
+         Unknown command 'nothing'.\n\
+         Warning: careful.\n"
+    );
+}
+
+fn source_cb(path: &Path) {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    register_eval(&mut core);
+    let path = path.to_string_lossy();
+    rair_eval(&mut core, &format!("source {path}"));
+    assert_eq!(core.get_loc(), 0x10);
+    let mut data = [0; 4];
+    core.io.pread(0x10, &mut data).unwrap();
+    assert_eq!(data, [0xde, 0xad, 0xbe, 0xef]);
+    assert!(core.stdout.bytes().unwrap().is_empty());
+    assert!(core.stderr.bytes().unwrap().is_empty());
+}
+#[test]
+fn test_source() {
+    operate_on_file(
+        &source_cb,
+        b"# open some memory and write to it\n\
+          o rw malloc://0x20\n\
+          \n\
+          s 0x10; wx dead\n\
+          wx beef @ $$+2 # comments are fine here too\n",
+    );
+}
+
+fn nested_cb(paths: &[&Path]) {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    register_eval(&mut core);
+    let (outer, inner) = (paths[0].to_string_lossy(), paths[1].to_string_lossy());
+    fs::write(paths[0], format!("o rw malloc://0x20\nes {inner}\nwx 02")).unwrap();
+    fs::write(paths[1], "s 0x8\nwx 01 @ 0x4").unwrap();
+    rair_eval(&mut core, &format!("es {outer}"));
+    assert_eq!(core.get_loc(), 0x8);
+    let mut data = [0; 8];
+    core.io.pread(0x4, &mut data[..5]).unwrap();
+    assert_eq!(data[..5], [1, 0, 0, 0, 2]);
+    assert!(core.stderr.bytes().unwrap().is_empty());
+
+    // the first failing line is reported by every script that sourced it.
+    core.stderr = Writer::new_buf();
+    fs::write(paths[1], "s 0x10\nnothing\ns 0x18").unwrap();
+    rair_eval(&mut core, &format!("source {outer}"));
+    assert_eq!(core.get_loc(), 0x10);
+    assert_eq!(
+        core.stderr.utf8_string().unwrap(),
+        format!(
+            "Error: Execution failed\n\
+             Unknown command 'nothing'.\n\
+             Error: Script failed\n\
+             Line 2 of {inner} failed.\n\
+             Error: Script failed\n\
+             Line 2 of {outer} failed.\n"
+        )
+    );
+
+    // a script sourcing itself stops at the recursion limit.
+    core.stderr = Writer::new_buf();
+    fs::write(paths[1], format!("s +1\nsource {inner}")).unwrap();
+    core.set_loc(0);
+    rair_eval(&mut core, &format!("source {inner}"));
+    assert_eq!(core.get_loc(), 16);
+    let err = core.stderr.utf8_string().unwrap();
+    assert!(err.starts_with(
+        "Error: Failed to run script\n\
+         Scripts can't be nested more than 16 levels deep.\n"
+    ));
+    assert_eq!(err.matches("Script failed").count(), 16);
+}
+#[test]
+fn test_nested_source() {
+    operate_on_files(&nested_cb, &[b"", b""]);
+}
+
+#[test]
+fn test_source_errors() {
+    let mut core = Core::new_no_colors();
+    core.stdout = Writer::new_buf();
+    core.stderr = Writer::new_buf();
+    register_eval(&mut core);
+    rair_eval(&mut core, "source");
+    rair_eval(&mut core, "es /this/script/does/not/exist");
+    assert!(core.stdout.bytes().unwrap().is_empty());
+    assert_eq!(
+        core.stderr.utf8_string().unwrap(),
+        "Arguments Error: Expected 1 argument(s), found 0.\n\
+         Error: Failed to run script\n\
+         No such file or directory (os error 2)\n"
+    );
+}
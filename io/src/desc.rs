@@ -1,7 +1,12 @@
 //! file descriptor data structure and needed tools to operate on single file.
 
+use crate::mapsquery::RIOMap;
 use crate::plugin::{RIOPlugin, RIOPluginOperations};
-use crate::utils::{IoError, IoMode};
+use crate::utils::{IoError, IoMode, Mutex};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
 /// This struct represents a file that is opened in [RIO]
@@ -13,12 +18,17 @@ pub struct RIODesc {
     pub(crate) paddr: u64, //padd is simulated physical address
     pub(crate) size: u64,
     raddr: u64, // raddr is the IO descriptor address, general rule of interaction paddr is high level lie, while raddr is the real thing.
+    // Name of the plugin that opened this file, files are reopened with it after deserializing.
+    // Projects saved before it was stored leave it empty.
+    #[serde(default)]
+    plugin_name: String,
     // Since we are skiping files operation structures .. after deserializing RIO .. we must
     // reopen the files again and make sure that they are in the right place
     // for sake of serde skip Box<dyn RIOPluginOperations + Sync + Send> must implement Default and
     // the implementation is found in plugins.rs
+    // Plugins need `&mut` to read, the lock lets descriptors be read through a shared reference.
     #[serde(skip)]
-    plugin_operations: Box<dyn RIOPluginOperations + Sync + Send>,
+    plugin_operations: Mutex<Box<dyn RIOPluginOperations + Sync + Send>>,
 }
 
 impl RIODesc {
@@ -37,40 +47,74 @@ impl RIODesc {
             perm: plugin_desc.perm,
             paddr: 0,
             size: plugin_desc.size,
-            plugin_operations: plugin_desc.plugin_operations,
+            plugin_operations: Mutex::new(plugin_desc.plugin_operations),
             raddr: plugin_desc.raddr,
+            plugin_name: plugin.get_metadata().name.to_owned(),
         };
         Ok(desc)
     }
     pub(crate) fn reopen(&mut self, plugin: &mut dyn RIOPlugin) -> Result<(), IoError> {
         let plugin_desc = plugin.open(&self.name, self.perm)?;
-        self.plugin_operations = plugin_desc.plugin_operations;
+        self.plugin_operations = Mutex::new(plugin_desc.plugin_operations);
         self.raddr = plugin_desc.raddr;
+        self.plugin_name = plugin.get_metadata().name.to_owned();
         Ok(())
     }
-    pub(crate) fn read(&mut self, paddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+    pub(crate) fn read(&self, paddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
         self.plugin_operations
+            .lock()
             .read(paddr - self.paddr as usize + self.raddr as usize, buffer)
     }
     pub(crate) fn write(&mut self, paddr: usize, buffer: &[u8]) -> Result<(), IoError> {
         self.plugin_operations
+            .get_mut()
             .write(paddr - self.paddr as usize + self.raddr as usize, buffer)
     }
+    pub(crate) fn flush(&mut self) -> Result<(), IoError> {
+        self.plugin_operations.get_mut().flush()
+    }
+    pub(crate) fn read_original(&self, paddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.plugin_operations
+            .lock()
+            .read_original(paddr - self.paddr as usize + self.raddr as usize, buffer)
+    }
+    pub(crate) fn resize(&mut self, size: u64) -> Result<(), IoError> {
+        self.name = self.plugin_operations.get_mut().resize(&self.name, size)?;
+        self.size = size;
+        Ok(())
+    }
     /// Returns URI of current file descriptor.
     #[must_use]
     pub fn name(&self) -> &str {
         &self.name
     }
+    /// Returns name of the plugin that opened this file as found in its [`RIOPluginMetadata`].
+    ///
+    /// [`RIOPluginMetadata`]: crate::RIOPluginMetadata
+    #[must_use]
+    pub fn plugin_name(&self) -> &str {
+        &self.plugin_name
+    }
     /// Returns *true* if paddr exists in this file descriptor and *false* otherwise.
     #[must_use]
     pub fn has_paddr(&self, paddr: u64) -> bool {
-        paddr >= self.paddr && paddr < self.paddr + self.size
+        self.contains(paddr)
+    }
+    /// Returns *true* if paddr is within `[paddr_base, paddr_end)` and *false* otherwise.
+    #[must_use]
+    pub fn contains(&self, paddr: u64) -> bool {
+        paddr >= self.paddr && paddr < self.paddr_end()
     }
     /// Returns the base physical address of this file.
     #[must_use]
     pub fn paddr_base(&self) -> u64 {
         self.paddr
     }
+    /// Returns the physical address right after the last byte of this file.
+    #[must_use]
+    pub fn paddr_end(&self) -> u64 {
+        self.paddr + self.size
+    }
     /// Returns size of file on disk.
     #[must_use]
     pub fn size(&self) -> u64 {
@@ -86,6 +130,20 @@ impl RIODesc {
     pub fn hndl(&self) -> u64 {
         self.hndl
     }
+    /// Returns mappings suggested by the plugin that opened this file, with `paddr` translated
+    /// to the physical address space.
+    #[must_use]
+    pub fn suggested_maps(&self) -> Vec<RIOMap> {
+        self.plugin_operations
+            .lock()
+            .suggested_maps()
+            .into_iter()
+            .map(|map| RIOMap {
+                paddr: map.paddr - self.raddr + self.paddr,
+                ..map
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -127,6 +185,21 @@ mod default_plugin_tests {
     fn test_desc_has_paddr() {
         operate_on_file(&test_desc_has_paddr_cb, DATA);
     }
+    fn test_desc_contains_cb(path: &Path) {
+        let mut plugin = defaultplugin::plugin();
+        let mut desc = RIODesc::open(&mut *plugin, &path.to_string_lossy(), IoMode::READ).unwrap();
+        desc.paddr = 0x40000;
+        let end = 0x40000 + DATA.len() as u64;
+        assert_eq!(desc.paddr_end(), end);
+        assert!(!desc.contains(0x40000 - 1));
+        assert!(desc.contains(0x40000));
+        assert!(desc.contains(end - 1));
+        assert!(!desc.contains(end));
+    }
+    #[test]
+    fn test_desc_contains() {
+        operate_on_file(&test_desc_contains_cb, DATA);
+    }
     fn test_desc_read_errors_cb(path: &Path) {
         let mut plugin = defaultplugin::plugin();
         let mut desc = RIODesc::open(&mut *plugin, &path.to_string_lossy(), IoMode::READ).unwrap();
@@ -3,7 +3,10 @@
 use crate::desc::RIODesc;
 use crate::plugin::RIOPlugin;
 use crate::utils::{IoError, IoMode};
+use alloc::boxed::Box;
 use alloc::collections::BinaryHeap;
+use alloc::format;
+use alloc::vec::Vec;
 use core::cmp::{min, Reverse};
 use rair_trees::ist::IST;
 use serde::{Deserialize, Serialize};
@@ -121,6 +124,82 @@ impl RIODescQuery {
         self.paddr_to_hndls.insert(lo, hi, hndl);
         Ok(hndl)
     }
+    // Replace the descriptor of `hndl` with one freshly opened with `flags` at the same paddr.
+    pub(crate) fn reopen(
+        &mut self,
+        plugin: &mut dyn RIOPlugin,
+        hndl: u64,
+        flags: IoMode,
+    ) -> Result<(), IoError> {
+        let desc = self.hndl_to_desc(hndl).ok_or(IoError::HndlNotFoundError)?;
+        let (paddr, size) = (desc.paddr, desc.size);
+        let mut new_desc = RIODesc::open(plugin, &desc.name, flags)?;
+        new_desc.hndl = hndl;
+        new_desc.paddr = paddr;
+        if new_desc.size != size {
+            self.paddr_to_hndls.delete_envelop(paddr, paddr + size - 1);
+            let hi = paddr + new_desc.size - 1;
+            if !self.paddr_to_hndls.overlap(paddr, hi).is_empty() {
+                self.paddr_to_hndls.insert(paddr, paddr + size - 1, hndl);
+                return Err(IoError::AddressesOverlapError);
+            }
+            self.paddr_to_hndls.insert(paddr, hi, hndl);
+        }
+        self.hndl_to_descs[hndl as usize] = Some(new_desc);
+        Ok(())
+    }
+    // Resize the descriptor of `hndl` in place, it can only grow into free physical space.
+    pub(crate) fn resize(&mut self, hndl: u64, size: u64) -> Result<(), IoError> {
+        let desc = self.hndl_to_desc(hndl).ok_or(IoError::HndlNotFoundError)?;
+        let (paddr, old_size) = (desc.paddr, desc.size);
+        self.paddr_to_hndls
+            .delete_envelop(paddr, paddr + old_size - 1);
+        let result = if self
+            .paddr_to_hndls
+            .overlap(paddr, paddr + size - 1)
+            .is_empty()
+        {
+            self.hndl_to_mut_desc(hndl).unwrap().resize(size)
+        } else {
+            Err(IoError::AddressesOverlapError)
+        };
+        let new_size = if result.is_ok() { size } else { old_size };
+        self.paddr_to_hndls
+            .insert(paddr, paddr + new_size - 1, hndl);
+        result
+    }
+    // Copy of the handle table where every descriptor is replaced by the one returned by `open`
+    // for it, handles and physical addresses stay the same.
+    pub(crate) fn fork<F>(&self, mut open: F) -> Result<RIODescQuery, IoError>
+    where
+        F: FnMut(&RIODesc) -> Result<RIODesc, IoError>,
+    {
+        let mut fork = RIODescQuery {
+            hndl_to_descs: Vec::with_capacity(self.hndl_to_descs.len()),
+            paddr_to_hndls: IST::new(),
+            next_hndl: self.next_hndl,
+            free_hndls: self.free_hndls.clone(),
+        };
+        for slot in &self.hndl_to_descs {
+            let Some(desc) = slot else {
+                fork.hndl_to_descs.push(None);
+                continue;
+            };
+            let mut new_desc = open(desc)?;
+            if new_desc.size != desc.size {
+                return Err(IoError::Custom(format!(
+                    "Size of {} changed since it was opened",
+                    desc.name
+                )));
+            }
+            new_desc.hndl = desc.hndl;
+            new_desc.paddr = desc.paddr;
+            fork.paddr_to_hndls
+                .insert(desc.paddr, desc.paddr + desc.size - 1, desc.hndl);
+            fork.hndl_to_descs.push(Some(new_desc));
+        }
+        Ok(fork)
+    }
     pub(crate) fn hndl_to_desc(&self, hndl: u64) -> Option<&RIODesc> {
         if hndl >= self.hndl_to_descs.len() as u64 {
             return None;
@@ -133,12 +212,12 @@ impl RIODescQuery {
         }
         self.hndl_to_descs[hndl as usize].as_mut()
     }
-    // Returns Option<Vec<hndl, start, size>>
+    // Returns Vec<hndl, start, size> or the first address that is not backed by any file
     pub(crate) fn paddr_range_to_hndl(
         &self,
         paddr: u64,
         size: u64,
-    ) -> Option<Vec<(u64, u64, u64)>> {
+    ) -> Result<Vec<(u64, u64, u64)>, u64> {
         let hndls: Vec<u64> = self
             .paddr_to_hndls
             .overlap(paddr, paddr + size - 1)
@@ -146,7 +225,7 @@ impl RIODescQuery {
             .map(|x| **x)
             .collect();
         if hndls.is_empty() {
-            return None;
+            return Err(paddr);
         }
         let mut ranged_hndl = Vec::with_capacity(hndls.len());
         let mut start = paddr;
@@ -154,7 +233,7 @@ impl RIODescQuery {
         for hndl in hndls {
             let desc = self.hndl_to_desc(hndl).unwrap();
             if start < desc.paddr {
-                return None;
+                return Err(start);
             }
             let delta = min(remaining, desc.size - (start - desc.paddr));
             ranged_hndl.push((hndl, start, delta));
@@ -162,9 +241,9 @@ impl RIODescQuery {
             remaining -= delta;
         }
         if remaining != 0 {
-            return None;
+            return Err(start);
         }
-        Some(ranged_hndl)
+        Ok(ranged_hndl)
     }
 
     pub(crate) fn paddr_sparce_range_to_hndl(&self, paddr: u64, size: u64) -> Vec<(u64, u64, u64)> {
@@ -219,6 +298,7 @@ impl<'a> IntoIterator for &'a mut RIODescQuery {
 mod desc_query_tests {
     use super::*;
     use crate::plugins::defaultplugin::plugin;
+    use crate::plugins::malloc;
     use std::path::Path;
     use test_file::*;
     fn test_open_close_cb(path: &[&Path]) {
@@ -397,7 +477,7 @@ mod desc_query_tests {
             descs.paddr_range_to_hndl(0x1000, 1).unwrap(),
             vec![(2, 0x1000, 1)]
         );
-        assert_eq!(descs.paddr_range_to_hndl(0x500, 1), None);
+        assert_eq!(descs.paddr_range_to_hndl(0x500, 1), Err(0x500));
         assert_eq!(descs.hndl_to_desc(0).unwrap().hndl, 0);
         assert_eq!(descs.hndl_to_desc(1).unwrap().hndl, 1);
         assert_eq!(descs.hndl_to_desc(2).unwrap().hndl, 2);
@@ -435,11 +515,11 @@ mod desc_query_tests {
             vec![(0, 0, 105), (1, 105, 105), (2, 210, 105)]
         );
         // overflow to the left
-        assert_eq!(descs.paddr_range_to_hndl(330, 200), None);
+        assert_eq!(descs.paddr_range_to_hndl(330, 200), Err(330));
         // overflow to the right
-        assert_eq!(descs.paddr_range_to_hndl(20, 315), None);
+        assert_eq!(descs.paddr_range_to_hndl(20, 315), Err(315));
         // overflow in the middle
-        assert_eq!(descs.paddr_range_to_hndl(20, 500), None);
+        assert_eq!(descs.paddr_range_to_hndl(20, 500), Err(315));
         // read from the middle of a descriptor
         assert_eq!(
             descs.paddr_range_to_hndl(20, 295).unwrap(),
@@ -512,4 +592,39 @@ mod desc_query_tests {
     fn test_paddr_sparce_range_to_hndl() {
         operate_on_files(&paddr_sparce_range_to_hndl_cb, &[DATA, DATA, DATA, DATA]);
     }
+    #[test]
+    fn test_many_descs() {
+        let mut p = malloc::plugin();
+        let mut descs = RIODescQuery::new();
+        // opened from the highest paddr down, with a 0x10 bytes gap after every file.
+        let count = 0x1000u64;
+        for i in (0..count).rev() {
+            descs
+                .register_open_at(
+                    &mut *p,
+                    "malloc://0x10",
+                    IoMode::READ | IoMode::WRITE,
+                    i * 0x20,
+                )
+                .unwrap();
+        }
+        assert_eq!(descs.paddr_to_hndls.size(), count);
+        // lookups walk a balanced tree.
+        assert!(descs.paddr_to_hndls.get_level() <= 2 * u64::from(count.ilog2() + 1));
+        for i in (0..count).step_by(0x101) {
+            let hndl = count - 1 - i;
+            assert_eq!(
+                descs.paddr_range_to_hndl(i * 0x20 + 8, 8).unwrap(),
+                vec![(hndl, i * 0x20 + 8, 8)]
+            );
+            assert_eq!(
+                descs.paddr_range_to_hndl(i * 0x20 + 8, 9).unwrap_err(),
+                i * 0x20 + 0x10
+            );
+            assert_eq!(
+                descs.paddr_sparce_range_to_hndl(i * 0x20 + 8, 0x30),
+                vec![(hndl, i * 0x20 + 8, 8), (hndl - 1, i * 0x20 + 0x20, 0x10)]
+            );
+        }
+    }
 }
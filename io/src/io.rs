@@ -2,11 +2,19 @@
 
 use crate::desc::RIODesc;
 use crate::descquery::RIODescQuery;
+use crate::iolog::{IoLog, IoLogRecord, IoOp};
+use crate::journal::{Change, WriteJournal};
 use crate::mapsquery::{RIOMap, RIOMapQuery};
-use crate::plugin::RIOPlugin;
+use crate::plugin::{RIOPlugin, RIOPluginMetadata};
 use crate::plugins;
-use crate::utils::{IoError, IoMode};
-use alloc::{collections::BTreeMap, sync::Arc};
+use crate::snapshot::{Snapshot, SnapshotFile};
+use crate::utils::{Endian, IoError, IoMode, Mutex};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, format, sync::Arc, vec};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 // Credits goes to @Talchas#7429 for the idea of using remote
@@ -31,6 +39,14 @@ pub struct RIO {
     maps: RIOMapQuery,
     #[serde(skip)]
     plugins: Vec<Box<dyn RIOPlugin + Sync + Send>>,
+    #[serde(skip)]
+    log: Mutex<Option<IoLog>>,
+    #[serde(skip)]
+    read_hook: Option<Mutex<IoHook>>,
+    #[serde(skip)]
+    write_hook: Option<Mutex<IoHook>>,
+    #[serde(skip)]
+    journal: Option<WriteJournal>,
 }
 
 impl Serialize for RIO {
@@ -50,22 +66,57 @@ impl<'de> Deserialize<'de> for RIO {
         let mut io = RIO::deserialize(deserializer)?;
         plugins::load_plugins(&mut io);
         for desc in &mut io.descs {
-            let mut found = false;
-            for plugin in &mut io.plugins {
-                if plugin.accept_uri(&desc.name) {
-                    desc.reopen(&mut **plugin).map_err(de::Error::custom)?;
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                return Err(de::Error::custom(IoError::IoPluginNotFoundError));
-            }
+            let Some(plugin) = Self::plugin_of(&mut io.plugins, desc) else {
+                return Err(de::Error::custom(IoError::IoPluginNotFoundError(
+                    desc.name.clone(),
+                )));
+            };
+            desc.reopen(&mut **plugin).map_err(de::Error::custom)?;
         }
         Ok(io)
     }
 }
 
+// Size of the buffer used by [`RIO::pfill`] and [`RIO::vfill`].
+const FILL_CHUNK: u64 = 0x1000;
+
+/// URIs that failed to open along with the reason, as returned by [`RIO::open_many`].
+#[cfg(feature = "std")]
+pub type OpenErrors = Vec<(String, IoError)>;
+
+/// Kind of access reported to hooks, see [`RIO::set_read_hook`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HookOp {
+    /// Reading from physical address space.
+    PhyRead,
+    /// Writing into physical address space.
+    PhyWrite,
+    /// Reading from virtual address space.
+    VirRead,
+    /// Writing into virtual address space.
+    VirWrite,
+}
+
+/// Callback called with the kind of access, the address and the size of each successful read or
+/// write, see [`RIO::set_read_hook`] and [`RIO::set_write_hook`].
+pub type IoHook = Box<dyn FnMut(HookOp, u64, u64) + Send>;
+
+// Generates a method that reads one `$ty` integer using `$read` and decodes it in given byte order.
+macro_rules! typed_read {
+    ($name:ident, $read:ident, $ty:ty, $space:literal) => {
+        #[doc = concat!("Read a `", stringify!($ty), "` from ", $space, " address space using the given byte order.")]
+        pub fn $name(&self, addr: u64, endian: Endian) -> Result<$ty, IoError> {
+            let mut buf = [0; core::mem::size_of::<$ty>()];
+            self.$read(addr, &mut buf)?;
+            Ok(match endian {
+                Endian::Little => <$ty>::from_le_bytes(buf),
+                Endian::Big => <$ty>::from_be_bytes(buf),
+                Endian::Native => <$ty>::from_ne_bytes(buf),
+            })
+        }
+    };
+}
+
 impl RIO {
     /// Returns new Input/Output interface to be used
     ///
@@ -83,9 +134,39 @@ impl RIO {
 
     /// THIS FUNCTION IS NOT SUPPOSED TO BE THAT TRIVIAL
     /// I WANT IT TO LITERALLY OPEN A PLUGIN FILE
+    /// Plugins are asked if they [`RIOPlugin::accept_uri`] in the order they were loaded, and
+    /// the first one that accepts the uri is the one used to open it.
     pub fn load_plugin(&mut self, plugin: Box<dyn RIOPlugin + Sync + Send>) {
         self.plugins.push(plugin);
     }
+
+    /// Load `plugin` in front of all loaded plugins so that it takes precedence over them,
+    /// including built-in plugins, for any uri it accepts.
+    pub fn load_plugin_front(&mut self, plugin: Box<dyn RIOPlugin + Sync + Send>) {
+        self.plugins.insert(0, plugin);
+    }
+
+    /// Unload all plugins whose [`RIOPluginMetadata::name`] is `name`. Files that are already
+    /// opened by these plugins stay opened. If no such plugin is loaded an [`IoError::Custom`]
+    /// error is returned.
+    ///
+    /// [`RIOPluginMetadata::name`]: crate::plugin::RIOPluginMetadata::name
+    pub fn unload_plugin_by_name(&mut self, name: &str) -> Result<(), IoError> {
+        let count = self.plugins.len();
+        self.plugins
+            .retain(|plugin| plugin.get_metadata().name != name);
+        if self.plugins.len() == count {
+            return Err(IoError::Custom(format!(
+                "No loaded IO plugin is named {name}"
+            )));
+        }
+        Ok(())
+    }
+    /// Iterate over metadata of loaded plugins in the order they are asked to open URIs.
+    #[must_use]
+    pub fn plugins_iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'static RIOPluginMetadata> + 'a> {
+        Box::new(self.plugins.iter().map(|plugin| plugin.get_metadata()))
+    }
     /// Allows us to open file and have it accessable from out physical address space,
     /// *open* will automatically load the file in the smallest available physical address while
     /// [`RIO::open_at`] will allow user to determine what physical address to use. `uri` is
@@ -105,15 +186,19 @@ impl RIO {
     /// io.open("hello.txt", IoMode::READ);
     /// ```
     pub fn open(&mut self, uri: &str, flags: IoMode) -> Result<u64, IoError> {
+        let mut result = Err(IoError::IoPluginNotFoundError(uri.to_owned()));
         for plugin in &mut self.plugins {
             if plugin.accept_uri(uri) {
-                if let Ok(hndl) = self.descs.register_open_default(&mut **plugin, uri, flags) {
-                    return Ok(hndl);
-                }
-                return self.descs.register_open(&mut **plugin, uri, flags);
+                result = Self::check_modes(&**plugin, flags).and_then(|()| {
+                    self.descs
+                        .register_open_default(&mut **plugin, uri, flags)
+                        .or_else(|_| self.descs.register_open(&mut **plugin, uri, flags))
+                });
+                break;
             }
         }
-        Err(IoError::IoPluginNotFoundError)
+        self.log_open(&result);
+        result
     }
 
     /// Allows us to open file and have it accessable from out physical address space
@@ -136,12 +221,115 @@ impl RIO {
     /// }
     /// ```
     pub fn open_at(&mut self, uri: &str, flags: IoMode, at: u64) -> Result<u64, IoError> {
+        let mut result = Err(IoError::IoPluginNotFoundError(uri.to_owned()));
         for plugin in &mut self.plugins {
             if plugin.accept_uri(uri) {
-                return self.descs.register_open_at(&mut **plugin, uri, flags, at);
+                result = Self::check_modes(&**plugin, flags)
+                    .and_then(|()| self.descs.register_open_at(&mut **plugin, uri, flags, at));
+                break;
             }
         }
-        Err(IoError::IoPluginNotFoundError)
+        self.log_open(&result);
+        result
+    }
+
+    /// Same as [`RIO::open`] but `uri` is opened by the plugin whose [`RIOPluginMetadata::name`]
+    /// is `plugin_name` (ignoring case) without asking it if it [`RIOPlugin::accept_uri`]. If no
+    /// such plugin is loaded an [`IoError::Custom`] error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{RIO, IoMode, IoError};
+    /// fn main() -> Result<(), IoError> {
+    ///     let mut io = RIO::new();
+    ///     let hndl = io.open_with("malloc", "malloc://0x20", IoMode::READ | IoMode::WRITE)?;
+    ///     assert_eq!(io.hndl_to_desc(hndl).unwrap().plugin_name(), "Malloc");
+    ///     return Ok(());
+    /// }
+    /// ```
+    pub fn open_with(
+        &mut self,
+        plugin_name: &str,
+        uri: &str,
+        flags: IoMode,
+    ) -> Result<u64, IoError> {
+        let result = self.register_open_with(plugin_name, uri, flags, None);
+        self.log_open(&result);
+        result
+    }
+
+    /// Same as [`RIO::open_at`] but `uri` is opened by the plugin named `plugin_name` the same
+    /// way [`RIO::open_with`] does.
+    pub fn open_at_with(
+        &mut self,
+        plugin_name: &str,
+        uri: &str,
+        flags: IoMode,
+        at: u64,
+    ) -> Result<u64, IoError> {
+        let result = self.register_open_with(plugin_name, uri, flags, Some(at));
+        self.log_open(&result);
+        result
+    }
+
+    /// Open every file matching the shell glob `pattern` (for example `dumps/*.hex`). The
+    /// pattern may start with a plugin prefix such as `ihex://`, which is then used for every
+    /// match. Files are opened in sorted filename order right after each other, starting at
+    /// the end of the physical address space.
+    ///
+    /// # Return value
+    /// Handles of the opened files are returned along with the URIs that failed to open and
+    /// why, a failure never stops the rest of the batch. An [`IoError`] is returned only if
+    /// `pattern` itself is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rair_io::{RIO, IoMode, IoError};
+    /// fn main() -> Result<(), IoError> {
+    ///     let mut io = RIO::new();
+    ///     let (hndls, errors) = io.open_many("ihex://dumps/*.hex", IoMode::READ)?;
+    ///     return Ok(());
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn open_many(
+        &mut self,
+        pattern: &str,
+        flags: IoMode,
+    ) -> Result<(Vec<u64>, OpenErrors), IoError> {
+        let (prefix, path_pattern) = match pattern.split_once("://") {
+            Some((scheme, path)) => (format!("{scheme}://"), path),
+            None => (String::new(), pattern),
+        };
+        let entries = glob::glob(path_pattern)
+            .map_err(|e| IoError::Custom(format!("Invalid pattern {pattern}: {e}")))?;
+        let mut paths = Vec::new();
+        let mut errors = Vec::new();
+        for entry in entries {
+            match entry {
+                Ok(path) => paths.push(path),
+                Err(e) => {
+                    let uri = format!("{prefix}{}", e.path().display());
+                    errors.push((uri, IoError::Parse(e.into())));
+                }
+            }
+        }
+        paths.sort();
+        let mut at = self.uri_iter().map(RIODesc::paddr_end).max().unwrap_or(0);
+        let mut hndls = Vec::new();
+        for path in paths {
+            let uri = format!("{prefix}{}", path.display());
+            match self.open_at(&uri, flags, at) {
+                Ok(hndl) => {
+                    at = self.descs.hndl_to_desc(hndl).unwrap().paddr_end();
+                    hndls.push(hndl);
+                }
+                Err(e) => errors.push((uri, e)),
+            }
+        }
+        Ok((hndls, errors))
     }
 
     /// Close an opened file, delete its physical and virtual address space.
@@ -163,10 +351,135 @@ impl RIO {
 
     pub fn close(&mut self, hndl: u64) -> Result<(), IoError> {
         // delete all memory mappings related to the closed handle
-        self.descs.close(hndl)?;
+        let result = self.descs.close(hndl);
+        let (addr, size) = match &result {
+            Ok(desc) => (desc.paddr_base(), desc.size()),
+            Err(_) => (0, 0),
+        };
+        self.log(IoOp::Close, addr, size, Some(hndl), result.is_ok());
+        result?;
+        self.clear_write_journal();
+        Ok(())
+    }
+
+    /// Reopen the file identified by `hndl` using the same URI with `flags` permissions, at the
+    /// same physical address, so handles and maps into that file keep working. This picks up
+    /// changes done to the underlying file by other processes, and can be used to make a read
+    /// only file writable. Any changes that were done to a file opened as Copy-On-Write are
+    /// lost. If the file size changes such that it would overlap another opened file, an
+    /// [`IoError`] is returned and the old descriptor is kept as is.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rair_io::RIO;
+    /// use rair_io::IoMode;
+    /// use rair_io::IoError;
+    /// fn main() -> Result<(), IoError> {
+    ///     let mut io = RIO::new();
+    ///     let hndl = io.open("hello.txt", IoMode::READ)?;
+    ///     io.reopen(hndl, IoMode::READ | IoMode::WRITE)?;
+    ///     io.pwrite(0, b"H")?;
+    ///     return Ok(());
+    /// }
+    /// ```
+    pub fn reopen(&mut self, hndl: u64, flags: IoMode) -> Result<(), IoError> {
+        let desc = self
+            .descs
+            .hndl_to_desc(hndl)
+            .ok_or(IoError::HndlNotFoundError)?;
+        let plugin = Self::plugin_of(&mut self.plugins, desc)
+            .ok_or_else(|| IoError::IoPluginNotFoundError(desc.name.clone()))?;
+        Self::check_modes(&**plugin, flags)?;
+        self.descs.reopen(&mut **plugin, hndl, flags)?;
+        self.clear_write_journal();
+        Ok(())
+    }
+
+    /// Change the size of the file identified by `hndl` to `size` bytes, keeping it at the same
+    /// physical address. Growing zero fills the new bytes and only works if the space right
+    /// after the file is free, otherwise [`IoError::AddressesOverlapError`] is returned. Maps
+    /// into bytes that are dropped by shrinking are kept as is. Only plugins that support
+    /// resizing (such as `malloc://`) can be resized, other files fail with [`IoError::Custom`].
+    /// Resizing clears the write journal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoError, IoMode, RIO};
+    /// fn main() -> Result<(), IoError> {
+    ///     let mut io = RIO::new();
+    ///     let hndl = io.open("malloc://0x20", IoMode::READ | IoMode::WRITE)?;
+    ///     io.resize(hndl, 0x40)?;
+    ///     io.pwrite(0x30, &[0xff])?;
+    ///     return Ok(());
+    /// }
+    /// ```
+    pub fn resize(&mut self, hndl: u64, size: u64) -> Result<(), IoError> {
+        if size == 0 {
+            return Err(IoError::Custom(
+                "Can not resize files to 0 bytes".to_owned(),
+            ));
+        }
+        self.descs.resize(hndl, size)?;
+        self.clear_write_journal();
         Ok(())
     }
 
+    /// Returns an independent copy of this [RIO] with the same files opened at the same handles
+    /// and physical addresses, and with the same maps. Writes done through either copy are
+    /// never visible through the other one.
+    ///
+    /// Files are opened again using the built-in plugins, exactly like when deserializing
+    /// [RIO]. Writable files are opened as Copy-On-Write in the copy, while files that are
+    /// already opened as Copy-On-Write get their current content copied over. Plugins that
+    /// do not support Copy-On-Write are assumed to be memory based, so their files are
+    /// opened with the same permissions and their content is copied over as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::RIO;
+    /// use rair_io::IoMode;
+    /// use rair_io::IoError;
+    /// fn main() -> Result<(), IoError> {
+    ///     let mut io = RIO::new();
+    ///     io.open("malloc://0x20", IoMode::READ | IoMode::WRITE)?;
+    ///     let mut fork = io.fork()?;
+    ///     fork.pwrite(0x10, &[0xff])?;
+    ///     let mut fillme = [0xff];
+    ///     io.pread(0x10, &mut fillme)?;
+    ///     assert_eq!(fillme, [0]);
+    ///     return Ok(());
+    /// }
+    /// ```
+    pub fn fork(&self) -> Result<RIO, IoError> {
+        let mut io = RIO::new();
+        let plugins = &mut io.plugins;
+        io.descs = self.descs.fork(|desc| {
+            let plugin = Self::plugin_of(plugins, desc)
+                .ok_or_else(|| IoError::IoPluginNotFoundError(desc.name.clone()))?;
+            let cow = plugin.supported_modes().contains(IoMode::COW);
+            let perm = if cow && desc.perm.contains(IoMode::WRITE) {
+                IoMode::COW
+            } else {
+                desc.perm
+            };
+            let mut new_desc = RIODesc::open(&mut **plugin, &desc.name, perm)?;
+            if desc.perm.contains(IoMode::COW) || (!cow && desc.perm.contains(IoMode::WRITE)) {
+                let mut buf = vec![0; desc.size as usize];
+                desc.read(desc.paddr as usize, &mut buf)?;
+                new_desc.paddr = desc.paddr;
+                new_desc.write(desc.paddr as usize, &buf)?;
+            }
+            Ok(new_desc)
+        })?;
+        for map in &self.maps {
+            io.maps.map(map.paddr, map.vaddr, map.size)?;
+        }
+        Ok(io)
+    }
+
     /// Close all open files, and reset all virtual and physical address spaces.
     ///
     /// # Example
@@ -187,6 +500,94 @@ impl RIO {
     pub fn close_all(&mut self) {
         self.maps = RIOMapQuery::new();
         self.descs = RIODescQuery::new();
+        self.clear_write_journal();
+    }
+
+    /// Ask every opened file to persist changes that are still only kept in memory to its
+    /// backing storage, so that they are not lost on exit. Changes done to files opened as
+    /// Copy-On-Write are never persisted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rair_io::{IoError, IoMode, RIO};
+    /// fn main() -> Result<(), IoError> {
+    ///     let mut io = RIO::new();
+    ///     io.open("hello.txt", IoMode::READ | IoMode::WRITE)?;
+    ///     io.pwrite(0, b"H")?;
+    ///     io.flush()?;
+    ///     return Ok(());
+    /// }
+    /// ```
+    pub fn flush(&mut self) -> Result<(), IoError> {
+        for desc in &mut self.descs {
+            desc.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Save the current content of every file opened with write or Copy-On-Write permissions,
+    /// so that it can be brought back later with [`RIO::restore`]. For Copy-On-Write files
+    /// the saved content is what the Copy-On-Write layer serves, not the file on disk.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoError, IoMode, RIO};
+    /// fn main() -> Result<(), IoError> {
+    ///     let mut io = RIO::new();
+    ///     io.open("malloc://0x20", IoMode::READ | IoMode::WRITE)?;
+    ///     let snapshot = io.snapshot()?;
+    ///     io.pwrite(0x10, &[0xff])?;
+    ///     io.restore(&snapshot)?;
+    ///     let mut fillme = [0xff];
+    ///     io.pread(0x10, &mut fillme)?;
+    ///     assert_eq!(fillme, [0]);
+    ///     return Ok(());
+    /// }
+    /// ```
+    pub fn snapshot(&mut self) -> Result<Snapshot, IoError> {
+        let mut snapshot = Snapshot::default();
+        for desc in &mut self.descs {
+            if !desc.perm.intersects(IoMode::WRITE | IoMode::COW) {
+                continue;
+            }
+            let mut data = vec![0; desc.size as usize];
+            desc.read(desc.paddr as usize, &mut data)?;
+            snapshot.files.push(SnapshotFile {
+                hndl: desc.hndl,
+                name: desc.name.clone(),
+                data,
+            });
+        }
+        Ok(snapshot)
+    }
+
+    /// Write back the content saved in `snapshot` by [`RIO::snapshot`]. Every saved file must
+    /// still be opened at the same handle with the same URI, size and permissions, otherwise
+    /// an [`IoError`] is returned and nothing is written. The write journal is cleared.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), IoError> {
+        for file in &snapshot.files {
+            let desc = self
+                .descs
+                .hndl_to_desc(file.hndl)
+                .ok_or(IoError::HndlNotFoundError)?;
+            if desc.name != file.name
+                || desc.size != file.data.len() as u64
+                || !desc.perm.intersects(IoMode::WRITE | IoMode::COW)
+            {
+                return Err(IoError::Custom(format!(
+                    "Snapshot of {} does not match handle {}",
+                    file.name, file.hndl
+                )));
+            }
+        }
+        for file in &snapshot.files {
+            let desc = self.descs.hndl_to_mut_desc(file.hndl).unwrap();
+            desc.write(desc.paddr as usize, &file.data)?;
+        }
+        self.clear_write_journal();
+        Ok(())
     }
 
     /// Read from the physical address space of current [RIO] object. If there is no enough
@@ -202,22 +603,37 @@ impl RIO {
     /// let mut fillme: Vec<u8> = vec![0; 8];
     /// io.pread(0x20, &mut fillme);
     /// ```
-    pub fn pread(&mut self, paddr: u64, buf: &mut [u8]) -> Result<(), IoError> {
+    pub fn pread(&self, paddr: u64, buf: &mut [u8]) -> Result<(), IoError> {
+        let ret = self.pread_logged(paddr, buf);
+        self.hook(HookOp::PhyRead, paddr, buf.len() as u64, ret.is_ok());
+        ret
+    }
+    // Same as `pread` but without running the read hook.
+    fn pread_logged(&self, paddr: u64, buf: &mut [u8]) -> Result<(), IoError> {
         let result = self.descs.paddr_range_to_hndl(paddr, buf.len() as u64);
-        if let Some(operations) = result {
-            let mut start = 0;
-            for (hndl, paddr, size) in operations {
-                let desc = self.descs.hndl_to_mut_desc(hndl).unwrap();
-                desc.read(
-                    paddr as usize,
-                    &mut buf[start as usize..(start + size) as usize],
-                )?;
-                start += size;
-            }
-            Ok(())
-        } else {
-            Err(IoError::AddressNotFound)
+        let hndl = Self::first_hndl(result.as_deref().ok());
+        let ret = match result {
+            Ok(operations) => self.pread_operations(&operations, buf),
+            Err(at) => Err(IoError::AddressNotFound { at }),
+        };
+        self.log(IoOp::Read, paddr, buf.len() as u64, hndl, ret.is_ok());
+        ret
+    }
+    fn pread_operations(
+        &self,
+        operations: &[(u64, u64, u64)],
+        buf: &mut [u8],
+    ) -> Result<(), IoError> {
+        let mut start = 0;
+        for &(hndl, paddr, size) in operations {
+            let desc = self.descs.hndl_to_desc(hndl).unwrap();
+            desc.read(
+                paddr as usize,
+                &mut buf[start as usize..(start + size) as usize],
+            )?;
+            start += size;
         }
+        Ok(())
     }
     /// Read from the physical address space of current [RIO] object. Data is stored in a sparce
     /// vector represented by [`BTreeMap`]. Error is returned only in case of internal IO errors.
@@ -231,11 +647,11 @@ impl RIO {
     /// io.open_at("foo.txt", IoMode::READ, 0x20);
     /// let data = io.pread_sparce(0x20, 0x50); //reads at most 0x50 bytes from foo.txt
     ///```  
-    pub fn pread_sparce(&mut self, paddr: u64, size: u64) -> Result<BTreeMap<u64, u8>, IoError> {
+    pub fn pread_sparce(&self, paddr: u64, size: u64) -> Result<BTreeMap<u64, u8>, IoError> {
         let mut result = BTreeMap::new();
         let ranges = self.descs.paddr_sparce_range_to_hndl(paddr, size);
         for (hndl, paddr, size) in ranges {
-            let desc = self.descs.hndl_to_mut_desc(hndl).unwrap();
+            let desc = self.descs.hndl_to_desc(hndl).unwrap();
             let mut buffer = vec![0; size as usize];
             desc.read(paddr as usize, &mut buffer)?;
             for (i, v) in buffer.iter().enumerate() {
@@ -244,6 +660,38 @@ impl RIO {
         }
         Ok(result)
     }
+    /// Same as [`RIO::pread_sparce`] but bytes are read as they were before changes that are
+    /// only kept in memory, such as writes to files opened as [`IoMode::COW`] or to `data://`
+    /// files.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::RIO;
+    /// use rair_io::IoMode;
+    /// let mut io = RIO::new();
+    /// io.open("data://hex,0011", IoMode::READ | IoMode::WRITE).unwrap();
+    /// io.pwrite(0x1, &[0xff]).unwrap();
+    /// assert_eq!(io.pread_sparce(0x1, 1).unwrap()[&0x1], 0xff);
+    /// assert_eq!(io.pread_original_sparce(0x1, 1).unwrap()[&0x1], 0x11);
+    /// ```
+    pub fn pread_original_sparce(
+        &self,
+        paddr: u64,
+        size: u64,
+    ) -> Result<BTreeMap<u64, u8>, IoError> {
+        let mut result = BTreeMap::new();
+        let ranges = self.descs.paddr_sparce_range_to_hndl(paddr, size);
+        for (hndl, paddr, size) in ranges {
+            let desc = self.descs.hndl_to_desc(hndl).unwrap();
+            let mut buffer = vec![0; size as usize];
+            desc.read_original(paddr as usize, &mut buffer)?;
+            for (i, v) in buffer.iter().enumerate() {
+                result.insert(paddr + i as u64, *v);
+            }
+        }
+        Ok(result)
+    }
     /// Write into the physical address space of current [RIO] object. If there is no enough
     /// space to accomodate *buf* an error is returned.
     ///
@@ -258,87 +706,294 @@ impl RIO {
     /// io.pwrite(0x20, &fillme);
     /// ```
     pub fn pwrite(&mut self, paddr: u64, buf: &[u8]) -> Result<(), IoError> {
+        let ret = self.pwrite_logged(paddr, buf);
+        self.hook(HookOp::PhyWrite, paddr, buf.len() as u64, ret.is_ok());
+        ret
+    }
+    // Same as `pwrite` but without running the write hook.
+    fn pwrite_logged(&mut self, paddr: u64, buf: &[u8]) -> Result<(), IoError> {
         let result = self.descs.paddr_range_to_hndl(paddr, buf.len() as u64);
-        if let Some(operations) = result {
+        let hndl = Self::first_hndl(result.as_deref().ok());
+        let ret = match result {
+            Ok(operations) => self.journaled_pwrite_operations(paddr, &operations, buf),
+            Err(at) => Err(IoError::AddressNotFound { at }),
+        };
+        self.log(IoOp::Write, paddr, buf.len() as u64, hndl, ret.is_ok());
+        ret
+    }
+    /// Same as [`RIO::pwrite`] except that writing a range that spans more than one file
+    /// fails with [`IoError::CrossDescriptorWrite`] without writing anything.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoError, IoMode, RIO};
+    /// let mut io = RIO::new();
+    /// io.open_at("malloc://0x10", IoMode::READ | IoMode::WRITE, 0x0).unwrap();
+    /// io.open_at("malloc://0x10", IoMode::READ | IoMode::WRITE, 0x10).unwrap();
+    /// assert_eq!(io.pwrite_strict(0xc, &[0; 8]), Err(IoError::CrossDescriptorWrite));
+    /// ```
+    pub fn pwrite_strict(&mut self, paddr: u64, buf: &[u8]) -> Result<(), IoError> {
+        if let Ok(operations) = self.descs.paddr_range_to_hndl(paddr, buf.len() as u64) {
+            if operations.len() > 1 {
+                let hndl = Self::first_hndl(Some(&operations));
+                self.log(IoOp::Write, paddr, buf.len() as u64, hndl, false);
+                return Err(IoError::CrossDescriptorWrite);
+            }
+        }
+        self.pwrite(paddr, buf)
+    }
+    // Same as `pwrite_operations` but records the previous bytes if the write journal is enabled.
+    fn journaled_pwrite_operations(
+        &mut self,
+        paddr: u64,
+        operations: &[(u64, u64, u64)],
+        buf: &[u8],
+    ) -> Result<(), IoError> {
+        if self.journal.is_none() {
+            return self.pwrite_operations(operations, buf);
+        }
+        let mut before = vec![0; buf.len()];
+        if self.pread_operations(operations, &mut before).is_err() {
+            // the old bytes are unknown, so older entries can no longer be replayed safely.
+            self.clear_write_journal();
+            return self.pwrite_operations(operations, buf);
+        }
+        self.pwrite_operations(operations, buf)?;
+        if let Some(journal) = &mut self.journal {
+            journal.record(Change {
+                paddr,
+                before,
+                after: buf.to_vec(),
+            });
+        }
+        Ok(())
+    }
+    fn pwrite_operations(
+        &mut self,
+        operations: &[(u64, u64, u64)],
+        buf: &[u8],
+    ) -> Result<(), IoError> {
+        let mut start = 0;
+        for &(hndl, paddr, size) in operations {
+            let desc = self.descs.hndl_to_mut_desc(hndl).unwrap();
+            desc.write(
+                paddr as usize,
+                &buf[start as usize..(start + size) as usize],
+            )?;
+            start += size;
+        }
+        Ok(())
+    }
+    /// Fill `size` bytes of the physical address space starting at `paddr` with `byte`. Data is
+    /// written in small chunks so filling huge ranges doesn't need a buffer as big as the range.
+    /// Fills are not recorded in the write journal, see [`RIO::enable_write_journal`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoMode, RIO};
+    /// let mut io = RIO::new();
+    /// io.open_at("malloc://0x100", IoMode::READ | IoMode::WRITE, 0x0).unwrap();
+    /// io.pfill(0x10, 0x20, 0xff).unwrap();
+    /// ```
+    pub fn pfill(&mut self, paddr: u64, size: u64, byte: u8) -> Result<(), IoError> {
+        let ret = self.pfill_logged(paddr, size, byte);
+        self.hook(HookOp::PhyWrite, paddr, size, ret.is_ok());
+        ret
+    }
+    // Same as `pfill` but without running the write hook.
+    fn pfill_logged(&mut self, paddr: u64, size: u64, byte: u8) -> Result<(), IoError> {
+        let result = self.descs.paddr_range_to_hndl(paddr, size);
+        let hndl = Self::first_hndl(result.as_deref().ok());
+        let ret = match result {
+            Ok(operations) => self.pfill_operations(&operations, byte),
+            Err(at) => Err(IoError::AddressNotFound { at }),
+        };
+        self.log(IoOp::Write, paddr, size, hndl, ret.is_ok());
+        ret
+    }
+    fn pfill_operations(
+        &mut self,
+        operations: &[(u64, u64, u64)],
+        byte: u8,
+    ) -> Result<(), IoError> {
+        let total: u64 = operations.iter().map(|op| op.2).sum();
+        let chunk = vec![byte; total.min(FILL_CHUNK) as usize];
+        for &(hndl, paddr, size) in operations {
+            let desc = self.descs.hndl_to_mut_desc(hndl).unwrap();
             let mut start = 0;
-            for (hndl, paddr, size) in operations {
-                let desc = self.descs.hndl_to_mut_desc(hndl).unwrap();
-                desc.write(
-                    paddr as usize,
-                    &buf[start as usize..(start + size) as usize],
-                )?;
-                start += size;
+            while start < size {
+                let len = (size - start).min(FILL_CHUNK);
+                desc.write((paddr + start) as usize, &chunk[..len as usize])?;
+                start += len;
             }
-            Ok(())
-        } else {
-            Err(IoError::AddressNotFound)
         }
+        Ok(())
     }
     ///  Map memory regions from physical address space to virtual address space
     pub fn map(&mut self, paddr: u64, vaddr: u64, size: u64) -> Result<(), IoError> {
-        if self.descs.paddr_range_to_hndl(paddr, size).is_none() {
-            return Err(IoError::AddressNotFound);
-        }
-        self.maps.map(paddr, vaddr, size)
+        let operations = self.descs.paddr_range_to_hndl(paddr, size);
+        let hndl = Self::first_hndl(operations.as_deref().ok());
+        let result = match operations {
+            Ok(_) => self.maps.map(paddr, vaddr, size),
+            Err(at) => Err(IoError::AddressNotFound { at }),
+        };
+        self.log(IoOp::Map, vaddr, size, hndl, result.is_ok());
+        result
+    }
+
+    /// Map `size` bytes starting at `paddr` to the lowest virtual address that has enough
+    /// free space and return that address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoMode, RIO};
+    /// let mut io = RIO::new();
+    /// io.open("malloc://0x100", IoMode::READ | IoMode::WRITE).unwrap();
+    /// io.map(0x0, 0x10, 0x10).unwrap();
+    /// assert_eq!(io.map_anywhere(0x20, 0x10).unwrap(), 0x0);
+    /// assert_eq!(io.map_anywhere(0x40, 0x10).unwrap(), 0x20);
+    /// ```
+    pub fn map_anywhere(&mut self, paddr: u64, size: u64) -> Result<u64, IoError> {
+        let ranges: Vec<(u64, u64)> = self.map_iter().map(|map| (map.vaddr, map.size)).collect();
+        let vaddr = Self::lowest_fit(&ranges, size).ok_or(IoError::AddressesOverlapError)?;
+        self.map(paddr, vaddr, size)?;
+        Ok(vaddr)
     }
 
     /// unmap already mapped regions
     pub fn unmap(&mut self, vaddr: u64, size: u64) -> Result<(), IoError> {
-        self.maps.unmap(vaddr, size)
+        let result = self.maps.unmap(vaddr, size);
+        self.log(IoOp::Unmap, vaddr, size, None, result.is_ok());
+        result
+    }
+
+    /// Map every `(paddr, vaddr, size)` entry of `layout` in order. Either all entries get
+    /// mapped or none: if any entry fails to map, entries that were mapped before it are
+    /// unmapped again and the error is returned. Entries of size zero are rejected before
+    /// anything gets mapped.
+    pub fn apply_map_layout(&mut self, layout: &[(u64, u64, u64)]) -> Result<(), IoError> {
+        if let Some(i) = layout.iter().position(|&(_, _, size)| size == 0) {
+            return Err(IoError::Custom(format!("Layout entry {i} has zero size")));
+        }
+        for (i, &(paddr, vaddr, size)) in layout.iter().enumerate() {
+            if let Err(e) = self.map(paddr, vaddr, size) {
+                for &(_, vaddr, size) in layout[..i].iter().rev() {
+                    self.unmap(vaddr, size).unwrap();
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
     }
 
     /// read memory from virtual address space. If there is no enough
     /// data to fill *buf* an error is returned.
-    pub fn vread(&mut self, vaddr: u64, buf: &mut [u8]) -> Result<(), IoError> {
-        let result = self.maps.split_vaddr_range(vaddr, buf.len() as u64);
-        if let Some(maps) = result {
-            let mut start = 0;
-            for map in maps {
-                self.pread(
-                    map.paddr,
-                    &mut buf[start as usize..(start + map.size) as usize],
-                )?;
-                start += map.size;
-            }
-            Ok(())
-        } else {
-            Err(IoError::AddressNotFound)
+    pub fn vread(&self, vaddr: u64, buf: &mut [u8]) -> Result<(), IoError> {
+        let maps = self
+            .maps
+            .split_vaddr_range(vaddr, buf.len() as u64)
+            .map_err(|at| IoError::AddressNotFound { at })?;
+        let mut start = 0;
+        for map in maps {
+            self.pread_logged(
+                map.paddr,
+                &mut buf[start as usize..(start + map.size) as usize],
+            )?;
+            self.hook(HookOp::VirRead, map.vaddr, map.size, true);
+            start += map.size;
         }
+        Ok(())
     }
     /// read memory from virtual address space. Data is stored in a sparce
     /// vector represented by [`BTreeMap`]. Error is returned only in case of
     /// internal IO errors.
-    pub fn vread_sparce(&mut self, vaddr: u64, size: u64) -> Result<BTreeMap<u64, u8>, IoError> {
+    pub fn vread_sparce(&self, vaddr: u64, size: u64) -> Result<BTreeMap<u64, u8>, IoError> {
         let mut result = BTreeMap::new();
         let maps = self.maps.split_vaddr_sparce_range(vaddr, size);
         for map in maps {
             let mut buf = vec![0; map.size as usize];
-            self.pread(map.paddr, &mut buf)?;
+            self.pread_logged(map.paddr, &mut buf)?;
+            self.hook(HookOp::VirRead, map.vaddr, map.size, true);
             for (i, v) in buf.iter().enumerate() {
                 result.insert(map.vaddr + i as u64, *v);
             }
         }
         Ok(result)
     }
-    /// write memory into virtual address space
+    /// Same as [`RIO::vread_sparce`] but bytes are read the same way
+    /// [`RIO::pread_original_sparce`] reads them.
+    pub fn vread_original_sparce(
+        &self,
+        vaddr: u64,
+        size: u64,
+    ) -> Result<BTreeMap<u64, u8>, IoError> {
+        let mut result = BTreeMap::new();
+        let maps = self.maps.split_vaddr_sparce_range(vaddr, size);
+        for map in maps {
+            for (paddr, v) in self.pread_original_sparce(map.paddr, map.size)? {
+                result.insert(paddr - map.paddr + map.vaddr, v);
+            }
+        }
+        Ok(result)
+    }
+    /// write memory into virtual address space, all the physical writes it takes are undone
+    /// and redone together by the write journal.
     pub fn vwrite(&mut self, vaddr: u64, buf: &[u8]) -> Result<(), IoError> {
-        let result = self.maps.split_vaddr_range(vaddr, buf.len() as u64);
-        if let Some(maps) = result {
-            let mut start = 0;
-            for map in maps {
-                self.pwrite(map.paddr, &buf[start as usize..(start + map.size) as usize])?;
-                start += map.size;
+        let maps = self
+            .maps
+            .split_vaddr_range(vaddr, buf.len() as u64)
+            .map_err(|at| IoError::AddressNotFound { at })?;
+        if let Some(journal) = &mut self.journal {
+            journal.begin_group();
+        }
+        let mut start = 0;
+        let mut ret = Ok(());
+        for map in maps {
+            ret = self.pwrite_logged(map.paddr, &buf[start as usize..(start + map.size) as usize]);
+            self.hook(HookOp::VirWrite, map.vaddr, map.size, ret.is_ok());
+            if ret.is_err() {
+                break;
             }
-            Ok(())
-        } else {
-            Err(IoError::AddressNotFound)
+            start += map.size;
+        }
+        if let Some(journal) = &mut self.journal {
+            journal.end_group();
+        }
+        ret
+    }
+    /// Fill `size` bytes of the virtual address space starting at `vaddr` with `byte`.
+    pub fn vfill(&mut self, vaddr: u64, size: u64, byte: u8) -> Result<(), IoError> {
+        let maps = self
+            .maps
+            .split_vaddr_range(vaddr, size)
+            .map_err(|at| IoError::AddressNotFound { at })?;
+        for map in maps {
+            self.pfill_logged(map.paddr, map.size, byte)?;
+            self.hook(HookOp::VirWrite, map.vaddr, map.size, true);
         }
+        Ok(())
     }
 
+    typed_read!(pread_u16, pread, u16, "physical");
+    typed_read!(pread_u32, pread, u32, "physical");
+    typed_read!(pread_u64, pread, u64, "physical");
+    typed_read!(pread_i16, pread, i16, "physical");
+    typed_read!(pread_i32, pread, i32, "physical");
+    typed_read!(pread_i64, pread, i64, "physical");
+    typed_read!(vread_u16, vread, u16, "virtual");
+    typed_read!(vread_u32, vread, u32, "virtual");
+    typed_read!(vread_u64, vread, u64, "virtual");
+    typed_read!(vread_i16, vread, i16, "virtual");
+    typed_read!(vread_i32, vread, i32, "virtual");
+    typed_read!(vread_i64, vread, i64, "virtual");
+
     /// convert virtual address to physical address
     #[must_use]
     pub fn vir_to_phy(&self, vaddr: u64, size: u64) -> Option<Vec<RIOMap>> {
-        self.maps.split_vaddr_range(vaddr, size)
+        self.maps.split_vaddr_range(vaddr, size).ok()
     }
     /// This funciton reverse-queries individual physical addresses. It convert
     /// physical address to virtual address. The return value is a vector of
@@ -359,39 +1014,662 @@ impl RIO {
     pub fn map_iter<'a>(&'a self) -> Box<dyn Iterator<Item = Arc<RIOMap>> + 'a> {
         self.maps.into_iter()
     }
-    /// Return equivalent [`RIODesc`] structure for the given *hndl*
-    #[must_use]
-    pub fn hndl_to_desc(&self, hndl: u64) -> Option<&RIODesc> {
-        self.descs.hndl_to_desc(hndl)
+    /// Returns `(start, size)` of every hole in the physical address space between the lowest
+    /// and the highest opened files, sorted by address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoMode, RIO};
+    /// let mut io = RIO::new();
+    /// io.open_at("malloc://0x10", IoMode::READ | IoMode::WRITE, 0x0).unwrap();
+    /// io.open_at("malloc://0x10", IoMode::READ | IoMode::WRITE, 0x30).unwrap();
+    /// assert_eq!(io.gaps(), vec![(0x10, 0x20)]);
+    /// ```
+    #[must_use]
+    pub fn gaps(&self) -> Vec<(u64, u64)> {
+        let mut ranges: Vec<(u64, u64)> = self
+            .uri_iter()
+            .map(|desc| (desc.paddr_base(), desc.size()))
+            .collect();
+        ranges.sort_unstable();
+        Self::holes(&ranges)
+    }
+
+    /// Returns the lowest physical address that has at least `min_size` free bytes right after
+    /// it, including the space before the lowest file and after the highest one. It can be
+    /// used to pick addresses for [`RIO::open_at`] deterministically.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoMode, RIO};
+    /// let mut io = RIO::new();
+    /// io.open_at("malloc://0x10", IoMode::READ | IoMode::WRITE, 0x10).unwrap();
+    /// assert_eq!(io.largest_free_region(0x10), Some(0x0));
+    /// assert_eq!(io.largest_free_region(0x11), Some(0x20));
+    /// ```
+    #[must_use]
+    pub fn largest_free_region(&self, min_size: u64) -> Option<u64> {
+        let mut ranges: Vec<(u64, u64)> = self
+            .uri_iter()
+            .map(|desc| (desc.paddr_base(), desc.size()))
+            .collect();
+        ranges.sort_unstable();
+        Self::lowest_fit(&ranges, min_size)
+    }
+
+    // Lowest address with `min_size` free bytes around sorted, non overlapping
+    // `(start, size)` ranges.
+    fn lowest_fit(ranges: &[(u64, u64)], min_size: u64) -> Option<u64> {
+        let mut start = 0;
+        for &(addr, size) in ranges {
+            if addr - start >= min_size {
+                return Some(start);
+            }
+            start = addr + size;
+        }
+        // the free space after the highest range is `u64::MAX - start + 1` bytes.
+        (min_size == 0 || u64::MAX - start >= min_size - 1).then_some(start)
+    }
+
+    /// Returns `(start, size)` of every hole in the virtual address space between the lowest
+    /// and the highest maps, sorted by address.
+    #[must_use]
+    pub fn vgaps(&self) -> Vec<(u64, u64)> {
+        let ranges: Vec<(u64, u64)> = self.map_iter().map(|map| (map.vaddr, map.size)).collect();
+        Self::holes(&ranges)
+    }
+
+    // Holes between sorted, non overlapping `(start, size)` ranges.
+    fn holes(ranges: &[(u64, u64)]) -> Vec<(u64, u64)> {
+        ranges
+            .windows(2)
+            .filter_map(|pair| {
+                let end = pair[0].0 + pair[0].1;
+                (end < pair[1].0).then(|| (end, pair[1].0 - end))
+            })
+            .collect()
+    }
+
+    /// Returns the handle of the file that backs physical address `paddr` and the offset of
+    /// `paddr` inside that file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoMode, RIO};
+    /// let mut io = RIO::new();
+    /// let hndl = io.open_at("malloc://0x10", IoMode::READ | IoMode::WRITE, 0x100).unwrap();
+    /// assert_eq!(io.paddr_to_hndl(0x104), Some((hndl, 0x4)));
+    /// assert_eq!(io.paddr_to_hndl(0x110), None);
+    /// ```
+    #[must_use]
+    pub fn paddr_to_hndl(&self, paddr: u64) -> Option<(u64, u64)> {
+        let (hndl, ..) = *self.descs.paddr_range_to_hndl(paddr, 1).ok()?.first()?;
+        let desc = self.hndl_to_desc(hndl)?;
+        Some((hndl, paddr - desc.paddr_base()))
+    }
+
+    /// Returns the physical address that virtual address `vaddr` is mapped to.
+    #[must_use]
+    pub fn vaddr_to_paddr(&self, vaddr: u64) -> Option<u64> {
+        let maps = self.maps.split_vaddr_range(vaddr, 1).ok()?;
+        Some(maps.first()?.paddr)
+    }
+
+    /// Return equivalent [`RIODesc`] structure for the given *hndl*
+    #[must_use]
+    pub fn hndl_to_desc(&self, hndl: u64) -> Option<&RIODesc> {
+        self.descs.hndl_to_desc(hndl)
+    }
+
+    /// Start recording IO operations into a trace log that holds at most `capacity`
+    /// records, older records are dropped first. Enabling an already enabled log clears it.
+    pub fn enable_io_log(&mut self, capacity: usize) {
+        *self.log.get_mut() = Some(IoLog::new(capacity));
+    }
+
+    /// Stop recording IO operations and drop the trace log.
+    pub fn disable_io_log(&mut self) {
+        *self.log.get_mut() = None;
+    }
+
+    /// Returns *true* if IO operations are being recorded.
+    #[must_use]
+    pub fn is_io_log_enabled(&self) -> bool {
+        self.log.lock().is_some()
+    }
+
+    /// Returns copy of recorded IO operations from oldest to newest.
+    #[must_use]
+    pub fn io_log(&self) -> Vec<IoLogRecord> {
+        self.log
+            .lock()
+            .as_ref()
+            .map(|log| log.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Call `hook` with the kind of access, the address and the size of every successful
+    /// [`RIO::pread`], [`RIO::vread`], [`RIO::vread_sparce`] and typed read. Virtual reads call
+    /// `hook` once for each mapped range they read with its virtual address. The hook replaces
+    /// any hook that was set before.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoMode, RIO};
+    /// let mut io = RIO::new();
+    /// io.open("malloc://0x20", IoMode::READ | IoMode::WRITE).unwrap();
+    /// io.set_read_hook(Box::new(|op, addr, size| {
+    ///     println!("{op:?} of 0x{size:x} bytes at 0x{addr:x}")
+    /// }));
+    /// io.pread(0x10, &mut [0; 4]).unwrap();
+    /// ```
+    pub fn set_read_hook(&mut self, hook: IoHook) {
+        self.read_hook = Some(Mutex::new(hook));
+    }
+
+    /// Same as [`RIO::set_read_hook`] but `hook` is called for every successful write or fill
+    /// of the physical or virtual address space.
+    pub fn set_write_hook(&mut self, hook: IoHook) {
+        self.write_hook = Some(Mutex::new(hook));
+    }
+
+    /// Check whether a write hook is set, see [`RIO::set_write_hook`].
+    #[must_use]
+    pub fn has_write_hook(&self) -> bool {
+        self.write_hook.is_some()
+    }
+
+    /// Remove the read hook only, see [`RIO::set_read_hook`].
+    pub fn clear_read_hook(&mut self) {
+        self.read_hook = None;
+    }
+
+    /// Remove the write hook only, see [`RIO::set_write_hook`].
+    pub fn clear_write_hook(&mut self) {
+        self.write_hook = None;
+    }
+
+    /// Remove the read and write hooks, see [`RIO::set_read_hook`].
+    pub fn clear_hooks(&mut self) {
+        self.read_hook = None;
+        self.write_hook = None;
+    }
+
+    /// Start recording writes into a journal so they can be undone with [`RIO::undo`] and
+    /// redone with [`RIO::redo`]. At most `depth` writes are kept, older writes are dropped
+    /// first. Enabling an already enabled journal clears it.
+    ///
+    /// Every [`RIO::pwrite`] records the bytes it overwrote as read through the descriptor, so
+    /// for Copy-On-Write files undo restores what the Copy-On-Write layer served before the
+    /// write. A [`RIO::vwrite`] is recorded as one entry even if it spans more than one map.
+    /// [`RIO::pfill`] and [`RIO::vfill`] are not recorded. Closing or reopening files clears
+    /// the journal since physical addresses may then refer to different data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rair_io::{IoError, IoMode, RIO};
+    /// fn main() -> Result<(), IoError> {
+    ///     let mut io = RIO::new();
+    ///     io.open("malloc://0x20", IoMode::READ | IoMode::WRITE)?;
+    ///     io.enable_write_journal(100);
+    ///     io.pwrite(0x10, &[0xff])?;
+    ///     assert!(io.undo()?);
+    ///     let mut fillme = [0xff];
+    ///     io.pread(0x10, &mut fillme)?;
+    ///     assert_eq!(fillme, [0]);
+    ///     return Ok(());
+    /// }
+    /// ```
+    pub fn enable_write_journal(&mut self, depth: usize) {
+        self.journal = Some(WriteJournal::new(depth));
+    }
+
+    /// Stop recording writes and drop the write journal.
+    pub fn disable_write_journal(&mut self) {
+        self.journal = None;
+    }
+
+    /// Returns *true* if writes are being recorded in the write journal.
+    #[must_use]
+    pub fn is_write_journal_enabled(&self) -> bool {
+        self.journal.is_some()
+    }
+
+    /// Undo the most recent write in the journal by writing back the bytes it overwrote.
+    /// Returns *false* if there is nothing to undo. If writing fails the entry is kept so
+    /// that it can be retried.
+    pub fn undo(&mut self) -> Result<bool, IoError> {
+        let Some(group) = self.journal.as_mut().and_then(WriteJournal::pop_undo) else {
+            return Ok(false);
+        };
+        match self.replay(group.iter().rev().map(|c| (c.paddr, &*c.before))) {
+            Ok(()) => self.journal.as_mut().unwrap().push_redo(group),
+            Err(e) => {
+                self.journal.as_mut().unwrap().push_undo(group);
+                return Err(e);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Redo the most recently undone write. Returns *false* if there is nothing to redo.
+    /// Any write done after an undo drops everything that can be redone.
+    pub fn redo(&mut self) -> Result<bool, IoError> {
+        let Some(group) = self.journal.as_mut().and_then(WriteJournal::pop_redo) else {
+            return Ok(false);
+        };
+        match self.replay(group.iter().map(|c| (c.paddr, &*c.after))) {
+            Ok(()) => self.journal.as_mut().unwrap().push_undo(group),
+            Err(e) => {
+                self.journal.as_mut().unwrap().push_redo(group);
+                return Err(e);
+            }
+        }
+        Ok(true)
+    }
+
+    // Write `changes` without recording them in the journal.
+    fn replay<'a>(
+        &mut self,
+        mut changes: impl Iterator<Item = (u64, &'a [u8])>,
+    ) -> Result<(), IoError> {
+        let journal = self.journal.take();
+        let ret = changes.try_for_each(|(paddr, bytes)| self.pwrite(paddr, bytes));
+        self.journal = journal;
+        ret
+    }
+
+    fn clear_write_journal(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            journal.clear();
+        }
+    }
+
+    // Run the hook of successful reads and writes.
+    fn hook(&self, op: HookOp, addr: u64, size: u64, ok: bool) {
+        let hook = match op {
+            HookOp::PhyRead | HookOp::VirRead => self.read_hook.as_ref(),
+            HookOp::PhyWrite | HookOp::VirWrite => self.write_hook.as_ref(),
+        };
+        if let (true, Some(hook)) = (ok, hook) {
+            (hook.lock())(op, addr, size);
+        }
+    }
+
+    // Record `op` in the trace log.
+    fn log(&self, op: IoOp, addr: u64, size: u64, hndl: Option<u64>, ok: bool) {
+        if let Some(log) = self.log.lock().as_mut() {
+            log.push(IoLogRecord {
+                op,
+                addr,
+                size,
+                hndl,
+                ok,
+            });
+        }
+    }
+
+    fn log_open(&self, result: &Result<u64, IoError>) {
+        if !self.is_io_log_enabled() {
+            return;
+        }
+        match result {
+            Ok(hndl) => {
+                let desc = self.descs.hndl_to_desc(*hndl).unwrap();
+                let (addr, size) = (desc.paddr_base(), desc.size());
+                self.log(IoOp::Open, addr, size, Some(*hndl), true);
+            }
+            Err(_) => self.log(IoOp::Open, 0, 0, None, false),
+        }
+    }
+
+    fn register_open_with(
+        &mut self,
+        plugin_name: &str,
+        uri: &str,
+        flags: IoMode,
+        at: Option<u64>,
+    ) -> Result<u64, IoError> {
+        let plugin = self
+            .plugins
+            .iter_mut()
+            .find(|plugin| plugin.get_metadata().name.eq_ignore_ascii_case(plugin_name))
+            .ok_or_else(|| {
+                IoError::Custom(format!("No loaded IO plugin is named {plugin_name}"))
+            })?;
+        Self::check_modes(&**plugin, flags)?;
+        match at {
+            Some(at) => self.descs.register_open_at(&mut **plugin, uri, flags, at),
+            None => self
+                .descs
+                .register_open_default(&mut **plugin, uri, flags)
+                .or_else(|_| self.descs.register_open(&mut **plugin, uri, flags)),
+        }
+    }
+
+    // The plugin that opened `desc` if it is still loaded, otherwise the first one accepting its URI.
+    fn plugin_of<'a>(
+        plugins: &'a mut [Box<dyn RIOPlugin + Sync + Send>],
+        desc: &RIODesc,
+    ) -> Option<&'a mut Box<dyn RIOPlugin + Sync + Send>> {
+        let index = plugins
+            .iter()
+            .position(|plugin| plugin.get_metadata().name == desc.plugin_name())
+            .or_else(|| {
+                plugins
+                    .iter()
+                    .position(|plugin| plugin.accept_uri(&desc.name))
+            })?;
+        plugins.get_mut(index)
+    }
+
+    fn check_modes(plugin: &(dyn RIOPlugin + Sync + Send), flags: IoMode) -> Result<(), IoError> {
+        // EXEC is only informative, so plugins never have to support it.
+        let unsupported = flags - plugin.supported_modes() - IoMode::EXEC;
+        if unsupported.is_empty() {
+            return Ok(());
+        }
+        Err(IoError::Custom(format!(
+            "Plugin {} does not support {unsupported}",
+            plugin.get_metadata().name
+        )))
+    }
+
+    fn first_hndl(operations: Option<&[(u64, u64, u64)]>) -> Option<u64> {
+        operations.and_then(<[_]>::first).map(|op| op.0)
+    }
+}
+
+#[cfg(test)]
+mod rio_tests {
+
+    use super::*;
+    use crate::plugin::{RIOPluginDesc, RIOPluginMetadata};
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+    use std::thread;
+    use test_file::*;
+    fn test_failing_open_cb(path: &[&Path]) {
+        let mut io = RIO::new();
+        let mut bad_path = "badformat://".to_owned();
+        bad_path.push_str(&path[0].to_string_lossy());
+        let mut e = io.open(&bad_path, IoMode::READ);
+        assert_eq!(
+            e.err().unwrap(),
+            IoError::IoPluginNotFoundError(bad_path.clone())
+        );
+        e = io.open_at(&bad_path, IoMode::READ, 0x500);
+        let err = e.err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            format!("Can not find Suitable IO Plugin for {bad_path}.")
+        );
+        assert_eq!(err, IoError::IoPluginNotFoundError(bad_path));
+        io.open(&path[0].to_string_lossy(), IoMode::READ).unwrap();
+        e = io.open_at(&path[1].to_string_lossy(), IoMode::READ, 0);
+        assert_eq!(e.err().unwrap(), IoError::AddressesOverlapError);
+        io.open(&path[1].to_string_lossy(), IoMode::READ).unwrap();
+        e = io.open_at(&path[1].to_string_lossy(), IoMode::READ, 0);
+        assert_eq!(e.err().unwrap(), IoError::AddressesOverlapError);
+        io.close_all();
+    }
+    #[test]
+    fn test_failing_open() {
+        operate_on_files(&test_failing_open_cb, &[DATA, DATA]);
+    }
+    struct ReadOnlyPlugin;
+    const READ_ONLY_METADATA: RIOPluginMetadata = RIOPluginMetadata {
+        name: "ReadOnly",
+        desc: "",
+        author: "",
+        license: "",
+        version: "",
+    };
+    impl RIOPlugin for ReadOnlyPlugin {
+        fn get_metadata(&self) -> &'static RIOPluginMetadata {
+            &READ_ONLY_METADATA
+        }
+        fn open(&mut self, _uri: &str, _flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+            panic!("Unsupported modes must be rejected before calling open");
+        }
+        fn accept_uri(&self, uri: &str) -> bool {
+            uri.starts_with("ro://")
+        }
+        fn supported_modes(&self) -> IoMode {
+            IoMode::READ
+        }
+    }
+    struct ShadowPlugin;
+    const SHADOW_METADATA: RIOPluginMetadata = RIOPluginMetadata {
+        name: "Shadow",
+        desc: "",
+        author: "",
+        license: "",
+        version: "",
+    };
+    impl RIOPlugin for ShadowPlugin {
+        fn get_metadata(&self) -> &'static RIOPluginMetadata {
+            &SHADOW_METADATA
+        }
+        fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+            Ok(RIOPluginDesc {
+                name: uri.to_owned(),
+                perm: flags,
+                raddr: 0,
+                size: 0x10,
+                plugin_operations: Box::default(),
+            })
+        }
+        fn accept_uri(&self, _uri: &str) -> bool {
+            true
+        }
+    }
+    #[test]
+    fn test_plugin_precedence() {
+        let mut io = RIO::new();
+        io.load_plugin(Box::new(ShadowPlugin));
+        // the default plugin is tried first and fails to open missing files.
+        io.open("/no/such/file", IoMode::READ).unwrap_err();
+        assert_eq!(io.plugins_iter().last().unwrap().name, "Shadow");
+        io.unload_plugin_by_name("Shadow").unwrap();
+        io.load_plugin_front(Box::new(ShadowPlugin));
+        assert_eq!(io.plugins_iter().next().unwrap().name, "Shadow");
+        let hndl = io.open("/no/such/file", IoMode::READ).unwrap();
+        assert_eq!(io.hndl_to_desc(hndl).unwrap().size(), 0x10);
+        io.unload_plugin_by_name("Shadow").unwrap();
+        assert_eq!(
+            io.unload_plugin_by_name("Shadow").err().unwrap(),
+            IoError::Custom("No loaded IO plugin is named Shadow".to_owned())
+        );
+        io.open("/no/such/file", IoMode::READ).unwrap_err();
+    }
+    const IHEX: &[u8] = b":0100000041BE\n:00000001FF\n";
+    fn plugin_name_cb(path: &Path) {
+        let mut io = RIO::new();
+        let uri = path.to_string_lossy();
+        let plain = io.open(&uri, IoMode::READ).unwrap();
+        let ihex = io.open(&format!("ihex://{uri}"), IoMode::READ).unwrap();
+        assert_eq!(io.hndl_to_desc(plain).unwrap().plugin_name(), "FilePlugin");
+        assert_eq!(io.hndl_to_desc(ihex).unwrap().plugin_name(), "IHex");
+        // reopening sticks to the plugin that opened the file.
+        io.load_plugin_front(Box::new(ShadowPlugin));
+        io.reopen(plain, IoMode::READ).unwrap();
+        assert_eq!(io.hndl_to_desc(plain).unwrap().plugin_name(), "FilePlugin");
+        assert_eq!(io.hndl_to_desc(plain).unwrap().size(), IHEX.len() as u64);
+        let forked = io.fork().unwrap();
+        assert_eq!(forked.hndl_to_desc(ihex).unwrap().plugin_name(), "IHex");
+    }
+    #[test]
+    fn test_plugin_name() {
+        operate_on_file(&plugin_name_cb, IHEX);
+    }
+    fn open_with_cb(path: &Path) {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        let hndl = io.open_with("malloc", "malloc://0x10", perm).unwrap();
+        assert_eq!(io.hndl_to_desc(hndl).unwrap().plugin_name(), "Malloc");
+        // plain paths are opened by the default plugin unless another one is forced.
+        let b64 = io
+            .open_at_with("Base64", &path.to_string_lossy(), IoMode::READ, 0x100)
+            .unwrap();
+        let mut fillme = [0; 5];
+        io.pread(0x100, &mut fillme).unwrap();
+        assert_eq!(&fillme, b"hello");
+        assert_eq!(io.hndl_to_desc(b64).unwrap().plugin_name(), "Base64");
+        assert_eq!(
+            io.open_with("nothing", "malloc://0x10", perm)
+                .err()
+                .unwrap(),
+            IoError::Custom("No loaded IO plugin is named nothing".to_owned())
+        );
+        assert_eq!(
+            io.open_with("malloc", "malloc://0x10", IoMode::COW)
+                .err()
+                .unwrap(),
+            IoError::Custom("Plugin Malloc does not support COW".to_owned())
+        );
+    }
+    #[test]
+    fn test_open_with() {
+        operate_on_file(&open_with_cb, b"aGVsbG8=");
+    }
+    #[test]
+    fn test_pwrite_strict() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        io.open_at("malloc://0x10", perm, 0x0).unwrap();
+        io.open_at("malloc://0x10", perm, 0x10).unwrap();
+        let data = [0xff; 8];
+        assert_eq!(
+            io.pwrite_strict(0xc, &data).err().unwrap(),
+            IoError::CrossDescriptorWrite
+        );
+        let mut fillme = [1; 8];
+        io.pread(0xc, &mut fillme).unwrap();
+        assert_eq!(fillme, [0; 8]);
+        io.pwrite_strict(0x8, &data).unwrap();
+        io.pwrite_strict(0x10, &data).unwrap();
+        assert_eq!(
+            io.pwrite_strict(0x1c, &data).err().unwrap(),
+            IoError::AddressNotFound { at: 0x20 }
+        );
+        io.pwrite(0x4, &data).unwrap();
+        io.pwrite(0xc, &data).unwrap();
+        let mut fillme = [0; 0x20];
+        io.pread(0x0, &mut fillme).unwrap();
+        let mut expected = [0; 0x20];
+        expected[0x4..0x18].copy_from_slice(&[0xff; 0x14]);
+        assert_eq!(fillme, expected);
+    }
+    #[test]
+    fn test_apply_map_layout() {
+        let mut io = RIO::new();
+        io.open("malloc://0x300", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        let layout = [
+            (0x0, 0x1000, 0x100),
+            (0x100, 0x3000, 0x100),
+            (0x200, 0x2000, 0x100),
+        ];
+        io.apply_map_layout(&layout).unwrap();
+        for (paddr, vaddr, size) in layout {
+            let maps = io.vir_to_phy(vaddr, size).unwrap();
+            assert_eq!(maps.len(), 1);
+            assert_eq!((maps[0].paddr, maps[0].size), (paddr, size));
+        }
+        // last entry overlaps an existing map, so the whole layout is rolled back.
+        let bad = [
+            (0x0, 0x5000, 0x100),
+            (0x100, 0x6000, 0x100),
+            (0x200, 0x1050, 0x10),
+        ];
+        assert_eq!(
+            io.apply_map_layout(&bad).err().unwrap(),
+            IoError::AddressesOverlapError
+        );
+        assert_eq!(io.vir_to_phy(0x5000, 1), None);
+        assert_eq!(io.vir_to_phy(0x6000, 1), None);
+        assert_eq!(io.map_iter().count(), 3);
+        let empty = [(0x0, 0x5000, 0x100), (0x100, 0x6000, 0x0)];
+        assert_eq!(
+            io.apply_map_layout(&empty).err().unwrap(),
+            IoError::Custom("Layout entry 1 has zero size".to_owned())
+        );
+        assert_eq!(io.vir_to_phy(0x5000, 1), None);
+        assert_eq!(io.map_iter().count(), 3);
+    }
+    #[test]
+    fn test_unsupported_modes() {
+        let mut io = RIO::new();
+        io.load_plugin(Box::new(ReadOnlyPlugin));
+        let e = io.open("ro://foo", IoMode::READ | IoMode::WRITE);
+        assert_eq!(
+            e.err().unwrap(),
+            IoError::Custom("Plugin ReadOnly does not support WRITE".to_owned())
+        );
+        let e = io.open_at("ro://foo", IoMode::COW, 0x500);
+        assert_eq!(
+            e.err().unwrap(),
+            IoError::Custom("Plugin ReadOnly does not support COW".to_owned())
+        );
+        let e = io.open("malloc://0x50", IoMode::READ | IoMode::COW);
+        assert_eq!(
+            e.err().unwrap(),
+            IoError::Custom("Plugin Malloc does not support COW".to_owned())
+        );
     }
-}
-
-#[cfg(test)]
-mod rio_tests {
-
-    use super::*;
-    use std::io;
-    use std::path::Path;
-    use test_file::*;
-    fn test_failing_open_cb(path: &[&Path]) {
+    fn test_fork_cb(paths: &[&Path]) {
         let mut io = RIO::new();
-        let mut bad_path = "badformat://".to_owned();
-        bad_path.push_str(&path[0].to_string_lossy());
-        let mut e = io.open(&bad_path, IoMode::READ);
-        assert_eq!(e.err().unwrap(), IoError::IoPluginNotFoundError);
-        e = io.open_at(&bad_path, IoMode::READ, 0x500);
-        assert_eq!(e.err().unwrap(), IoError::IoPluginNotFoundError);
-        io.open(&path[0].to_string_lossy(), IoMode::READ).unwrap();
-        e = io.open_at(&path[1].to_string_lossy(), IoMode::READ, 0);
-        assert_eq!(e.err().unwrap(), IoError::AddressesOverlapError);
-        io.open(&path[1].to_string_lossy(), IoMode::READ).unwrap();
-        e = io.open_at(&path[1].to_string_lossy(), IoMode::READ, 0);
-        assert_eq!(e.err().unwrap(), IoError::AddressesOverlapError);
+        let perm = IoMode::READ | IoMode::WRITE;
+        io.open(&paths[0].to_string_lossy(), perm).unwrap();
+        io.open(&paths[1].to_string_lossy(), IoMode::COW).unwrap();
+        let hndl = io.open("malloc://0x10", perm).unwrap();
+        io.close(0).unwrap();
+        let size = DATA.len() as u64;
+        io.open_at(&paths[0].to_string_lossy(), perm, 0x5000)
+            .unwrap();
+        io.map(0x5000, 0x1000, size).unwrap();
+        io.pwrite(0x5000, &[0xaa]).unwrap();
+        io.pwrite(size, &[0xbb]).unwrap();
+        io.pwrite(size * 2, &[0xcc]).unwrap();
+        let mut fork = io.fork().unwrap();
+        assert_eq!(
+            fork.uri_iter().map(RIODesc::hndl).collect::<Vec<_>>(),
+            [0, 1, hndl]
+        );
+        assert_eq!(fork.hndl_to_desc(0).unwrap().perm(), IoMode::COW);
+        assert_eq!(fork.hndl_to_desc(hndl).unwrap().perm(), perm);
+        assert_eq!(fork.map_iter().count(), 1);
+        let mut fillme = [0; 1];
+        fork.vread(0x1000, &mut fillme).unwrap();
+        assert_eq!(fillme, [0xaa]);
+        for (addr, byte) in [(size, 0xbb), (size * 2, 0xcc)] {
+            fork.pread(addr, &mut fillme).unwrap();
+            assert_eq!(fillme, [byte]);
+        }
+        for addr in [0x5000, size, size * 2] {
+            fork.pwrite(addr, &[0x11]).unwrap();
+        }
+        for (addr, byte) in [(0x5000, 0xaa), (size, 0xbb), (size * 2, 0xcc)] {
+            io.pread(addr, &mut fillme).unwrap();
+            assert_eq!(fillme, [byte]);
+        }
+        drop(fork);
         io.close_all();
+        let data = fs::read(paths[0]).unwrap();
+        assert_eq!(data[0], 0xaa);
+        assert_eq!(data[1..], DATA[1..]);
+        assert_eq!(fs::read(paths[1]).unwrap(), DATA);
     }
     #[test]
-    fn test_failing_open() {
-        operate_on_files(&test_failing_open_cb, &[DATA, DATA]);
+    fn test_fork() {
+        operate_on_files(&test_fork_cb, &[DATA, DATA]);
     }
     fn test_pread_cb(paths: &[&Path]) {
         let mut io = RIO::new();
@@ -425,15 +1703,49 @@ mod rio_tests {
     fn test_pread() {
         operate_on_files(&test_pread_cb, &[DATA, DATA, DATA]);
     }
+    fn test_pread_typed_cb(paths: &[&Path]) {
+        let mut io = RIO::new();
+        for path in paths {
+            io.open(&path.to_string_lossy(), IoMode::READ).unwrap();
+        }
+        let end = DATA.len() as u64;
+        // DATA ends with 0x4d, 0xd5 and starts with 0x00, 0x01, 0x01
+        assert_eq!(io.pread_u16(end - 1, Endian::Little).unwrap(), 0x00d5);
+        assert_eq!(io.pread_u16(end - 1, Endian::Big).unwrap(), 0xd500);
+        assert_eq!(io.pread_u32(end - 1, Endian::Big).unwrap(), 0xd500_0101);
+        assert_eq!(io.pread_u32(end - 1, Endian::Little).unwrap(), 0x0101_00d5);
+        assert_eq!(
+            io.pread_u64(end * 2 - 2, Endian::Big).unwrap(),
+            0x4dd5_0001_0102_0305
+        );
+        assert_eq!(
+            io.pread_u64(end * 2 - 2, Endian::Native).unwrap(),
+            u64::from_ne_bytes([0x4d, 0xd5, 0x00, 0x01, 0x01, 0x02, 0x03, 0x05])
+        );
+        assert_eq!(io.pread_i16(end - 1, Endian::Big).unwrap(), -0x2b00);
+        assert_eq!(io.pread_i32(end - 1, Endian::Big).unwrap(), -0x2aff_feffi32);
+        assert_eq!(
+            io.pread_i64(end * 2 - 2, Endian::Big).unwrap(),
+            0x4dd5_0001_0102_0305
+        );
+        assert_eq!(
+            io.pread_u32(end * 3 - 2, Endian::Little).unwrap_err(),
+            IoError::AddressNotFound { at: end * 3 }
+        );
+    }
+    #[test]
+    fn test_pread_typed() {
+        operate_on_files(&test_pread_typed_cb, &[DATA, DATA, DATA]);
+    }
     fn test_fail_pread_cb(paths: &[&Path]) {
         let mut io = RIO::new();
         let mut fillme: Vec<u8> = vec![0; 8];
         io.open(&paths[0].to_string_lossy(), IoMode::READ).unwrap();
         let mut e = io.pread(0x500, &mut fillme);
-        assert_eq!(e.err().unwrap(), IoError::AddressNotFound);
+        assert_eq!(e.err().unwrap(), IoError::AddressNotFound { at: 0x500 });
         fillme = vec![0; DATA.len() + 1];
         e = io.pread(0, &mut fillme);
-        assert_eq!(e.err().unwrap(), IoError::AddressNotFound);
+        assert_eq!(e.err().unwrap(), IoError::AddressNotFound { at: 105 });
         io.open(&paths[1].to_string_lossy(), IoMode::READ).unwrap();
         io.open_at(
             &paths[2].to_string_lossy(),
@@ -443,13 +1755,79 @@ mod rio_tests {
         .unwrap();
         fillme = vec![0; DATA.len() * 3];
         e = io.pread(0, &mut fillme);
-        assert_eq!(e.err().unwrap(), IoError::AddressNotFound);
+        assert_eq!(e.err().unwrap(), IoError::AddressNotFound { at: 210 });
     }
     #[test]
     fn test_fail_pread() {
         operate_on_files(&test_fail_pread_cb, &[DATA, DATA, DATA]);
     }
+    fn test_shared_read_cb(paths: &[&Path]) {
+        let mut io = RIO::new();
+        for path in paths {
+            io.open(&path.to_string_lossy(), IoMode::READ).unwrap();
+        }
+        io.map(0, 0x1000, DATA.len() as u64 * 2).unwrap();
+        io.enable_io_log(0x1000);
+        let shared = &io;
+        thread::scope(|s| {
+            for _ in 0..2u8 {
+                s.spawn(move || {
+                    for i in 0..0x100u64 {
+                        let mut fillme = vec![0; DATA.len()];
+                        let size = DATA.len() as u64;
+                        shared.pread(i % 2 * size, &mut fillme).unwrap();
+                        assert_eq!(fillme, DATA);
+                        shared.vread(0x1000 + size - 4, &mut fillme[..8]).unwrap();
+                        assert_eq!(&fillme[..4], &DATA[DATA.len() - 4..]);
+                        assert_eq!(&fillme[4..8], &DATA[..4]);
+                    }
+                });
+            }
+        });
+        // every iteration of both threads logs one pread and one vread.
+        assert_eq!(io.io_log().len(), 2 * 0x100 * 2);
+    }
+    #[test]
+    fn test_shared_read() {
+        operate_on_files(&test_shared_read_cb, &[DATA, DATA]);
+    }
 
+    #[test]
+    fn test_pfill() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        io.open_at("malloc://0x1800", perm, 0x0).unwrap();
+        io.open_at("malloc://0x10", perm, 0x1800).unwrap();
+        io.open_at("malloc://0x1800", perm, 0x1810).unwrap();
+        io.pfill(0x10, 0x2ff0, 0xab).unwrap();
+        let mut fillme = vec![0; 0x3010];
+        io.pread(0, &mut fillme).unwrap();
+        assert_eq!(fillme[..0x10], [0; 0x10]);
+        assert!(fillme[0x10..0x3000].iter().all(|b| *b == 0xab));
+        assert_eq!(fillme[0x3000..], [0; 0x10]);
+        assert_eq!(
+            io.pfill(0x3000, 0x100, 0).unwrap_err(),
+            IoError::AddressNotFound { at: 0x3010 }
+        );
+    }
+    #[test]
+    fn test_vfill() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        io.open_at("malloc://0x100", perm, 0x0).unwrap();
+        io.open_at("malloc://0x100", perm, 0x100).unwrap();
+        io.map(0x100, 0x1000, 0x100).unwrap();
+        io.map(0x0, 0x1100, 0x100).unwrap();
+        io.vfill(0x10f0, 0x20, 0xcc).unwrap();
+        let mut fillme = [0; 0x20];
+        io.pread(0x1f0, &mut fillme[..0x10]).unwrap();
+        io.pread(0x0, &mut fillme[0x10..]).unwrap();
+        assert_eq!(fillme, [0xcc; 0x20]);
+        assert_eq!(
+            io.vfill(0x11f0, 0x20, 0).unwrap_err(),
+            IoError::AddressNotFound { at: 0x1200 }
+        );
+    }
     fn test_pwrite_cb(paths: &[&Path]) {
         let mut io = RIO::new();
         let mut fillme: Vec<u8> = vec![0; 8];
@@ -491,10 +1869,10 @@ mod rio_tests {
         io.open(&paths[0].to_string_lossy(), IoMode::READ | IoMode::WRITE)
             .unwrap();
         e = io.pwrite(0x500, &write_me);
-        assert_eq!(e.err().unwrap(), IoError::AddressNotFound);
+        assert_eq!(e.err().unwrap(), IoError::AddressNotFound { at: 0x500 });
         write_me = vec![0; DATA.len() + 1];
         e = io.pwrite(0, &write_me);
-        assert_eq!(e.err().unwrap(), IoError::AddressNotFound);
+        assert_eq!(e.err().unwrap(), IoError::AddressNotFound { at: 105 });
         io.open(&paths[1].to_string_lossy(), IoMode::READ | IoMode::WRITE)
             .unwrap();
         io.open_at(
@@ -505,7 +1883,7 @@ mod rio_tests {
         .unwrap();
         write_me = vec![0; DATA.len() * 3];
         e = io.pwrite(0, &write_me);
-        assert_eq!(e.err().unwrap(), IoError::AddressNotFound);
+        assert_eq!(e.err().unwrap(), IoError::AddressNotFound { at: 210 });
     }
     #[test]
     fn test_fail_pwrite() {
@@ -574,7 +1952,7 @@ mod rio_tests {
         );
         assert_eq!(
             io.map(0x200, 0x7000, 0x50).err().unwrap(),
-            IoError::AddressNotFound
+            IoError::AddressNotFound { at: 0x200 }
         );
     }
     #[test]
@@ -617,13 +1995,44 @@ mod rio_tests {
         assert_eq!(fillme, sanity_data);
         assert_eq!(
             io.vread(0x300, &mut fillme).err().unwrap(),
-            IoError::AddressNotFound
+            IoError::AddressNotFound { at: 0x300 }
         );
     }
     #[test]
     fn test_vread() {
         operate_on_files(&test_vread_cb, &[DATA, DATA, DATA]);
     }
+    fn test_vread_typed_cb(paths: &[&Path]) {
+        let mut io = RIO::new();
+        io.open_at(&paths[0].to_string_lossy(), IoMode::READ, 0x1000)
+            .unwrap();
+        io.open_at(&paths[1].to_string_lossy(), IoMode::READ, 0x2000)
+            .unwrap();
+        io.map(0x1000, 0x400, DATA.len() as u64).unwrap();
+        io.map(0x2000, 0x400 + DATA.len() as u64, DATA.len() as u64)
+            .unwrap();
+        let end = 0x400 + DATA.len() as u64;
+        assert_eq!(io.vread_u16(end - 1, Endian::Little).unwrap(), 0x00d5);
+        assert_eq!(io.vread_u32(end - 2, Endian::Big).unwrap(), 0x4dd5_0001);
+        assert_eq!(
+            io.vread_u64(end - 4, Endian::Little).unwrap(),
+            0x0201_0100_d54d_88c5
+        );
+        assert_eq!(io.vread_i16(end - 1, Endian::Big).unwrap(), -0x2b00);
+        assert_eq!(io.vread_i32(end - 1, Endian::Big).unwrap(), -0x2aff_feffi32);
+        assert_eq!(
+            io.vread_i64(end - 4, Endian::Big).unwrap(),
+            -0x3a77_b22a_fffe_fefe
+        );
+        assert_eq!(
+            io.vread_u64(0x3fc, Endian::Native).unwrap_err(),
+            IoError::AddressNotFound { at: 0x3fc }
+        );
+    }
+    #[test]
+    fn test_vread_typed() {
+        operate_on_files(&test_vread_typed_cb, &[DATA, DATA]);
+    }
 
     fn test_vwrite_cb(paths: &[&Path]) {
         let mut io = RIO::new();
@@ -669,7 +2078,7 @@ mod rio_tests {
         assert_eq!(fillme, vec![1; DATA.len() * 3]);
         assert_eq!(
             io.vwrite(0x300, &fillme).err().unwrap(),
-            IoError::AddressNotFound
+            IoError::AddressNotFound { at: 0x300 }
         );
     }
     #[test]
@@ -750,6 +2159,99 @@ mod rio_tests {
         operate_on_files(&map_iter_cb, &[DATA, DATA, DATA, DATA]);
     }
 
+    #[test]
+    fn test_gaps() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        assert!(io.gaps().is_empty());
+        io.open_at("malloc://0x100", perm, 0x1000).unwrap();
+        io.open_at("malloc://0x10", perm, 0x100).unwrap();
+        assert_eq!(io.gaps(), vec![(0x110, 0xef0)]);
+        io.open_at("malloc://0x20", perm, 0x800).unwrap();
+        // adjacent files leave no hole.
+        io.open_at("malloc://0x10", perm, 0x820).unwrap();
+        assert_eq!(io.gaps(), vec![(0x110, 0x6f0), (0x830, 0x7d0)]);
+        assert!(io.vgaps().is_empty());
+        io.map(0x1000, 0x5000, 0x10).unwrap();
+        io.map(0x1000, 0x4000, 0x100).unwrap();
+        io.map(0x100, 0x4100, 0x10).unwrap();
+        io.map(0x800, 0x8000, 0x30).unwrap();
+        assert_eq!(io.vgaps(), vec![(0x4110, 0xef0), (0x5010, 0x2ff0)]);
+    }
+
+    #[test]
+    fn test_largest_free_region() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        assert_eq!(io.largest_free_region(u64::MAX), Some(0));
+        io.open_at("malloc://0x100", perm, 0x100).unwrap();
+        io.open_at("malloc://0x100", perm, 0x300).unwrap();
+        io.open_at("malloc://0x100", perm, 0x500).unwrap();
+        assert_eq!(io.largest_free_region(0), Some(0));
+        assert_eq!(io.largest_free_region(0x100), Some(0));
+        assert_eq!(io.largest_free_region(0x101), Some(0x600));
+        io.open_at("malloc://0x10", perm, 0x0).unwrap();
+        assert_eq!(io.largest_free_region(0xf0), Some(0x10));
+        assert_eq!(io.largest_free_region(0xf1), Some(0x200));
+        // the only fit is above the highest file.
+        assert_eq!(io.largest_free_region(0x1000), Some(0x600));
+        assert_eq!(io.largest_free_region(u64::MAX - 0x5ff), Some(0x600));
+        assert_eq!(io.largest_free_region(u64::MAX - 0x5fe), None);
+    }
+
+    #[test]
+    fn test_addr_to_hndl() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        let hndls = [
+            io.open_at("malloc://0x100", perm, 0x0).unwrap(),
+            io.open_at("malloc://0x10", perm, 0x100).unwrap(),
+            io.open_at("malloc://0x100", perm, 0x200).unwrap(),
+        ];
+        assert_eq!(io.paddr_to_hndl(0x0), Some((hndls[0], 0x0)));
+        assert_eq!(io.paddr_to_hndl(0xff), Some((hndls[0], 0xff)));
+        assert_eq!(io.paddr_to_hndl(0x100), Some((hndls[1], 0x0)));
+        assert_eq!(io.paddr_to_hndl(0x10f), Some((hndls[1], 0xf)));
+        assert_eq!(io.paddr_to_hndl(0x110), None);
+        assert_eq!(io.paddr_to_hndl(0x250), Some((hndls[2], 0x50)));
+        assert_eq!(io.paddr_to_hndl(0x300), None);
+        io.map(0x0, 0x1000, 0x110).unwrap();
+        io.map(0x200, 0x2000, 0x100).unwrap();
+        assert_eq!(io.vaddr_to_paddr(0x1000), Some(0x0));
+        assert_eq!(io.vaddr_to_paddr(0x1105), Some(0x105));
+        assert_eq!(io.vaddr_to_paddr(0x1110), None);
+        assert_eq!(io.vaddr_to_paddr(0x20ff), Some(0x2ff));
+        assert_eq!(io.vaddr_to_paddr(0x0), None);
+        let paddr = io.vaddr_to_paddr(0x2010).unwrap();
+        assert_eq!(io.paddr_to_hndl(paddr), Some((hndls[2], 0x10)));
+    }
+
+    #[test]
+    fn test_map_anywhere() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        io.open("malloc://0x100", perm).unwrap();
+        let first = io.map_anywhere(0x0, 0x40).unwrap();
+        let second = io.map_anywhere(0x40, 0x40).unwrap();
+        assert_eq!((first, second), (0x0, 0x40));
+        // successive maps never overlap.
+        io.vwrite(first, &[0xaa; 0x40]).unwrap();
+        io.vwrite(second, &[0xbb; 0x40]).unwrap();
+        let mut buffer = [0; 0x80];
+        io.pread(0x0, &mut buffer).unwrap();
+        assert_eq!(buffer[..0x40], [0xaa; 0x40]);
+        assert_eq!(buffer[0x40..], [0xbb; 0x40]);
+        // the lowest hole that fits is picked, 0x41 bytes do not fit in [0x0, 0x40).
+        io.unmap(first, 0x40).unwrap();
+        io.map(0x80, 0x100, 0x10).unwrap();
+        assert_eq!(io.map_anywhere(0x80, 0x41).unwrap(), 0x80);
+        assert_eq!(io.map_anywhere(0x80, 0x40).unwrap(), 0x0);
+        assert_eq!(
+            io.map_anywhere(0x100, 0x10).unwrap_err(),
+            IoError::AddressNotFound { at: 0x100 }
+        );
+    }
+
     fn pread_sparce_cb(paths: &[&Path]) {
         let mut io = RIO::new();
         let mut start = 0;
@@ -877,4 +2379,514 @@ mod rio_tests {
     fn test_serde() {
         operate_on_files(&serde_cb, &[DATA, DATA, DATA]);
     }
+
+    fn serde_plugin_name_cb(path: &Path) {
+        let mut io = RIO::new();
+        let hndl = io
+            .open_with("base64", &path.to_string_lossy(), IoMode::READ)
+            .unwrap();
+        let serialized = serde_json::to_string(&io).unwrap();
+        drop(io);
+        // files are reopened with the plugin that opened them even if it rejects their URI.
+        let io: RIO = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(io.hndl_to_desc(hndl).unwrap().plugin_name(), "Base64");
+        let mut fillme = [0; 3];
+        io.pread(0, &mut fillme).unwrap();
+        assert_eq!(&fillme, b"The");
+        // projects saved without plugin names fall back to the plugin accepting the URI.
+        let legacy = serialized.replace(",\"plugin_name\":\"Base64\"", "");
+        assert_ne!(legacy, serialized);
+        let io: RIO = serde_json::from_str(&legacy).unwrap();
+        assert_eq!(io.hndl_to_desc(hndl).unwrap().plugin_name(), "FilePlugin");
+    }
+    #[test]
+    fn test_serde_plugin_name() {
+        operate_on_file(&serde_plugin_name_cb, b"VGhl");
+    }
+
+    fn serde_bincode_cb(paths: &[&Path]) {
+        let mut io = RIO::new();
+        io.open_at(&paths[0].to_string_lossy(), IoMode::READ, 0x1000)
+            .unwrap();
+        io.open_at(&paths[1].to_string_lossy(), IoMode::READ, 0x2000)
+            .unwrap();
+        io.open_at(&paths[2].to_string_lossy(), IoMode::READ, 0x3000)
+            .unwrap();
+        io.map(0x1000, 0x400, DATA.len() as u64).unwrap();
+        io.map(0x2000, 0x400 + DATA.len() as u64, DATA.len() as u64)
+            .unwrap();
+        io.map(0x3000, 0x400 + DATA.len() as u64 * 2, DATA.len() as u64)
+            .unwrap();
+        let serialized = bincode::serialize(&io).unwrap();
+        drop(io);
+        io = bincode::deserialize(&serialized).unwrap();
+        let mut fillme: Vec<u8> = vec![0; 8];
+        io.vread(0x400, &mut fillme).unwrap();
+        assert_eq!(fillme, &DATA[0..8]);
+        io.vread(0x400 + DATA.len() as u64 * 2, &mut fillme)
+            .unwrap();
+        assert_eq!(fillme, &DATA[0..8]);
+        assert_eq!(io.hndl_to_desc(2).unwrap().paddr_base(), 0x3000);
+    }
+    #[test]
+    fn test_serde_bincode() {
+        operate_on_files(&serde_bincode_cb, &[DATA, DATA, DATA]);
+    }
+    fn io_log_cb(path: &Path) {
+        let mut io = RIO::new();
+        let mut fillme: Vec<u8> = vec![0; 8];
+        io.open_at(&path.to_string_lossy(), IoMode::READ, 0x1000)
+            .unwrap();
+        io.pread(0x1000, &mut fillme).unwrap();
+        assert!(io.io_log().is_empty());
+        assert!(!io.is_io_log_enabled());
+        io.enable_io_log(10);
+        io.open_at(&path.to_string_lossy(), IoMode::COW, 0x2000)
+            .unwrap();
+        io.pread(0x1004, &mut fillme).unwrap();
+        io.pwrite(0x2000, &[0xff; 4]).unwrap();
+        io.pwrite(0x5000, &[0xff; 4]).unwrap_err();
+        let log = io.io_log();
+        assert_eq!(
+            log,
+            vec![
+                IoLogRecord {
+                    op: IoOp::Open,
+                    addr: 0x2000,
+                    size: DATA.len() as u64,
+                    hndl: Some(1),
+                    ok: true
+                },
+                IoLogRecord {
+                    op: IoOp::Read,
+                    addr: 0x1004,
+                    size: 8,
+                    hndl: Some(0),
+                    ok: true
+                },
+                IoLogRecord {
+                    op: IoOp::Write,
+                    addr: 0x2000,
+                    size: 4,
+                    hndl: Some(1),
+                    ok: true
+                },
+                IoLogRecord {
+                    op: IoOp::Write,
+                    addr: 0x5000,
+                    size: 4,
+                    hndl: None,
+                    ok: false
+                },
+            ]
+        );
+        io.disable_io_log();
+        assert!(io.io_log().is_empty());
+    }
+    #[test]
+    fn test_io_log() {
+        operate_on_file(&io_log_cb, DATA);
+    }
+    #[test]
+    fn test_hooks() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        io.open_at("malloc://0x10", perm, 0x0).unwrap();
+        io.open_at("malloc://0x10", perm, 0x10).unwrap();
+        io.map(0x8, 0x1000, 0x18).unwrap();
+        let reads = Arc::new(Mutex::new(Vec::new()));
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let r = reads.clone();
+        io.set_read_hook(Box::new(move |op, addr, size| {
+            r.lock().push((op, addr, size));
+        }));
+        let w = writes.clone();
+        io.set_write_hook(Box::new(move |op, addr, size| {
+            w.lock().push((op, addr, size));
+        }));
+        let mut fillme = [0; 8];
+        io.pread(0xc, &mut fillme).unwrap();
+        io.vread(0x1004, &mut fillme).unwrap();
+        io.vread_sparce(0x1014, 8).unwrap();
+        io.pread(0x1c, &mut fillme).unwrap_err();
+        io.pwrite(0xc, &[0xff; 8]).unwrap();
+        io.vfill(0x1000, 4, 0xaa).unwrap();
+        io.vwrite(0x1010, &[0xbb; 4]).unwrap();
+        io.pwrite(0x1c, &[0xff; 8]).unwrap_err();
+        assert_eq!(
+            *reads.lock(),
+            [
+                (HookOp::PhyRead, 0xc, 8),
+                (HookOp::VirRead, 0x1004, 8),
+                (HookOp::VirRead, 0x1014, 4)
+            ]
+        );
+        assert_eq!(
+            *writes.lock(),
+            [
+                (HookOp::PhyWrite, 0xc, 8),
+                (HookOp::VirWrite, 0x1000, 4),
+                (HookOp::VirWrite, 0x1010, 4)
+            ]
+        );
+        assert!(io.has_write_hook());
+        io.clear_write_hook();
+        assert!(!io.has_write_hook());
+        io.pwrite(0, &fillme).unwrap();
+        io.pread(0, &mut fillme).unwrap();
+        assert_eq!(reads.lock().len(), 4);
+        assert_eq!(writes.lock().len(), 3);
+        io.clear_read_hook();
+        io.pread(0, &mut fillme).unwrap();
+        assert_eq!(reads.lock().len(), 4);
+        io.set_write_hook(Box::new(|_, _, _| {}));
+        io.clear_hooks();
+        assert!(!io.has_write_hook());
+        io.pread(0, &mut fillme).unwrap();
+        io.pwrite(0, &fillme).unwrap();
+        assert_eq!(reads.lock().len(), 4);
+        assert_eq!(writes.lock().len(), 3);
+    }
+    fn snapshot_cb(paths: &[&Path]) {
+        let mut io = RIO::new();
+        io.open(&paths[0].to_string_lossy(), IoMode::READ).unwrap();
+        let cow = io.open(&paths[1].to_string_lossy(), IoMode::COW).unwrap();
+        let hndl = io
+            .open("malloc://0x20", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        let base = io.hndl_to_desc(cow).unwrap().paddr_base();
+        let malloc = io.hndl_to_desc(hndl).unwrap().paddr_base();
+        io.pwrite(base, &[0xff; 4]).unwrap();
+        io.pwrite(malloc, &[1, 2]).unwrap();
+        let snapshot = io.snapshot().unwrap();
+        assert_eq!(snapshot.files.len(), 2);
+        io.pwrite(base + 2, &[0xaa; 4]).unwrap();
+        io.pwrite(malloc + 1, &[3, 4]).unwrap();
+        // snapshots survive a round trip through serde like the rest of RIO.
+        let serialized = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: Snapshot = serde_json::from_str(&serialized).unwrap();
+        io.restore(&snapshot).unwrap();
+        assert_bytes(&mut io, base, &[0xff, 0xff, 0xff, 0xff, DATA[4], DATA[5]]);
+        assert_bytes(&mut io, malloc, &[1, 2, 0]);
+        assert_eq!(fs::read(paths[1]).unwrap(), DATA);
+        io.close(hndl).unwrap();
+        assert_eq!(
+            io.restore(&snapshot).unwrap_err(),
+            IoError::HndlNotFoundError
+        );
+        io.open("malloc://0x10", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        io.pwrite(base, &[0; 2]).unwrap();
+        assert_eq!(
+            io.restore(&snapshot).unwrap_err(),
+            IoError::Custom("Snapshot of malloc://0x20 does not match handle 2".to_owned())
+        );
+        // nothing is written when the snapshot doesn't match.
+        assert_bytes(&mut io, base, &[0, 0]);
+    }
+    fn resize_cb(path: &Path) {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        let hndl = io.open_at("malloc://0x10", perm, 0x0).unwrap();
+        io.open_at("malloc://0x10", perm, 0x40).unwrap();
+        let file = io
+            .open_at(&path.to_string_lossy(), IoMode::READ, 0x100)
+            .unwrap();
+        io.pwrite(0x8, &[0xff; 8]).unwrap();
+        io.resize(hndl, 0x40).unwrap();
+        let desc = io.hndl_to_desc(hndl).unwrap();
+        assert_eq!(desc.size(), 0x40);
+        assert_eq!(desc.name(), "malloc://0x40");
+        assert_bytes(
+            &mut io,
+            0x8,
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0],
+        );
+        io.pwrite(0x3f, &[1]).unwrap();
+        assert_eq!(
+            io.resize(hndl, 0x41).unwrap_err(),
+            IoError::AddressesOverlapError
+        );
+        assert_eq!(io.hndl_to_desc(hndl).unwrap().size(), 0x40);
+        io.resize(hndl, 0xc).unwrap();
+        io.pwrite(0xc, &[1]).unwrap_err();
+        assert_bytes(&mut io, 0x8, &[0xff; 4]);
+        // the new uri opens a file of the same size when forking.
+        let mut fork = io.fork().unwrap();
+        assert_eq!(fork.hndl_to_desc(hndl).unwrap().size(), 0xc);
+        assert_bytes(&mut fork, 0x8, &[0xff; 4]);
+        assert_eq!(
+            io.resize(file, 0x10).unwrap_err(),
+            IoError::Custom(format!("{} can not be resized", path.to_string_lossy()))
+        );
+        assert_eq!(
+            io.resize(hndl, 0).unwrap_err(),
+            IoError::Custom("Can not resize files to 0 bytes".to_owned())
+        );
+        assert_eq!(io.resize(7, 0x10).unwrap_err(), IoError::HndlNotFoundError);
+    }
+    #[test]
+    fn test_resize() {
+        operate_on_file(&resize_cb, DATA);
+    }
+    fn open_many_cb(dir: &Path) {
+        let mut io = RIO::new();
+        io.open("malloc://0x10", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        let pattern = format!("{}/*.bin", dir.to_string_lossy());
+        let (hndls, errors) = io.open_many(&pattern, IoMode::READ).unwrap();
+        assert_eq!(hndls, vec![1, 2, 3]);
+        assert!(errors.is_empty());
+        let files: Vec<(&str, u64, u64)> = io
+            .uri_iter()
+            .skip(1)
+            .map(|desc| {
+                (
+                    desc.name().rsplit('/').next().unwrap(),
+                    desc.paddr_base(),
+                    desc.size(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            files,
+            vec![("a.bin", 0x10, 2), ("b.bin", 0x12, 4), ("c.bin", 0x16, 1)]
+        );
+        assert_bytes(&mut io, 0x10, &[1, 1, 2, 2, 2, 2, 3]);
+        // files that fail to open don't stop the others.
+        fs::create_dir_all(dir.join("a.b64")).unwrap();
+        let pattern = format!("b64://{}/*.b64", dir.to_string_lossy());
+        let (hndls, errors) = io.open_many(&pattern, IoMode::READ).unwrap();
+        assert_eq!(hndls, vec![4]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].0,
+            format!("b64://{}/a.b64", dir.to_string_lossy())
+        );
+        assert_bytes(&mut io, 0x17, b"ok");
+        let (hndls, errors) = io
+            .open_many(&format!("{}/*.txt", dir.to_string_lossy()), IoMode::READ)
+            .unwrap();
+        assert!(hndls.is_empty() && errors.is_empty());
+        assert_eq!(
+            io.open_many("[", IoMode::READ).unwrap_err(),
+            IoError::Custom(
+                "Invalid pattern [: Pattern syntax error near position 0: invalid range pattern"
+                    .to_owned()
+            )
+        );
+    }
+    #[test]
+    fn test_open_many() {
+        operate_on_dir(
+            &open_many_cb,
+            &[
+                ("c.bin", &[3]),
+                ("a.bin", &[1; 2]),
+                ("b.bin", &[2; 4]),
+                ("good.b64", b"b2s="),
+            ],
+        );
+    }
+    #[test]
+    fn test_snapshot() {
+        operate_on_files(&snapshot_cb, &[DATA, DATA]);
+    }
+    fn assert_bytes(io: &mut RIO, paddr: u64, expected: &[u8]) {
+        let mut fillme = vec![0; expected.len()];
+        io.pread(paddr, &mut fillme).unwrap();
+        assert_eq!(fillme, expected);
+    }
+    #[test]
+    fn test_undo_redo() {
+        let mut io = RIO::new();
+        io.open("malloc://0x20", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        io.enable_write_journal(10);
+        assert!(io.is_write_journal_enabled());
+        io.pwrite(0x4, &[1, 2, 3, 4]).unwrap();
+        io.pwrite(0x6, &[5, 6, 7, 8]).unwrap();
+        assert_bytes(&mut io, 0x4, &[1, 2, 5, 6, 7, 8]);
+        assert!(io.undo().unwrap());
+        assert_bytes(&mut io, 0x4, &[1, 2, 3, 4, 0, 0]);
+        assert!(io.undo().unwrap());
+        assert_bytes(&mut io, 0x4, &[0; 6]);
+        assert!(!io.undo().unwrap());
+        assert!(io.redo().unwrap());
+        assert_bytes(&mut io, 0x4, &[1, 2, 3, 4, 0, 0]);
+        // a new write drops what is left to redo.
+        io.pwrite(0x0, &[9]).unwrap();
+        assert!(!io.redo().unwrap());
+        assert!(io.undo().unwrap());
+        assert_bytes(&mut io, 0x0, &[0, 0, 0, 0, 1, 2, 3, 4]);
+        io.disable_write_journal();
+        assert!(!io.is_write_journal_enabled());
+        assert!(!io.undo().unwrap());
+    }
+    #[test]
+    fn test_undo_depth() {
+        let mut io = RIO::new();
+        io.open("malloc://0x20", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        io.enable_write_journal(2);
+        for i in 1..=3 {
+            io.pwrite(0x0, &[i]).unwrap();
+        }
+        assert!(io.undo().unwrap());
+        assert!(io.undo().unwrap());
+        assert!(!io.undo().unwrap());
+        assert_bytes(&mut io, 0x0, &[1]);
+        // closing files makes the journal meaningless.
+        io.pwrite(0x0, &[4]).unwrap();
+        io.close_all();
+        io.open("malloc://0x20", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        assert!(!io.undo().unwrap());
+    }
+    fn undo_cow_cb(path: &Path) {
+        let mut io = RIO::new();
+        io.open(&path.to_string_lossy(), IoMode::COW).unwrap();
+        io.enable_write_journal(10);
+        io.pwrite(0x10, &[0xff; 4]).unwrap();
+        io.pwrite(0x12, &[0xaa; 4]).unwrap();
+        assert!(io.undo().unwrap());
+        assert_bytes(
+            &mut io,
+            0x10,
+            &[0xff, 0xff, 0xff, 0xff, DATA[0x14], DATA[0x15]],
+        );
+        assert!(io.undo().unwrap());
+        assert_bytes(&mut io, 0x10, &DATA[0x10..0x16]);
+        assert!(io.redo().unwrap());
+        assert_bytes(
+            &mut io,
+            0x10,
+            &[0xff, 0xff, 0xff, 0xff, DATA[0x14], DATA[0x15]],
+        );
+        assert_eq!(fs::read(path).unwrap(), DATA);
+    }
+    #[test]
+    fn test_undo_cow() {
+        operate_on_file(&undo_cow_cb, DATA);
+    }
+    #[test]
+    fn test_undo_vwrite() {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        io.open_at("malloc://0x10", perm, 0x0).unwrap();
+        io.open_at("malloc://0x10", perm, 0x10).unwrap();
+        io.map(0x10, 0x1000, 0x10).unwrap();
+        io.map(0x0, 0x1010, 0x10).unwrap();
+        io.enable_write_journal(10);
+        io.vwrite(0x100e, &[1, 2, 3, 4]).unwrap();
+        assert_bytes(&mut io, 0x0, &[3, 4]);
+        assert_bytes(&mut io, 0x1e, &[1, 2]);
+        assert!(io.undo().unwrap());
+        assert_bytes(&mut io, 0x0, &[0, 0]);
+        assert_bytes(&mut io, 0x1e, &[0, 0]);
+        assert!(!io.undo().unwrap());
+        assert!(io.redo().unwrap());
+        assert_bytes(&mut io, 0x0, &[3, 4]);
+        assert_bytes(&mut io, 0x1e, &[1, 2]);
+    }
+    fn reopen_cb(paths: &[&Path]) {
+        let mut io = RIO::new();
+        let path = paths[0].to_string_lossy();
+        let hndl = io.open_at(&path, IoMode::READ, 0x1000).unwrap();
+        io.open_at(&paths[1].to_string_lossy(), IoMode::READ, 0x2000)
+            .unwrap();
+        io.map(0x1000, 0x5000, 0x10).unwrap();
+        let mut new_data = DATA.to_vec();
+        new_data.reverse();
+        new_data.extend_from_slice(&[0xff; 0x10]);
+        fs::write(&*path, &new_data).unwrap();
+        io.reopen(hndl, IoMode::READ).unwrap();
+        let desc = io.hndl_to_desc(hndl).unwrap();
+        assert_eq!(desc.paddr_base(), 0x1000);
+        assert_eq!(desc.size(), new_data.len() as u64);
+        assert_eq!(desc.perm(), IoMode::READ);
+        let mut fillme = vec![0; new_data.len()];
+        io.pread(0x1000, &mut fillme).unwrap();
+        assert_eq!(fillme, new_data);
+        let mut fillme = vec![0; 0x10];
+        io.vread(0x5000, &mut fillme).unwrap();
+        assert_eq!(fillme, &new_data[..0x10]);
+        // growing into the next file must fail and keep the old descriptor.
+        fs::write(&*path, [0; 0x2000]).unwrap();
+        assert_eq!(
+            io.reopen(hndl, IoMode::READ).err().unwrap(),
+            IoError::AddressesOverlapError
+        );
+        assert_eq!(io.hndl_to_desc(hndl).unwrap().size(), new_data.len() as u64);
+        assert_eq!(
+            io.reopen(hndl + 5, IoMode::READ).err().unwrap(),
+            IoError::HndlNotFoundError
+        );
+    }
+    #[test]
+    fn test_reopen() {
+        operate_on_files(&reopen_cb, &[DATA, DATA]);
+    }
+
+    fn reopen_perm_cb(path: &Path) {
+        let mut io = RIO::new();
+        let hndl = io
+            .open_at(&path.to_string_lossy(), IoMode::READ, 0x1000)
+            .unwrap();
+        io.map(0x1000, 0x5000, 0x10).unwrap();
+        assert!(io.vwrite(0x5000, &[0xff; 2]).is_err());
+        io.reopen(hndl, IoMode::READ | IoMode::WRITE).unwrap();
+        let desc = io.hndl_to_desc(hndl).unwrap();
+        assert_eq!(desc.paddr_base(), 0x1000);
+        assert_eq!(desc.perm(), IoMode::READ | IoMode::WRITE);
+        io.vwrite(0x5000, &[0xff; 2]).unwrap();
+        io.pwrite(0x1002, &[0xee]).unwrap();
+        io.reopen(hndl, IoMode::READ).unwrap();
+        assert!(io.pwrite(0x1000, &[0]).is_err());
+        drop(io);
+        assert_eq!(fs::read(path).unwrap()[..4], [0xff, 0xff, 0xee, DATA[3]]);
+    }
+    fn flush_cb(paths: &[&Path]) {
+        let mut io = RIO::new();
+        let perm = IoMode::READ | IoMode::WRITE;
+        io.open(&paths[0].to_string_lossy(), perm).unwrap();
+        let b64 = io
+            .open(&format!("b64://{}", paths[1].to_string_lossy()), perm)
+            .unwrap();
+        let cow = io.open(&paths[2].to_string_lossy(), IoMode::COW).unwrap();
+        let malloc = io.open("malloc://0x10", perm).unwrap();
+        let paddrs: Vec<u64> = [b64, cow, malloc]
+            .iter()
+            .map(|hndl| io.hndl_to_desc(*hndl).unwrap().paddr_base())
+            .collect();
+        io.pwrite(0x0, &[0xff; 2]).unwrap();
+        io.pwrite(paddrs[0], b"rai").unwrap();
+        io.pwrite(paddrs[1], &[0xff; 2]).unwrap();
+        io.pwrite(paddrs[2], &[0xff; 2]).unwrap();
+        io.flush().unwrap();
+        let mut buffer = [0; 2];
+        io.pread(paddrs[1], &mut buffer).unwrap();
+        assert_eq!(buffer, [0xff; 2]);
+        drop(io);
+        assert_eq!(fs::read(paths[0]).unwrap()[..3], [0xff, 0xff, DATA[2]]);
+        assert_eq!(fs::read(paths[1]).unwrap()[..4], *b"cmFp");
+        // Copy-On-Write changes never reach the file.
+        assert_eq!(fs::read(paths[2]).unwrap(), DATA);
+    }
+    #[test]
+    fn test_flush() {
+        operate_on_files(&flush_cb, &[DATA, b"AAAAAAAA", DATA]);
+    }
+
+    #[test]
+    fn test_reopen_perm() {
+        operate_on_file(&reopen_perm_cb, DATA);
+        let mut io = RIO::new();
+        let hndl = io.open("data://hex,00", IoMode::READ).unwrap();
+        assert_eq!(
+            io.reopen(hndl, IoMode::COW).err().unwrap(),
+            IoError::Custom("Plugin Data does not support COW".to_owned())
+        );
+        assert_eq!(io.hndl_to_desc(hndl).unwrap().perm(), IoMode::READ);
+    }
 }
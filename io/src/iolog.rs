@@ -0,0 +1,104 @@
+//! Trace log of operations done on [RIO].
+
+use alloc::collections::VecDeque;
+use alloc::fmt;
+
+/// Kind of operation recorded in the IO trace log.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum IoOp {
+    /// Reading from physical address space.
+    Read,
+    /// Writing into physical address space.
+    Write,
+    /// Mapping physical address range into virtual address space.
+    Map,
+    /// Unmapping virtual address range.
+    Unmap,
+    /// Opening a file.
+    Open,
+    /// Closing a file.
+    Close,
+}
+
+impl fmt::Display for IoOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoOp::Read => write!(f, "read"),
+            IoOp::Write => write!(f, "write"),
+            IoOp::Map => write!(f, "map"),
+            IoOp::Unmap => write!(f, "unmap"),
+            IoOp::Open => write!(f, "open"),
+            IoOp::Close => write!(f, "close"),
+        }
+    }
+}
+
+/// Single record in the IO trace log.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IoLogRecord {
+    /// Operation that was done.
+    pub op: IoOp,
+    /// Physical address for read, write, open and close operations, virtual address for
+    /// map and unmap operations.
+    pub addr: u64,
+    /// Size of the accessed range.
+    pub size: u64,
+    /// Handle of the (first) file involved in the operation if any.
+    pub hndl: Option<u64>,
+    /// *true* if the operation succeeded and *false* otherwise.
+    pub ok: bool,
+}
+
+/// Ring buffer of [`IoLogRecord`], once full the oldest record is dropped.
+pub(crate) struct IoLog {
+    capacity: usize,
+    records: VecDeque<IoLogRecord>,
+}
+
+impl IoLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        IoLog {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        }
+    }
+    pub(crate) fn push(&mut self, record: IoLogRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &IoLogRecord> {
+        self.records.iter()
+    }
+}
+
+#[cfg(test)]
+mod test_iolog {
+    use super::*;
+    fn record(addr: u64) -> IoLogRecord {
+        IoLogRecord {
+            op: IoOp::Read,
+            addr,
+            size: 1,
+            hndl: None,
+            ok: true,
+        }
+    }
+    #[test]
+    fn test_ring_buffer() {
+        let mut log = IoLog::new(2);
+        log.push(record(0));
+        log.push(record(1));
+        log.push(record(2));
+        let addrs: Vec<u64> = log.iter().map(|r| r.addr).collect();
+        assert_eq!(addrs, vec![1, 2]);
+        let mut log = IoLog::new(0);
+        log.push(record(0));
+        assert_eq!(log.iter().count(), 0);
+    }
+}
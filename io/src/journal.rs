@@ -0,0 +1,127 @@
+//! Bounded journal of physical writes used to undo and redo them.
+
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Bytes of the physical address space at `paddr` before and after one write.
+pub(crate) struct Change {
+    pub(crate) paddr: u64,
+    pub(crate) before: Vec<u8>,
+    pub(crate) after: Vec<u8>,
+}
+
+/// Changes that are undone or redone together, in the order they were written.
+pub(crate) type Group = Vec<Change>;
+
+pub(crate) struct WriteJournal {
+    depth: usize,
+    undo: VecDeque<Group>,
+    redo: Vec<Group>,
+    // changes recorded while a group is open are committed as one entry by `end_group`.
+    pending: Option<Group>,
+}
+
+impl WriteJournal {
+    pub(crate) fn new(depth: usize) -> Self {
+        WriteJournal {
+            depth,
+            undo: VecDeque::with_capacity(depth),
+            redo: Vec::new(),
+            pending: None,
+        }
+    }
+
+    pub(crate) fn begin_group(&mut self) {
+        self.pending = Some(Vec::new());
+    }
+
+    pub(crate) fn end_group(&mut self) {
+        if let Some(group) = self.pending.take() {
+            if !group.is_empty() {
+                self.commit(group);
+            }
+        }
+    }
+
+    pub(crate) fn record(&mut self, change: Change) {
+        match &mut self.pending {
+            Some(group) => group.push(change),
+            None => self.commit(vec![change]),
+        }
+    }
+
+    // Any new write makes the undone changes unreachable.
+    fn commit(&mut self, group: Group) {
+        self.redo.clear();
+        if self.depth == 0 {
+            return;
+        }
+        if self.undo.len() == self.depth {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(group);
+    }
+
+    pub(crate) fn pop_undo(&mut self) -> Option<Group> {
+        self.undo.pop_back()
+    }
+
+    pub(crate) fn pop_redo(&mut self) -> Option<Group> {
+        self.redo.pop()
+    }
+
+    pub(crate) fn push_undo(&mut self, group: Group) {
+        self.undo.push_back(group);
+    }
+
+    pub(crate) fn push_redo(&mut self, group: Group) {
+        self.redo.push(group);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod test_journal {
+    use super::*;
+    fn change(paddr: u64) -> Change {
+        Change {
+            paddr,
+            before: vec![0],
+            after: vec![1],
+        }
+    }
+    #[test]
+    fn test_depth() {
+        let mut journal = WriteJournal::new(2);
+        journal.record(change(0));
+        journal.record(change(1));
+        journal.record(change(2));
+        assert_eq!(journal.pop_undo().unwrap()[0].paddr, 2);
+        assert_eq!(journal.pop_undo().unwrap()[0].paddr, 1);
+        assert!(journal.pop_undo().is_none());
+        let mut journal = WriteJournal::new(0);
+        journal.record(change(0));
+        assert!(journal.pop_undo().is_none());
+    }
+    #[test]
+    fn test_group() {
+        let mut journal = WriteJournal::new(2);
+        journal.begin_group();
+        journal.record(change(0));
+        journal.record(change(1));
+        journal.end_group();
+        journal.begin_group();
+        journal.end_group();
+        let group = journal.pop_undo().unwrap();
+        assert_eq!(group.len(), 2);
+        journal.push_redo(group);
+        journal.record(change(2));
+        assert!(journal.pop_redo().is_none());
+    }
+}
@@ -1,14 +1,25 @@
 //! Rair IO abstraction layer
+//!
+//! With the default `std` feature turned off the crate is `no_std` and only needs `alloc`,
+//! in that case the only built-in plugin is the memory based `malloc://` plugin.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
 extern crate alloc;
 mod desc;
 mod descquery;
 mod io;
+mod iolog;
+mod journal;
 mod mapsquery;
 mod plugin;
 mod plugins;
+mod snapshot;
 mod utils;
 pub use crate::desc::*;
 pub use crate::io::*;
+pub use crate::iolog::*;
 pub use crate::mapsquery::*;
 pub use crate::plugin::*;
+pub use crate::snapshot::Snapshot;
 pub use crate::utils::*;
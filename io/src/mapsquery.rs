@@ -1,7 +1,9 @@
 //! Data structure that enables queries and reverse queries on vaddr <--> paddr.
 
 use crate::utils::IoError;
+use alloc::boxed::Box;
 use alloc::sync::Arc;
+use alloc::vec::Vec;
 use core::cmp::min;
 use rair_trees::ist::IST;
 use serde::{Deserialize, Serialize};
@@ -94,7 +96,8 @@ impl RIOMapQuery {
         self.rev_maps.insert(paddr, paddr + size - 1, mapping);
         Ok(())
     }
-    pub fn split_vaddr_range(&self, vaddr: u64, size: u64) -> Option<Vec<RIOMap>> {
+    // Returns fragments of the given range or the first address that is not mapped
+    pub fn split_vaddr_range(&self, vaddr: u64, size: u64) -> Result<Vec<RIOMap>, u64> {
         let maps: Vec<Arc<RIOMap>> = self
             .maps
             .overlap(vaddr, vaddr + size - 1)
@@ -102,14 +105,14 @@ impl RIOMapQuery {
             .map(|&x| x.clone())
             .collect();
         if maps.is_empty() {
-            return None;
+            return Err(vaddr);
         }
         let mut ranges = Vec::with_capacity(maps.len());
         let mut start = vaddr;
         let mut remaining = size;
         for map in maps {
             if start < map.vaddr {
-                return None;
+                return Err(start);
             }
             let delta = min(remaining, map.size - (start - map.vaddr));
             let frag = RIOMap {
@@ -122,9 +125,9 @@ impl RIOMapQuery {
             remaining -= delta;
         }
         if remaining != 0 {
-            return None;
+            return Err(start);
         }
-        Some(ranges)
+        Ok(ranges)
     }
     pub fn rev_query(&self, paddr: u64) -> Vec<u64> {
         let maps: Vec<Arc<RIOMap>> = self.rev_maps.at(paddr).iter().map(|&x| x.clone()).collect();
@@ -166,11 +169,10 @@ impl RIOMapQuery {
         ranged_hndl
     }
     pub fn unmap(&mut self, vaddr: u64, size: u64) -> Result<(), IoError> {
-        let fragments = self.split_vaddr_range(vaddr, size);
-        if fragments.is_none() {
-            return Err(IoError::AddressNotFound);
-        }
-        for frag in fragments.unwrap() {
+        let fragments = self
+            .split_vaddr_range(vaddr, size)
+            .map_err(|at| IoError::AddressNotFound { at })?;
+        for frag in fragments {
             let old_map = self
                 .maps
                 .delete_envelop(frag.vaddr, frag.vaddr + frag.size - 1)[0]
@@ -261,7 +263,7 @@ mod maps_query_test {
                 size: 0x100
             }]
         );
-        assert_eq!(map_query.split_vaddr_range(0x1100, 0x100), None);
+        assert_eq!(map_query.split_vaddr_range(0x1100, 0x100), Err(0x1100));
     }
 
     #[test]
@@ -270,10 +272,10 @@ mod maps_query_test {
         map_query.map(0x1000, 0x4000, 0x1000).unwrap();
         let mut e = map_query.map(0x3000, 0x4100, 0x1000).err();
         assert_eq!(e.unwrap(), IoError::AddressesOverlapError);
-        assert_eq!(map_query.split_vaddr_range(0x3000, 0x2000), None);
-        assert_eq!(map_query.split_vaddr_range(0x3000, 0x3000), None);
+        assert_eq!(map_query.split_vaddr_range(0x3000, 0x2000), Err(0x3000));
+        assert_eq!(map_query.split_vaddr_range(0x3000, 0x3000), Err(0x3000));
         e = map_query.unmap(0x3500, 0x500).err();
-        assert_eq!(e.unwrap(), IoError::AddressNotFound);
+        assert_eq!(e.unwrap(), IoError::AddressNotFound { at: 0x3500 });
     }
 
     #[test]
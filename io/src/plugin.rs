@@ -1,6 +1,11 @@
 //! RIO interface for implementing new plugin.
 
+use crate::mapsquery::RIOMap;
 use crate::utils::{IoError, IoMode};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 /// Metadata that describes the plugin
 #[derive(PartialEq)]
@@ -41,6 +46,14 @@ pub trait RIOPlugin {
     /// Check if the given file can be opened wit the current plugin (only by checking the uri
     /// without opening the file)
     fn accept_uri(&self, uri: &str) -> bool;
+    /// Permissions that files opened by this plugin can use, [`RIO::open`] and [`RIO::open_at`]
+    /// fail early when asked for any other permission. By default all permissions are supported.
+    ///
+    /// [`RIO::open`]: crate::RIO::open
+    /// [`RIO::open_at`]: crate::RIO::open_at
+    fn supported_modes(&self) -> IoMode {
+        IoMode::all()
+    }
 }
 /// A call to [`RIOPlugin::open`] would normally return [`RioPluginDesc`] that contains member that
 /// implements [`RIOPluginOperations`]. This way we always have way of reading and writing from file
@@ -52,6 +65,29 @@ pub trait RIOPluginOperations {
     /// Function that writes to a file represented by an object opened
     /// by [`RIOPlugin::open`] raddr is the real address of the in the file.
     fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError>;
+    /// Mappings that the file format asks for (for example loadable segments of executables),
+    /// `paddr` of each map is the real address in the file. By default nothing is suggested.
+    fn suggested_maps(&self) -> Vec<RIOMap> {
+        Vec::new()
+    }
+    /// Change the size of the file opened as `uri` to `size` bytes, new bytes are zero
+    /// filled. On success the URI that opens a file of the new size is returned. By default
+    /// files can not be resized.
+    fn resize(&mut self, uri: &str, _size: u64) -> Result<String, IoError> {
+        Err(IoError::Custom(format!("{uri} can not be resized")))
+    }
+    /// Persist changes that are only kept in memory to the backing storage of the file. Changes
+    /// done to files opened as Copy-On-Write must never reach the backing storage. By default
+    /// there is nothing to persist.
+    fn flush(&mut self) -> Result<(), IoError> {
+        Ok(())
+    }
+    /// Same as [`RIOPluginOperations::read`] but reads the bytes as they were before any change
+    /// that is only kept in memory, such as writes to files opened as Copy-On-Write. By default
+    /// there are no such changes.
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.read(raddr, buffer)
+    }
 }
 
 struct DefPluginOperations;
@@ -118,8 +118,10 @@ impl Base64Internal {
             return Err(IoError::Custom("Corrupted base64 data".to_owned()));
         }
         decoded_data[offset..offset + size].copy_from_slice(&buffer[0..size]);
+        // the last block may hold less than 3 bytes, encode only those to keep its padding.
+        let valid = cmp::min(3, self.len() as usize - base);
         BASE64_STANDARD
-            .encode_slice(decoded_data, &mut b64data)
+            .encode_slice(&decoded_data[..valid], &mut b64data)
             .unwrap();
         self.file.write(b64base, &b64data)?;
         Ok((raddr + size, &buffer[size..]))
@@ -148,8 +150,10 @@ impl Base64Internal {
             return Err(IoError::Custom("Corrupted base64 data".to_owned()));
         }
         decoded_data[0..size].copy_from_slice(&buffer[offset..]);
+        // the last block may hold less than 3 bytes, encode only those to keep its padding.
+        let valid = cmp::min(3, self.len() as usize - base);
         BASE64_STANDARD
-            .encode_slice(decoded_data, &mut b64data)
+            .encode_slice(&decoded_data[..valid], &mut b64data)
             .unwrap();
         self.file.write(b64base, &b64data)?;
         Ok((raddr, &buffer[..offset]))
@@ -201,6 +205,33 @@ impl RIOPluginOperations for Base64Internal {
         self.write_aligned_blocks(raddr, buffer)?;
         Ok(())
     }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        self.file.flush()
+    }
+
+    // the encoded blocks covering the buffer are read as they were and decoded at once.
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        if (self.len() as usize) < raddr + buffer.len() {
+            return Err(IoError::Parse(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "BufferOverflow",
+            )));
+        }
+        if buffer.is_empty() {
+            return Ok(());
+        }
+        let first = raddr / 3;
+        let last = (raddr + buffer.len()).div_ceil(3);
+        let mut b64data = vec![0; (last - first) * 4];
+        self.file.read_original(first * 4, &mut b64data)?;
+        let Ok(decoded) = BASE64_STANDARD.decode(b64data) else {
+            return Err(IoError::Custom("Corrupted base64 data".to_owned()));
+        };
+        let offset = raddr - first * 3;
+        buffer.copy_from_slice(&decoded[offset..offset + buffer.len()]);
+        Ok(())
+    }
 }
 
 struct Base64Plugin {
@@ -265,6 +296,7 @@ pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
 #[cfg(test)]
 mod test_base64 {
     use super::*;
+    use std::fs;
     use test_file::*;
 
     #[test]
@@ -429,4 +461,67 @@ mod test_base64 {
             "../testing_binaries/rio/base64/two_pad.b64",
         );
     }
+
+    fn cow_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("b64://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::COW).unwrap();
+        file.plugin_operations.write(4, b"QUICK").unwrap();
+        file.plugin_operations.write(18, b"X").unwrap();
+        let mut buffer = [0; 10];
+        file.plugin_operations.read(2, &mut buffer).unwrap();
+        assert_eq!(&buffer, b"e QUICK br");
+        file.plugin_operations
+            .read_original(2, &mut buffer)
+            .unwrap();
+        assert_eq!(&buffer, b"e quick br");
+        file.plugin_operations
+            .read_original(17, &mut buffer[..2])
+            .unwrap();
+        assert_eq!(&buffer[..2], b"ox");
+        assert_eq!(
+            file.plugin_operations
+                .read_original(18, &mut buffer[..2])
+                .unwrap_err(),
+            IoError::Parse(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "BufferOverflow"
+            ))
+        );
+        drop(file);
+        assert_eq!(fs::read(path).unwrap(), b"VGhlIHF1aWNrIGJyb3duIGZveA==");
+    }
+    #[test]
+    fn test_cow() {
+        operate_on_file(&cow_cb, b"VGhlIHF1aWNrIGJyb3duIGZveA==");
+    }
+
+    fn padding_cb(path: &Path) {
+        // (content, written offset, written bytes, content after writing)
+        let cases: [(&[u8], usize, &[u8], &[u8]); 4] = [
+            (b"VGg=", 1, b"H", b"VEg="),
+            (b"VEg=", 0, b"Th", b"VGg="),
+            (b"VA==", 0, b"t", b"dA=="),
+            (b"VGhlVA==", 2, b"EX", b"VGhFWA=="),
+        ];
+        let uri = format!("b64://{}", path.to_string_lossy());
+        let mut p = plugin();
+        for (before, raddr, data, after) in cases {
+            fs::write(path, before).unwrap();
+            let mut file = p.open(&uri, IoMode::READ | IoMode::WRITE).unwrap();
+            let size = file.size;
+            file.plugin_operations.write(raddr, data).unwrap();
+            drop(file);
+            assert_eq!(fs::read(path).unwrap(), after);
+            let mut file = p.open(&uri, IoMode::READ).unwrap();
+            assert_eq!(file.size, size);
+            let mut buffer = vec![0; data.len()];
+            file.plugin_operations.read(raddr, &mut buffer).unwrap();
+            assert_eq!(buffer, data);
+        }
+    }
+    #[test]
+    fn test_padding() {
+        operate_on_file(&padding_cb, b"");
+    }
 }
@@ -0,0 +1,330 @@
+//! RIO plugin that keeps recently read blocks of another file in memory.
+//!
+//! URIs look like `cache://<block>@<uri>` where `block` is the size of each cached block and
+//! `uri` is opened by whatever built-in plugin accepts it. Reads are served from the cache and
+//! only blocks that are missing are read from `uri`, once [`CACHE_BLOCKS`] blocks are cached
+//! the least recently used one is dropped. Writes go straight to `uri` and drop the blocks
+//! they touch.
+
+use crate::mapsquery::RIOMap;
+use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
+use crate::plugins::open_nested;
+use crate::utils::{str_to_size, IoError, IoMode};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Maximum number of blocks kept in memory for each file.
+pub const CACHE_BLOCKS: usize = 0x100;
+
+const METADATA: RIOPluginMetadata = RIOPluginMetadata {
+    name: "Cache",
+    desc: "This plugin is used to keep recently read blocks of files that can be \
+           opened by other plugins in memory, it helps with files that are slow to read.",
+    author: "Oddcoder",
+    license: "LGPL",
+    version: "0.0.1",
+};
+
+struct CacheInternal {
+    file: Box<dyn RIOPluginOperations + Sync + Send>,
+    block: usize,
+    base: usize, // raddr of the first byte of the inner file
+    size: usize,
+    blocks: BTreeMap<usize, (Vec<u8>, u64)>, // block index -> (data, last use)
+    lru: BTreeMap<u64, usize>,               // last use -> block index
+    clock: u64,
+}
+
+impl CacheInternal {
+    fn new(
+        file: Box<dyn RIOPluginOperations + Sync + Send>,
+        block: usize,
+        base: usize,
+        size: usize,
+    ) -> Self {
+        CacheInternal {
+            file,
+            block,
+            base,
+            size,
+            blocks: BTreeMap::new(),
+            lru: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    // Returns the cached block number `index`, reading it from the inner file on a miss.
+    fn get_block(&mut self, index: usize) -> Result<&[u8], IoError> {
+        self.clock += 1;
+        if let Some((_, used)) = self.blocks.get_mut(&index) {
+            self.lru.remove(used);
+            *used = self.clock;
+        } else {
+            let start = index * self.block;
+            let mut data = vec![0; self.block.min(self.size - start)];
+            self.file.read(self.base + start, &mut data)?;
+            if self.blocks.len() == CACHE_BLOCKS {
+                let (_, oldest) = self.lru.pop_first().unwrap();
+                self.blocks.remove(&oldest);
+            }
+            self.blocks.insert(index, (data, self.clock));
+        }
+        self.lru.insert(self.clock, index);
+        Ok(&self.blocks[&index].0)
+    }
+
+    // Drop cached blocks that overlap `[raddr, raddr + len)`.
+    fn invalidate(&mut self, raddr: usize, len: usize) {
+        if len == 0 || raddr < self.base {
+            return;
+        }
+        let first = (raddr - self.base) / self.block;
+        let last = (raddr - self.base + len - 1) / self.block;
+        let dropped: Vec<usize> = self.blocks.range(first..=last).map(|(i, _)| *i).collect();
+        for index in dropped {
+            let (_, used) = self.blocks.remove(&index).unwrap();
+            self.lru.remove(&used);
+        }
+    }
+}
+
+impl RIOPluginOperations for CacheInternal {
+    fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        // let the inner file report reads that are out of bounds.
+        if raddr < self.base || raddr - self.base + buffer.len() > self.size {
+            return self.file.read(raddr, buffer);
+        }
+        let mut offset = raddr - self.base;
+        let mut done = 0;
+        while done < buffer.len() {
+            let block = self.block;
+            let data = self.get_block(offset / block)?;
+            let start = offset % block;
+            let len = (data.len() - start).min(buffer.len() - done);
+            buffer[done..done + len].copy_from_slice(&data[start..start + len]);
+            done += len;
+            offset += len;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
+        // the inner file might be partially written even if writing fails.
+        self.invalidate(raddr, buffer.len());
+        self.file.write(raddr, buffer)
+    }
+
+    fn suggested_maps(&self) -> Vec<RIOMap> {
+        self.file.suggested_maps()
+    }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        self.file.flush()
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.file.read_original(raddr, buffer)
+    }
+}
+
+struct CachePlugin;
+
+impl CachePlugin {
+    // Split uri into the block size and the uri of the inner file.
+    fn parse_uri(uri: &str) -> Result<(u64, &str), IoError> {
+        let uri = uri.trim_start_matches("cache://");
+        let Some((block, inner)) = uri.split_once('@') else {
+            return Err(IoError::Custom(format!(
+                "Missing cache block size in {uri}"
+            )));
+        };
+        match str_to_size(block) {
+            Some(size) if size > 0 => Ok((size, inner)),
+            _ => Err(IoError::Custom(format!("Invalid cache block size {block}"))),
+        }
+    }
+}
+
+impl RIOPlugin for CachePlugin {
+    fn get_metadata(&self) -> &'static RIOPluginMetadata {
+        &METADATA
+    }
+
+    fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+        let (block, inner) = CachePlugin::parse_uri(uri)?;
+        let file = open_nested(inner, flags)?;
+        let desc = RIOPluginDesc {
+            name: uri.to_owned(),
+            perm: file.perm,
+            raddr: file.raddr,
+            size: file.size,
+            plugin_operations: Box::new(CacheInternal::new(
+                file.plugin_operations,
+                block as usize,
+                file.raddr as usize,
+                file.size as usize,
+            )),
+        };
+        Ok(desc)
+    }
+
+    fn accept_uri(&self, uri: &str) -> bool {
+        uri.starts_with("cache://")
+    }
+}
+
+pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
+    Box::new(CachePlugin)
+}
+
+#[cfg(test)]
+mod test_cache {
+    use super::*;
+    use crate::io::RIO;
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::fs;
+    use std::path::Path;
+    use test_file::*;
+
+    // memory based file that counts how many times it is read.
+    struct Counting {
+        data: Vec<u8>,
+        reads: Arc<AtomicUsize>,
+    }
+
+    impl RIOPluginOperations for Counting {
+        fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            let data = self
+                .data
+                .get(raddr..raddr + buffer.len())
+                .ok_or(IoError::AddressNotFound { at: raddr as u64 })?;
+            buffer.copy_from_slice(data);
+            Ok(())
+        }
+        fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
+            self.data[raddr..raddr + buffer.len()].copy_from_slice(buffer);
+            Ok(())
+        }
+    }
+
+    fn counting(data: &[u8], block: usize) -> (CacheInternal, Arc<AtomicUsize>) {
+        let reads = Arc::new(AtomicUsize::new(0));
+        let file = Counting {
+            data: data.to_vec(),
+            reads: reads.clone(),
+        };
+        (
+            CacheInternal::new(Box::new(file), block, 0, data.len()),
+            reads,
+        )
+    }
+
+    #[test]
+    fn test_parse_uri() {
+        assert_eq!(
+            CachePlugin::parse_uri("cache://0x1000@/tmp/dump").unwrap(),
+            (0x1000, "/tmp/dump")
+        );
+        assert_eq!(
+            CachePlugin::parse_uri("cache://16@xor://ff@/tmp/dump").unwrap(),
+            (16, "xor://ff@/tmp/dump")
+        );
+        assert_eq!(
+            CachePlugin::parse_uri("cache:///tmp/dump").unwrap_err(),
+            IoError::Custom("Missing cache block size in /tmp/dump".to_owned())
+        );
+        for block in ["", "0", "0x", "big"] {
+            assert_eq!(
+                CachePlugin::parse_uri(&format!("cache://{block}@/tmp/dump")).unwrap_err(),
+                IoError::Custom(format!("Invalid cache block size {block}"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_hits() {
+        let (mut file, reads) = counting(DATA, 0x10);
+        let mut buffer = [0; 8];
+        file.read(0x4, &mut buffer).unwrap();
+        assert_eq!(buffer, DATA[0x4..0xc]);
+        assert_eq!(reads.load(Ordering::Relaxed), 1);
+        // same block again.
+        file.read(0x8, &mut buffer).unwrap();
+        assert_eq!(buffer, DATA[0x8..0x10]);
+        assert_eq!(reads.load(Ordering::Relaxed), 1);
+        // crossing into the next block only reads the missing one.
+        file.read(0xc, &mut buffer).unwrap();
+        assert_eq!(buffer, DATA[0xc..0x14]);
+        assert_eq!(reads.load(Ordering::Relaxed), 2);
+        // the last block is shorter than the others.
+        let last = DATA.len() - DATA.len() % 0x10;
+        let mut tail = vec![0; DATA.len() - last];
+        file.read(last, &mut tail).unwrap();
+        assert_eq!(tail, DATA[last..]);
+        assert_eq!(reads.load(Ordering::Relaxed), 3);
+        assert!(file.read(DATA.len() - 1, &mut buffer).is_err());
+        assert_eq!(reads.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn test_write_invalidates() {
+        let (mut file, reads) = counting(DATA, 0x10);
+        let mut buffer = [0; 0x20];
+        file.read(0, &mut buffer).unwrap();
+        assert_eq!(reads.load(Ordering::Relaxed), 2);
+        file.write(0x1e, &[0xff; 4]).unwrap();
+        file.read(0, &mut buffer).unwrap();
+        assert_eq!(buffer[..0x1e], DATA[..0x1e]);
+        assert_eq!(buffer[0x1e..], [0xff; 2]);
+        // only the second block was dropped.
+        assert_eq!(reads.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_eviction() {
+        let (mut file, reads) = counting(&[0; CACHE_BLOCKS * 2], 1);
+        let mut buffer = [0];
+        for i in 0..=CACHE_BLOCKS {
+            file.read(i, &mut buffer).unwrap();
+        }
+        assert_eq!(reads.load(Ordering::Relaxed), CACHE_BLOCKS + 1);
+        assert_eq!(file.blocks.len(), CACHE_BLOCKS);
+        // block 0 was dropped to make room for the last block.
+        file.read(1, &mut buffer).unwrap();
+        assert_eq!(reads.load(Ordering::Relaxed), CACHE_BLOCKS + 1);
+        file.read(0, &mut buffer).unwrap();
+        assert_eq!(reads.load(Ordering::Relaxed), CACHE_BLOCKS + 2);
+        // now block 2 is the oldest, block 1 was used recently.
+        file.read(1, &mut buffer).unwrap();
+        assert_eq!(reads.load(Ordering::Relaxed), CACHE_BLOCKS + 2);
+        file.read(2, &mut buffer).unwrap();
+        assert_eq!(reads.load(Ordering::Relaxed), CACHE_BLOCKS + 3);
+    }
+
+    fn cache_cb(path: &Path) {
+        let mut io = RIO::new();
+        let uri = format!("cache://0x10@xor://ff@{}", path.to_string_lossy());
+        let hndl = io
+            .open_at(&uri, IoMode::READ | IoMode::WRITE, 0x1000)
+            .unwrap();
+        assert_eq!(io.hndl_to_desc(hndl).unwrap().size(), DATA.len() as u64);
+        let mut buffer = [0; 0x18];
+        io.pread(0x1004, &mut buffer).unwrap();
+        let expected: Vec<u8> = DATA[4..0x1c].iter().map(|b| !b).collect();
+        assert_eq!(buffer[..], expected);
+        io.pwrite(0x1008, &[0; 2]).unwrap();
+        io.pread(0x1004, &mut buffer).unwrap();
+        assert_eq!(buffer[4..6], [0; 2]);
+        assert_eq!(fs::read(path).unwrap()[8..10], [0xff; 2]);
+    }
+    #[test]
+    fn test_cache() {
+        operate_on_file(&cache_cb, DATA);
+    }
+}
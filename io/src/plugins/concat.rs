@@ -0,0 +1,238 @@
+//! RIO plugin that concatenates other files into one continuous file.
+//!
+//! URIs look like `concat://<uri1>|<uri2>|...` where every `uri` is opened by whatever
+//! built-in plugin accepts it, and files are laid out one right after the other.
+
+use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
+use crate::plugins::open_nested;
+use crate::utils::{IoError, IoMode};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+
+const METADATA: RIOPluginMetadata = RIOPluginMetadata {
+    name: "Concat",
+    desc: "This plugin is used to join files that can be opened by other \
+           plugins one after the other into one file.",
+    author: "Oddcoder",
+    license: "LGPL",
+    version: "0.0.1",
+};
+
+struct Part {
+    file: Box<dyn RIOPluginOperations + Sync + Send>,
+    start: usize, // offset of the first byte of this part in the concatenated file
+    raddr: usize, // raddr of the first byte of the part in the inner file
+    size: usize,
+}
+
+struct ConcatInternal {
+    parts: Vec<Part>,
+    size: usize,
+}
+
+impl ConcatInternal {
+    // Split `size` bytes starting at `raddr` into (part, raddr in part, size) operations.
+    fn split(&self, raddr: usize, size: usize) -> Result<Vec<(usize, usize, usize)>, IoError> {
+        if raddr + size > self.size {
+            return Err(IoError::AddressNotFound {
+                at: raddr.max(self.size) as u64,
+            });
+        }
+        let mut operations = Vec::new();
+        let mut i = self
+            .parts
+            .partition_point(|part| part.start + part.size <= raddr);
+        let (mut addr, end) = (raddr, raddr + size);
+        while addr < end {
+            let part = &self.parts[i];
+            let len = (part.start + part.size).min(end) - addr;
+            operations.push((i, part.raddr + addr - part.start, len));
+            addr += len;
+            i += 1;
+        }
+        Ok(operations)
+    }
+}
+
+impl RIOPluginOperations for ConcatInternal {
+    fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        let mut start = 0;
+        for (i, raddr, size) in self.split(raddr, buffer.len())? {
+            self.parts[i]
+                .file
+                .read(raddr, &mut buffer[start..start + size])?;
+            start += size;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
+        let mut start = 0;
+        for (i, raddr, size) in self.split(raddr, buffer.len())? {
+            self.parts[i]
+                .file
+                .write(raddr, &buffer[start..start + size])?;
+            start += size;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        for part in &mut self.parts {
+            part.file.flush()?;
+        }
+        Ok(())
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        let mut start = 0;
+        for (i, raddr, size) in self.split(raddr, buffer.len())? {
+            self.parts[i]
+                .file
+                .read_original(raddr, &mut buffer[start..start + size])?;
+            start += size;
+        }
+        Ok(())
+    }
+}
+
+struct ConcatPlugin;
+
+impl ConcatPlugin {
+    // Split uri into the uris of the files being concatenated.
+    fn parse_uri(uri: &str) -> Result<Vec<&str>, IoError> {
+        let uris: Vec<&str> = uri.trim_start_matches("concat://").split('|').collect();
+        if uris.iter().any(|uri| uri.is_empty()) {
+            return Err(IoError::Custom(format!("Empty file name in {uri}")));
+        }
+        Ok(uris)
+    }
+}
+
+impl RIOPlugin for ConcatPlugin {
+    fn get_metadata(&self) -> &'static RIOPluginMetadata {
+        &METADATA
+    }
+
+    fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+        let mut parts = Vec::new();
+        let mut size = 0;
+        for inner in ConcatPlugin::parse_uri(uri)? {
+            let file = open_nested(inner, flags)?;
+            parts.push(Part {
+                file: file.plugin_operations,
+                start: size,
+                raddr: file.raddr as usize,
+                size: file.size as usize,
+            });
+            size += file.size as usize;
+        }
+        let desc = RIOPluginDesc {
+            name: uri.to_owned(),
+            perm: flags,
+            raddr: 0,
+            size: size as u64,
+            plugin_operations: Box::new(ConcatInternal { parts, size }),
+        };
+        Ok(desc)
+    }
+
+    fn accept_uri(&self, uri: &str) -> bool {
+        uri.starts_with("concat://")
+    }
+}
+
+pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
+    Box::new(ConcatPlugin)
+}
+
+#[cfg(test)]
+mod test_concat {
+    use super::*;
+    use crate::io::RIO;
+    use alloc::vec;
+    use std::fs;
+    use std::path::Path;
+    use test_file::*;
+
+    #[test]
+    fn test_parse_uri() {
+        assert_eq!(
+            ConcatPlugin::parse_uri("concat:///tmp/a|b64:///tmp/b|malloc://0x10").unwrap(),
+            vec!["/tmp/a", "b64:///tmp/b", "malloc://0x10"]
+        );
+        assert_eq!(
+            ConcatPlugin::parse_uri("concat:///tmp/a").unwrap(),
+            vec!["/tmp/a"]
+        );
+        for uri in [
+            "concat://",
+            "concat:///tmp/a|",
+            "concat://|/tmp/a",
+            "concat://a||b",
+        ] {
+            assert_eq!(
+                ConcatPlugin::parse_uri(uri).unwrap_err(),
+                IoError::Custom(format!("Empty file name in {uri}"))
+            );
+        }
+    }
+
+    fn concat_cb(paths: &[&Path]) {
+        let mut p = plugin();
+        let uri = format!(
+            "concat://{}|{}|{}",
+            paths[0].to_string_lossy(),
+            paths[1].to_string_lossy(),
+            paths[2].to_string_lossy()
+        );
+        let mut file = p.open(&uri, IoMode::READ | IoMode::WRITE).unwrap();
+        assert_eq!(file.size, 0x20);
+        // spans all three parts.
+        let mut buffer = [0; 0x14];
+        file.plugin_operations.read(0x6, &mut buffer).unwrap();
+        assert_eq!(buffer[..0xa], DATA[0x6..0x10]);
+        assert_eq!(buffer[0xa..0xc], DATA[0..2]);
+        assert_eq!(buffer[0xc..], DATA[0..8]);
+        // ends right at the end of a part.
+        file.plugin_operations.read(0x10, &mut buffer[..2]).unwrap();
+        assert_eq!(buffer[..2], DATA[0..2]);
+        file.plugin_operations.read(0x1f, &mut buffer[..1]).unwrap();
+        assert_eq!(buffer[0], DATA[0xd]);
+        assert_eq!(
+            file.plugin_operations
+                .read(0x1f, &mut buffer[..2])
+                .unwrap_err(),
+            IoError::AddressNotFound { at: 0x20 }
+        );
+        file.plugin_operations.write(0xf, &[0xff; 4]).unwrap();
+        drop(file);
+        assert_eq!(fs::read(paths[0]).unwrap()[0xf], 0xff);
+        assert_eq!(fs::read(paths[1]).unwrap(), [0xff, 0xff]);
+        assert_eq!(fs::read(paths[2]).unwrap()[..2], [0xff, DATA[1]]);
+    }
+    #[test]
+    fn test_concat() {
+        operate_on_files(&concat_cb, &[&DATA[..0x10], &DATA[..2], &DATA[..0xe]]);
+    }
+
+    #[test]
+    fn test_nested() {
+        let mut io = RIO::new();
+        io.open(
+            "concat://pattern://4?incr|xor://ff@pattern://4?incr|slice://8:2@pattern://0x10?incr",
+            IoMode::READ,
+        )
+        .unwrap();
+        let mut buffer = [0; 10];
+        io.pread(0, &mut buffer).unwrap();
+        assert_eq!(buffer, [0, 1, 2, 3, 0xff, 0xfe, 0xfd, 0xfc, 8, 9]);
+        assert_eq!(
+            io.open("concat://pattern://4?incr|nothing://here", IoMode::READ)
+                .unwrap_err(),
+            IoError::IoPluginNotFoundError("nothing://here".to_owned())
+        );
+    }
+}
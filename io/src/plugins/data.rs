@@ -0,0 +1,222 @@
+//! RIO plugin that opens bytes embedded in the URI itself.
+//!
+//! URIs look like `data://base64,<payload>` or `data://hex,<payload>`, the payload is decoded
+//! once when the file is opened and kept in memory, so writes are never saved anywhere.
+
+use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
+use crate::utils::{IoError, IoMode};
+use base64::prelude::*;
+use core::ops::Range;
+use std::io;
+
+const METADATA: RIOPluginMetadata = RIOPluginMetadata {
+    name: "Data",
+    desc: "This plugin is used to open base64 or hex encoded bytes \
+           written in the uri as memory based files.",
+    author: "Oddcoder",
+    license: "LGPL",
+    version: "0.0.1",
+};
+
+struct DataInternal {
+    data: Vec<u8>,
+    original: Vec<u8>, // data as decoded from the uri
+    prot: IoMode,
+}
+
+impl DataInternal {
+    fn range(&self, raddr: usize, size: usize) -> Result<Range<usize>, IoError> {
+        if raddr + size > self.data.len() {
+            return Err(IoError::Parse(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "BufferOverflow",
+            )));
+        }
+        Ok(raddr..raddr + size)
+    }
+}
+
+impl RIOPluginOperations for DataInternal {
+    fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        let range = self.range(raddr, buffer.len())?;
+        buffer.copy_from_slice(&self.data[range]);
+        Ok(())
+    }
+
+    fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
+        if !self.prot.contains(IoMode::WRITE) {
+            return Err(IoError::Parse(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "File Not Writable",
+            )));
+        }
+        let range = self.range(raddr, buffer.len())?;
+        self.data[range].copy_from_slice(buffer);
+        Ok(())
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        let range = self.range(raddr, buffer.len())?;
+        buffer.copy_from_slice(&self.original[range]);
+        Ok(())
+    }
+}
+
+struct DataPlugin;
+
+impl DataPlugin {
+    fn decode_hex(payload: &str) -> Option<Vec<u8>> {
+        if !payload.len().is_multiple_of(2) {
+            return None;
+        }
+        (0..payload.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(payload.get(i..i + 2)?, 16).ok())
+            .collect()
+    }
+
+    fn decode(uri: &str) -> Result<Vec<u8>, IoError> {
+        let uri = uri.trim_start_matches("data://");
+        let Some((encoding, payload)) = uri.split_once(',') else {
+            return Err(IoError::Custom(format!("Missing data encoding in {uri}")));
+        };
+        let data = match encoding {
+            "base64" => BASE64_STANDARD
+                .decode(payload)
+                .map_err(|e| IoError::Custom(format!("Invalid base64 data ({e})")))?,
+            "hex" => Self::decode_hex(payload)
+                .ok_or_else(|| IoError::Custom(format!("Invalid hex data {payload}")))?,
+            _ => return Err(IoError::Custom(format!("Unknown data encoding {encoding}"))),
+        };
+        if data.is_empty() {
+            return Err(IoError::Custom(format!("Empty data in {uri}")));
+        }
+        Ok(data)
+    }
+}
+
+impl RIOPlugin for DataPlugin {
+    fn get_metadata(&self) -> &'static RIOPluginMetadata {
+        &METADATA
+    }
+
+    fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+        let data = DataPlugin::decode(uri)?;
+        let desc = RIOPluginDesc {
+            name: uri.to_owned(),
+            perm: flags,
+            raddr: 0,
+            size: data.len() as u64,
+            plugin_operations: Box::new(DataInternal {
+                original: data.clone(),
+                data,
+                prot: flags,
+            }),
+        };
+        Ok(desc)
+    }
+
+    fn accept_uri(&self, uri: &str) -> bool {
+        let split: Vec<&str> = uri.split("://").collect();
+        split.len() == 2 && split[0] == "data"
+    }
+
+    fn supported_modes(&self) -> IoMode {
+        IoMode::READ | IoMode::WRITE
+    }
+}
+
+pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
+    Box::new(DataPlugin)
+}
+
+#[cfg(test)]
+mod test_data {
+    use super::*;
+    use crate::io::RIO;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            DataPlugin::decode("data://hex,deadBEEF").unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(
+            DataPlugin::decode("data://base64,cmFpcg==").unwrap(),
+            b"rair"
+        );
+        let errors = [
+            ("data://deadbeef", "Missing data encoding in deadbeef"),
+            ("data://b32,deadbeef", "Unknown data encoding b32"),
+            ("data://hex,dea", "Invalid hex data dea"),
+            ("data://hex,dexd", "Invalid hex data dexd"),
+            ("data://hex,", "Empty data in hex,"),
+            ("data://base64,", "Empty data in base64,"),
+            (
+                "data://base64,cmFpc!==",
+                "Invalid base64 data (Invalid symbol 33, offset 5.)",
+            ),
+        ];
+        for (uri, msg) in errors {
+            assert_eq!(
+                DataPlugin::decode(uri).unwrap_err(),
+                IoError::Custom(msg.to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn test_data() {
+        let mut p = plugin();
+        let mut file = p
+            .open("data://hex,00010203", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        assert_eq!(file.size, 4);
+        let mut buffer = [0; 3];
+        file.plugin_operations.read(1, &mut buffer).unwrap();
+        assert_eq!(buffer, [1, 2, 3]);
+        file.plugin_operations.write(2, &[0xff, 0xff]).unwrap();
+        file.plugin_operations.read(1, &mut buffer).unwrap();
+        assert_eq!(buffer, [1, 0xff, 0xff]);
+        file.plugin_operations
+            .read_original(1, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, [1, 2, 3]);
+        assert_eq!(
+            file.plugin_operations.read(2, &mut buffer).unwrap_err(),
+            IoError::Parse(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "BufferOverflow"
+            ))
+        );
+        assert_eq!(
+            file.plugin_operations.write(4, &[0]).unwrap_err(),
+            IoError::Parse(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "BufferOverflow"
+            ))
+        );
+        file = p.open("data://hex,00010203", IoMode::READ).unwrap();
+        assert_eq!(
+            file.plugin_operations.write(0, &[0]).unwrap_err(),
+            IoError::Parse(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "File Not Writable"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_rio() {
+        let mut io = RIO::new();
+        io.open("data://base64,cmFpcg==", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        let mut buffer = [0; 4];
+        io.pread(0, &mut buffer).unwrap();
+        assert_eq!(&buffer, b"rair");
+        assert_eq!(
+            io.open("data://hex,00", IoMode::COW).unwrap_err(),
+            IoError::Custom("Plugin Data does not support COW".to_owned())
+        );
+    }
+}
@@ -10,17 +10,19 @@ use std::path::Path;
 enum FileInternals {
     Map(Mmap),
     MutMap(MmapMut),
+    // Copy-On-Write map and a read only map of the same file that still has the original bytes.
+    CowMap(MmapMut, Mmap),
 }
 
 impl FileInternals {
     fn len(&self) -> usize {
         match self {
             FileInternals::Map(m) => m.len(),
-            FileInternals::MutMap(m) => m.len(),
+            FileInternals::MutMap(m) | FileInternals::CowMap(m, _) => m.len(),
         }
     }
     fn as_mut(&mut self) -> Option<&mut MmapMut> {
-        if let FileInternals::MutMap(mutmap) = self {
+        if let FileInternals::MutMap(mutmap) | FileInternals::CowMap(mutmap, _) = self {
             Some(mutmap)
         } else {
             None
@@ -39,7 +41,7 @@ impl Deref for FileInternals {
     fn deref(&self) -> &[u8] {
         match self {
             FileInternals::Map(m) => m,
-            FileInternals::MutMap(m) => m,
+            FileInternals::MutMap(m) | FileInternals::CowMap(m, _) => m,
         }
     }
 }
@@ -72,6 +74,28 @@ impl RIOPluginOperations for FileInternals {
             )))
         }
     }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        // Copy-On-Write maps are private, so flushing them never touches the file.
+        if let Some(mutmap) = self.as_mut() {
+            mutmap.flush()?;
+        }
+        Ok(())
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        let FileInternals::CowMap(_, original) = self else {
+            return self.read(raddr, buffer);
+        };
+        if original.len() < raddr + buffer.len() {
+            return Err(IoError::Parse(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "BufferOverflow",
+            )));
+        }
+        buffer.copy_from_slice(&original[raddr..raddr + buffer.len()]);
+        Ok(())
+    }
 }
 
 struct FilePlugin;
@@ -107,7 +131,9 @@ impl RIOPlugin for FilePlugin {
             let f = OpenOptions::new()
                 .read(true)
                 .open(FilePlugin::uri_to_path(uri))?;
-            file = FileInternals::MutMap(unsafe { MmapOptions::new().map_copy(&f)? });
+            file = FileInternals::CowMap(unsafe { MmapOptions::new().map_copy(&f)? }, unsafe {
+                MmapOptions::new().map(&f)?
+            });
         } else if flags.contains(IoMode::WRITE) {
             let f = OpenOptions::new()
                 .read(true)
@@ -336,4 +362,37 @@ mod default_plugin_tests {
     fn test_write_errors() {
         operate_on_file(&test_write_errors_cb, DATA);
     }
+
+    fn test_read_original_cb(path: &Path) {
+        let mut plugin = plugin();
+        let mut desc = plugin.open(&path.to_string_lossy(), IoMode::COW).unwrap();
+        let mut buffer = [0; 4];
+        desc.plugin_operations.write(0x10, &[0xff; 2]).unwrap();
+        desc.plugin_operations.read(0xf, &mut buffer).unwrap();
+        assert_eq!(buffer, [DATA[0xf], 0xff, 0xff, DATA[0x12]]);
+        desc.plugin_operations
+            .read_original(0xf, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, DATA[0xf..0x13]);
+        let e = desc
+            .plugin_operations
+            .read_original(desc.size as usize - 2, &mut buffer);
+        match e {
+            Err(IoError::Parse(io_err)) => assert_eq!(io_err.kind(), io::ErrorKind::UnexpectedEof),
+            _ => panic!("UnexpectedEof Error should have been generated"),
+        }
+        // writes to other files reach the disk right away.
+        desc = plugin
+            .open(&path.to_string_lossy(), IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        desc.plugin_operations.write(0x10, &[0xff; 2]).unwrap();
+        desc.plugin_operations
+            .read_original(0xf, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, [DATA[0xf], 0xff, 0xff, DATA[0x12]]);
+    }
+    #[test]
+    fn test_read_original() {
+        operate_on_file(&test_read_original_cb, DATA);
+    }
 }
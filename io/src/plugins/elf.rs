@@ -0,0 +1,399 @@
+//! RIO plugin that opens ELF files and suggests mapping their loadable segments.
+
+use crate::mapsquery::RIOMap;
+use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
+use crate::plugins::defaultplugin;
+use crate::utils::{IoError, IoMode};
+
+const METADATA: RIOPluginMetadata = RIOPluginMetadata {
+    name: "Elf",
+    desc: "This plugin is used to open ELF files, the file content is \
+           exposed as is and loadable segments are suggested as maps.",
+    author: "Oddcoder",
+    license: "LGPL",
+    version: "0.0.1",
+};
+
+const MAGIC: &[u8] = b"\x7fELF";
+const PT_LOAD: u64 = 1;
+
+// Location of the fields needed from the ELF header and program headers as (offset, size).
+struct Layout {
+    header_size: u64,
+    phoff: (usize, usize),
+    phentsize: (usize, usize),
+    phnum: (usize, usize),
+    p_offset: (usize, usize),
+    p_vaddr: (usize, usize),
+    p_filesz: (usize, usize),
+}
+
+const ELF32: Layout = Layout {
+    header_size: 0x34,
+    phoff: (0x1c, 4),
+    phentsize: (0x2a, 2),
+    phnum: (0x2c, 2),
+    p_offset: (0x4, 4),
+    p_vaddr: (0x8, 4),
+    p_filesz: (0x10, 4),
+};
+
+const ELF64: Layout = Layout {
+    header_size: 0x40,
+    phoff: (0x20, 8),
+    phentsize: (0x36, 2),
+    phnum: (0x38, 2),
+    p_offset: (0x8, 8),
+    p_vaddr: (0x10, 8),
+    p_filesz: (0x20, 8),
+};
+
+struct Parser {
+    big_endian: bool,
+}
+
+impl Parser {
+    fn field(&self, data: &[u8], (offset, size): (usize, usize)) -> u64 {
+        let bytes = &data[offset..offset + size];
+        let fold = |acc: u64, b: &u8| (acc << 8u8) | u64::from(*b);
+        if self.big_endian {
+            bytes.iter().fold(0, fold)
+        } else {
+            bytes.iter().rev().fold(0, fold)
+        }
+    }
+}
+
+struct ElfInternal {
+    file: Box<dyn RIOPluginOperations + Sync + Send>,
+    maps: Vec<RIOMap>,
+}
+
+impl RIOPluginOperations for ElfInternal {
+    fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.file.read(raddr, buffer)
+    }
+
+    fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
+        self.file.write(raddr, buffer)
+    }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        self.file.flush()
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.file.read_original(raddr, buffer)
+    }
+
+    fn suggested_maps(&self) -> Vec<RIOMap> {
+        self.maps.clone()
+    }
+}
+
+struct ElfPlugin {
+    file_plugin: Box<dyn RIOPlugin + Sync + Send>,
+}
+
+impl ElfPlugin {
+    // Read `size` bytes at `offset` of the file or fail with `what` being truncated.
+    fn read_exact(
+        file: &mut RIOPluginDesc,
+        offset: u64,
+        size: u64,
+        what: &str,
+    ) -> Result<Vec<u8>, IoError> {
+        if offset.checked_add(size).is_none_or(|end| end > file.size) {
+            return Err(IoError::Custom(format!("{}: truncated {what}", file.name)));
+        }
+        let mut data = vec![0; size as usize];
+        file.plugin_operations
+            .read((file.raddr + offset) as usize, &mut data)?;
+        Ok(data)
+    }
+
+    fn parse_maps(file: &mut RIOPluginDesc) -> Result<Vec<RIOMap>, IoError> {
+        let ident = Self::read_exact(file, 0, 6, "ELF header")?;
+        if &ident[..4] != MAGIC {
+            return Err(IoError::Custom(format!("{}: bad ELF magic", file.name)));
+        }
+        let layout = match ident[4] {
+            1 => &ELF32,
+            2 => &ELF64,
+            class => {
+                return Err(IoError::Custom(format!(
+                    "{}: unknown ELF class {class}",
+                    file.name
+                )))
+            }
+        };
+        let big_endian = match ident[5] {
+            1 => false,
+            2 => true,
+            data => {
+                return Err(IoError::Custom(format!(
+                    "{}: unknown ELF data encoding {data}",
+                    file.name
+                )))
+            }
+        };
+        let parser = Parser { big_endian };
+        let header = Self::read_exact(file, 0, layout.header_size, "ELF header")?;
+        let phoff = parser.field(&header, layout.phoff);
+        let phentsize = parser.field(&header, layout.phentsize);
+        let phnum = parser.field(&header, layout.phnum);
+        let min_phentsize = (layout.p_filesz.0 + layout.p_filesz.1) as u64;
+        if phnum != 0 && phentsize < min_phentsize {
+            return Err(IoError::Custom(format!(
+                "{}: invalid program header size {phentsize}",
+                file.name
+            )));
+        }
+        let table = Self::read_exact(file, phoff, phentsize * phnum, "program headers")?;
+        let mut maps = Vec::new();
+        for phdr in table.chunks(phentsize.max(1) as usize) {
+            let p_type = parser.field(phdr, (0, 4));
+            let size = parser.field(phdr, layout.p_filesz);
+            if p_type != PT_LOAD || size == 0 {
+                continue;
+            }
+            let paddr = parser.field(phdr, layout.p_offset);
+            if paddr.checked_add(size).is_none_or(|end| end > file.size) {
+                return Err(IoError::Custom(format!("{}: truncated segment", file.name)));
+            }
+            let vaddr = parser.field(phdr, layout.p_vaddr);
+            maps.push(RIOMap {
+                paddr: file.raddr + paddr,
+                vaddr,
+                size,
+            });
+        }
+        Ok(maps)
+    }
+}
+
+impl RIOPlugin for ElfPlugin {
+    fn get_metadata(&self) -> &'static RIOPluginMetadata {
+        &METADATA
+    }
+
+    // plain paths are accepted too so that files can be forced open with this plugin.
+    fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+        let mut file = self
+            .file_plugin
+            .open(uri.trim_start_matches("elf://"), flags)?;
+        let maps = Self::parse_maps(&mut file)?;
+        let desc = RIOPluginDesc {
+            name: uri.to_owned(),
+            perm: file.perm,
+            raddr: file.raddr,
+            size: file.size,
+            plugin_operations: Box::new(ElfInternal {
+                file: file.plugin_operations,
+                maps,
+            }),
+        };
+        Ok(desc)
+    }
+
+    fn accept_uri(&self, uri: &str) -> bool {
+        let split: Vec<&str> = uri.split("://").collect();
+        split.len() == 2 && split[0] == "elf"
+    }
+
+    fn supported_modes(&self) -> IoMode {
+        self.file_plugin.supported_modes()
+    }
+}
+
+pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
+    Box::new(ElfPlugin {
+        file_plugin: defaultplugin::plugin(),
+    })
+}
+
+#[cfg(test)]
+mod test_elf {
+    use super::*;
+    use crate::io::RIO;
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use std::path::Path;
+    use test_file::*;
+
+    fn put(data: &mut [u8], offset: usize, value: u64, size: usize, big_endian: bool) {
+        for i in 0..size {
+            let shift = if big_endian { size - 1 - i } else { i } * 8;
+            data[offset + i] = (value >> shift) as u8;
+        }
+    }
+
+    // ELF with a loadable segment at 0x100 (0x20 bytes), a note and an empty loadable segment.
+    fn build_elf(layout: &Layout, class: u8, big_endian: bool) -> Vec<u8> {
+        let phentsize = if class == 1 { 0x20 } else { 0x38 };
+        let mut elf = vec![0; 0x120];
+        elf[..4].copy_from_slice(MAGIC);
+        elf[4] = class;
+        elf[5] = if big_endian { 2 } else { 1 };
+        let (phoff, size) = layout.phoff;
+        put(&mut elf, phoff, layout.header_size, size, big_endian);
+        put(&mut elf, layout.phentsize.0, phentsize, 2, big_endian);
+        put(&mut elf, layout.phnum.0, 3, 2, big_endian);
+        let segments = [
+            (PT_LOAD, 0x100, 0x40_0000, 0x20),
+            (4, 0x10, 0x1000, 0x10),
+            (PT_LOAD, 0, 0x60_0000, 0),
+        ];
+        for (i, (p_type, offset, vaddr, filesz)) in segments.into_iter().enumerate() {
+            let phdr = layout.header_size as usize + i * phentsize as usize;
+            put(&mut elf, phdr, p_type, 4, big_endian);
+            put(
+                &mut elf,
+                phdr + layout.p_offset.0,
+                offset,
+                layout.p_offset.1,
+                big_endian,
+            );
+            put(
+                &mut elf,
+                phdr + layout.p_vaddr.0,
+                vaddr,
+                layout.p_vaddr.1,
+                big_endian,
+            );
+            put(
+                &mut elf,
+                phdr + layout.p_filesz.0,
+                filesz,
+                layout.p_filesz.1,
+                big_endian,
+            );
+        }
+        elf[0x100..].copy_from_slice(&DATA[..0x20]);
+        elf
+    }
+
+    fn elf_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("elf://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::READ).unwrap();
+        assert_eq!(file.size, 0x120);
+        assert_eq!(
+            file.plugin_operations.suggested_maps(),
+            vec![RIOMap {
+                paddr: 0x100,
+                vaddr: 0x40_0000,
+                size: 0x20
+            }]
+        );
+        let mut buffer = [0; 8];
+        file.plugin_operations.read(0x100, &mut buffer).unwrap();
+        assert_eq!(buffer, DATA[..8]);
+    }
+    #[test]
+    fn test_elf64() {
+        operate_on_file(&elf_cb, &build_elf(&ELF64, 2, false));
+    }
+    #[test]
+    fn test_elf32_big_endian() {
+        operate_on_file(&elf_cb, &build_elf(&ELF32, 1, true));
+    }
+
+    fn cow_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("elf://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::COW).unwrap();
+        file.plugin_operations.write(0x100, &[0xff; 4]).unwrap();
+        let mut buffer = [0; 8];
+        file.plugin_operations.read(0x100, &mut buffer).unwrap();
+        assert_eq!(buffer[..4], [0xff; 4]);
+        file.plugin_operations
+            .read_original(0x100, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, DATA[..8]);
+    }
+    #[test]
+    fn test_cow() {
+        operate_on_file(&cow_cb, &build_elf(&ELF64, 2, false));
+    }
+
+    fn desc_cb(path: &Path) {
+        let mut io = RIO::new();
+        let uri = format!("elf://{}", path.to_string_lossy());
+        io.open_at("malloc://0x100", IoMode::READ | IoMode::WRITE, 0)
+            .unwrap();
+        let hndl = io.open_at(&uri, IoMode::READ, 0x1000).unwrap();
+        let maps = io.hndl_to_desc(hndl).unwrap().suggested_maps();
+        assert_eq!(
+            maps,
+            vec![RIOMap {
+                paddr: 0x1100,
+                vaddr: 0x40_0000,
+                size: 0x20
+            }]
+        );
+        io.map(maps[0].paddr, maps[0].vaddr, maps[0].size).unwrap();
+        let mut buffer = [0; 8];
+        io.vread(0x40_0008, &mut buffer).unwrap();
+        assert_eq!(buffer, DATA[8..16]);
+    }
+    #[test]
+    fn test_desc_suggested_maps() {
+        operate_on_file(&desc_cb, &build_elf(&ELF64, 2, false));
+    }
+
+    fn assert_open_err(data: &[u8], msg: &'static str) {
+        operate_on_file(
+            &|path: &Path| {
+                let uri = format!("elf://{}", path.to_string_lossy());
+                assert_eq!(
+                    plugin().open(&uri, IoMode::READ).err().unwrap(),
+                    IoError::Custom(format!("{}: {msg}", path.to_string_lossy()))
+                );
+            },
+            data,
+        );
+    }
+
+    #[test]
+    fn test_elf_errors() {
+        assert_open_err(DATA, "bad ELF magic");
+        assert_open_err(b"\x7fEL", "truncated ELF header");
+        let elf = build_elf(&ELF64, 2, false);
+        assert_open_err(&elf[..0x30], "truncated ELF header");
+        assert_open_err(&elf[..0x60], "truncated program headers");
+        assert_open_err(&elf[..0x110], "truncated segment");
+        let mut bad_class = elf.clone();
+        bad_class[4] = 3;
+        assert_open_err(&bad_class, "unknown ELF class 3");
+        let mut bad_data = elf;
+        bad_data[5] = 0;
+        assert_open_err(&bad_data, "unknown ELF data encoding 0");
+    }
+
+    struct FlushProbe(Arc<AtomicBool>);
+
+    impl RIOPluginOperations for FlushProbe {
+        fn read(&mut self, _raddr: usize, _buffer: &mut [u8]) -> Result<(), IoError> {
+            Ok(())
+        }
+        fn write(&mut self, _raddr: usize, _buffer: &[u8]) -> Result<(), IoError> {
+            Ok(())
+        }
+        fn flush(&mut self) -> Result<(), IoError> {
+            self.0.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_flush() {
+        let flushed = Arc::new(AtomicBool::new(false));
+        let mut elf = ElfInternal {
+            file: Box::new(FlushProbe(flushed.clone())),
+            maps: Vec::new(),
+        };
+        elf.flush().unwrap();
+        assert!(flushed.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,244 @@
+//! RIO plugin that opens gzip compressed files.
+
+use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
+use crate::utils::{IoError, IoMode};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+
+const METADATA: RIOPluginMetadata = RIOPluginMetadata {
+    name: "Gzip",
+    desc: "This plugin is used to open gzip compressed files, the whole \
+           file is decompressed into memory when opened. In case of writes \
+           the whole file is compressed again and written back to disk.",
+    author: "Oddcoder",
+    license: "LGPL",
+    version: "0.0.1",
+};
+
+struct GzipInternal {
+    path: PathBuf,
+    data: Vec<u8>,             // decompressed file content
+    original: Option<Vec<u8>>, // decompressed content on disk, only kept for Copy-On-Write
+    prot: IoMode,
+}
+
+fn read_from(data: &[u8], raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+    if data.len() < raddr + buffer.len() {
+        return Err(IoError::Parse(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "BufferOverflow",
+        )));
+    }
+    buffer.copy_from_slice(&data[raddr..raddr + buffer.len()]);
+    Ok(())
+}
+
+impl GzipInternal {
+    fn save_gzip(&self) -> Result<(), IoError> {
+        let file = File::create(&self.path)?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(&self.data)?;
+        encoder.finish()?;
+        Ok(())
+    }
+}
+
+impl RIOPluginOperations for GzipInternal {
+    fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        read_from(&self.data, raddr, buffer)
+    }
+
+    fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
+        if !self.prot.contains(IoMode::COW) && !self.prot.contains(IoMode::WRITE) {
+            return Err(IoError::Parse(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "File Not Writable",
+            )));
+        }
+        if self.data.len() < raddr + buffer.len() {
+            return Err(IoError::Parse(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "BufferOverflow",
+            )));
+        }
+        self.data[raddr..raddr + buffer.len()].copy_from_slice(buffer);
+        if self.prot.contains(IoMode::WRITE) {
+            self.save_gzip()?;
+        }
+        Ok(())
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        read_from(self.original.as_ref().unwrap_or(&self.data), raddr, buffer)
+    }
+}
+
+struct GzipPlugin;
+
+impl GzipPlugin {
+    fn uri_to_path(uri: &str) -> &Path {
+        let path = uri
+            .strip_prefix("gzip://")
+            .or_else(|| uri.strip_prefix("gz://"))
+            .unwrap_or(uri);
+        Path::new(path)
+    }
+}
+
+impl RIOPlugin for GzipPlugin {
+    fn get_metadata(&self) -> &'static RIOPluginMetadata {
+        &METADATA
+    }
+
+    fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+        if !self.accept_uri(uri) {
+            return Err(IoError::Custom(format!("Invalid uri {uri}")));
+        }
+        let path = GzipPlugin::uri_to_path(uri);
+        let compressed = fs::read(path)?;
+        let mut data = Vec::new();
+        if let Err(e) = GzDecoder::new(&*compressed).read_to_end(&mut data) {
+            return Err(IoError::Custom(format!(
+                "{} is not valid gzip data ({e})",
+                path.display()
+            )));
+        }
+        let original = flags.contains(IoMode::COW).then(|| data.clone());
+        let internal = GzipInternal {
+            path: path.to_path_buf(),
+            data,
+            original,
+            prot: flags,
+        };
+        let desc = RIOPluginDesc {
+            name: uri.to_owned(),
+            perm: flags,
+            raddr: 0,
+            size: internal.data.len() as u64,
+            plugin_operations: Box::new(internal),
+        };
+        Ok(desc)
+    }
+
+    fn accept_uri(&self, uri: &str) -> bool {
+        let split: Vec<&str> = uri.split("://").collect();
+        split.len() == 2 && (split[0] == "gzip" || split[0] == "gz")
+    }
+}
+
+pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
+    Box::new(GzipPlugin)
+}
+
+#[cfg(test)]
+mod test_gzip {
+    use super::*;
+    use test_file::*;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn decompress(path: &Path) -> Vec<u8> {
+        let mut data = Vec::new();
+        GzDecoder::new(&*fs::read(path).unwrap())
+            .read_to_end(&mut data)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_accept_uri() {
+        let p = plugin();
+        assert!(p.accept_uri("gzip:///tmp/dump.gz"));
+        assert!(p.accept_uri("gz:///tmp/dump.gz"));
+        assert!(!p.accept_uri("/tmp/dump.gz"));
+        assert!(!p.accept_uri("b64:///tmp/dump.gz"));
+    }
+
+    fn read_cb(path: &Path) {
+        let mut p = plugin();
+        for prefix in ["gzip://", "gz://"] {
+            let uri = format!("{prefix}{}", path.to_string_lossy());
+            let mut file = p.open(&uri, IoMode::READ).unwrap();
+            assert_eq!(file.size, DATA.len() as u64);
+            let mut buffer = vec![0; DATA.len()];
+            file.plugin_operations.read(0, &mut buffer).unwrap();
+            assert_eq!(buffer, DATA);
+            file.plugin_operations.read(0x10, &mut buffer[..8]).unwrap();
+            assert_eq!(buffer[..8], DATA[0x10..0x18]);
+            assert_eq!(
+                file.plugin_operations.read(1, &mut buffer).err().unwrap(),
+                IoError::Parse(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "BufferOverflow"
+                ))
+            );
+            assert_eq!(
+                file.plugin_operations.write(0, &[0]).err().unwrap(),
+                IoError::Parse(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "File Not Writable"
+                ))
+            );
+        }
+    }
+    #[test]
+    fn test_read() {
+        operate_on_file(&read_cb, &compress(DATA));
+    }
+
+    fn write_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("gzip://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::COW).unwrap();
+        file.plugin_operations.write(0x10, &[0xff; 8]).unwrap();
+        assert_eq!(decompress(path), DATA);
+        let mut original = [0; 8];
+        file.plugin_operations
+            .read_original(0x10, &mut original)
+            .unwrap();
+        assert_eq!(original, DATA[0x10..0x18]);
+        file = p.open(&uri, IoMode::READ | IoMode::WRITE).unwrap();
+        file.plugin_operations.write(0x10, &[0xff; 8]).unwrap();
+        // written bytes are on disk already.
+        file.plugin_operations
+            .read_original(0x10, &mut original)
+            .unwrap();
+        assert_eq!(original, [0xff; 8]);
+        let mut expected = DATA.to_vec();
+        expected[0x10..0x18].copy_from_slice(&[0xff; 8]);
+        assert_eq!(decompress(path), expected);
+        file = p.open(&uri, IoMode::READ).unwrap();
+        let mut buffer = vec![0; DATA.len()];
+        file.plugin_operations.read(0, &mut buffer).unwrap();
+        assert_eq!(buffer, expected);
+    }
+    #[test]
+    fn test_write() {
+        operate_on_file(&write_cb, &compress(DATA));
+    }
+
+    fn bad_header_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("gz://{}", path.to_string_lossy());
+        let err = p.open(&uri, IoMode::READ).err().unwrap();
+        assert_eq!(
+            err,
+            IoError::Custom(format!(
+                "{} is not valid gzip data (invalid gzip header)",
+                path.display()
+            ))
+        );
+    }
+    #[test]
+    fn test_bad_header() {
+        operate_on_file(&bad_header_cb, DATA);
+    }
+}
@@ -1,9 +1,12 @@
 //! RIO plugin that opens intel hex files.
+//!
+//! Data records are written with up to 16 bytes each, `ihex://<path>?width=<n>` changes that
+//! to `n` bytes where `n` is between 1 and 255.
 
 use super::defaultplugin;
 use super::dummy::Dummy;
 use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
-use crate::utils::{IoError, IoMode};
+use crate::utils::{str_to_size, IoError, IoMode};
 use alloc::collections::BTreeMap;
 use core::num::ParseIntError;
 use core::{fmt::Write as _, str};
@@ -16,7 +19,7 @@ use std::{
     fs::{File, OpenOptions},
     io,
     io::Write as _,
-    path::Path,
+    path::{Path, PathBuf},
 };
 const METADATA: RIOPluginMetadata = RIOPluginMetadata {
     name: "IHex",
@@ -31,9 +34,11 @@ const METADATA: RIOPluginMetadata = RIOPluginMetadata {
 
 struct FileInternals {
     file: Box<dyn RIOPluginOperations + Sync + Send>, // defaultplugin
-    uri: String,
-    bytes: BTreeMap<u64, u8>, // sparce array of bytes
+    path: PathBuf,
+    bytes: BTreeMap<u64, Vec<u8>>, // runs of bytes keyed by address, they never overlap or touch
+    original: Option<BTreeMap<u64, Vec<u8>>>, // runs on disk, only kept for Copy-On-Write
     prot: IoMode,
+    width: u8,        // maximum number of bytes per data record when writing
     ssa: Option<u32>, // used for Record 03
     sla: Option<u32>, // used for Record 05
 }
@@ -71,6 +76,15 @@ fn hex_big_dword(input: &[u8]) -> IResult<&[u8], u32> {
     let result = ((word1 as u32) << 16i32) + word2 as u32;
     Ok((input, result))
 }
+// All bytes of a record including the checksum must add up to 0 modulo 256.
+fn valid_checksum(record: &[u8]) -> bool {
+    let hex = record[1..].trim_ascii_end(); // skip ':' and newline
+    hex.chunks(2)
+        .map(|byte| from_hex(byte).unwrap())
+        .fold(0u8, u8::wrapping_add)
+        == 0
+}
+
 fn parse_record00(input: &[u8]) -> IResult<&[u8], Record> {
     // Data record
     let (input, _) = tag(":")(input)?;
@@ -151,23 +165,50 @@ impl FileInternals {
             parse_record05,
         ))(input)
     }
+    // Store `data` at `addr`, merging it with the runs it overlaps or touches.
+    fn insert(&mut self, addr: u64, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        let end = addr + data.len() as u64;
+        let (mut start, mut run) = (addr, Vec::new());
+        if let Some((&k, v)) = self.bytes.range(..=addr).next_back() {
+            if k + v.len() as u64 >= addr {
+                start = k;
+                run = self.bytes.remove(&k).unwrap();
+            }
+        }
+        let offset = (addr - start) as usize;
+        if run.len() < offset + data.len() {
+            run.resize(offset + data.len(), 0);
+        }
+        run[offset..offset + data.len()].copy_from_slice(data);
+        let next: Vec<u64> = self.bytes.range(addr..=end).map(|(k, _)| *k).collect();
+        for k in next {
+            let v = self.bytes.remove(&k).unwrap();
+            let run_end = start + run.len() as u64;
+            if k + v.len() as u64 > run_end {
+                run.extend_from_slice(&v[(run_end - k) as usize..]);
+            }
+        }
+        self.bytes.insert(start, run);
+    }
     fn parse_ihex(&mut self, mut input: &[u8]) -> Result<(), IoError> {
         let mut base = 0u64;
         let mut line = 1i32;
         loop {
-            let Ok(x) = Self::parse_record(input) else {
+            let Ok((rest, record)) = Self::parse_record(input) else {
                 return Err(IoError::Custom(format!(
                     "Invalid Ihex entry at line: {line}"
                 )));
             };
-            input = x.0;
-            match x.1 {
+            if !valid_checksum(&input[..input.len() - rest.len()]) {
+                return Err(IoError::Custom(format!("Bad checksum at line: {line}")));
+            }
+            input = rest;
+            match record {
                 Record::Eof => break,
-                Record::Data(addr, data) => {
-                    for i in 0..data.len() as u64 {
-                        self.bytes.insert(i + addr + base, data[i as usize]);
-                    }
-                }
+                Record::Data(addr, data) => self.insert(addr + base, &data),
                 Record::Ea(addr) => base = addr,
                 Record::Ssa(addr) => self.ssa = Some(addr),
                 Record::Sla(addr) => self.sla = Some(addr),
@@ -182,7 +223,7 @@ impl FileInternals {
             for byte in &ssa.to_be_bytes() {
                 checksum = (checksum + *byte as u16) & 0xFF;
             }
-            checksum = 256 - checksum;
+            checksum = (256 - checksum) & 0xff;
             writeln!(file, ":04000003{ssa:08x}{checksum:02x}")?;
         }
         if let Some(sla) = self.sla {
@@ -190,7 +231,7 @@ impl FileInternals {
             for byte in &sla.to_be_bytes() {
                 checksum = (checksum + *byte as u16) & 0xFF;
             }
-            checksum = 256 - checksum;
+            checksum = (256 - checksum) & 0xff;
             writeln!(file, ":04000005{sla:08x}{checksum:02x}")?;
         }
         Ok(())
@@ -201,61 +242,50 @@ impl FileInternals {
         for byte in &addr.to_be_bytes() {
             checksum = (checksum + *byte as u16) & 0xFF;
         }
-        checksum = 256 - checksum;
+        checksum = (256 - checksum) & 0xff;
         writeln!(file, ":02000004{addr:04x}{checksum:02x}")?;
         Ok(())
     }
 
     fn write_record02(file: &mut File, addr: u64) -> Result<(), IoError> {
+        // data records carry the low 16 bits, so the segment only holds bits 16..20.
         let addr = (addr >> 4i32) as u16 & 0xf000;
         let mut checksum = 4;
         for byte in &addr.to_be_bytes() {
             checksum = (checksum + *byte as u16) & 0xFF;
         }
-        checksum = 256 - checksum;
+        checksum = (256 - checksum) & 0xff;
         writeln!(file, ":02000002{addr:04x}{checksum:02x}")?;
         Ok(())
     }
 
+    fn write_record00(file: &mut File, addr: u64, data: &[u8]) -> Result<(), IoError> {
+        let offset = (addr & 0xffff) as u16;
+        let mut checksum = data.len() as u16;
+        for byte in offset.to_be_bytes().iter().chain(data) {
+            checksum = (checksum + *byte as u16) & 0xFF;
+        }
+        checksum = (256 - checksum) & 0xff;
+        let mut hex = String::with_capacity(data.len() * 2);
+        for byte in data {
+            write!(hex, "{byte:02x}").unwrap();
+        }
+        writeln!(file, ":{:02x}{offset:04x}00{hex}{checksum:02x}", data.len())?;
+        Ok(())
+    }
+
     fn write_data(&self, file: &mut File) -> Result<(), IoError> {
-        let mut checksum: u16 = 0x10;
-        let mut addr = self.base();
-        let mut data = String::new();
-        let mut i = 0i32;
-        for (k, v) in &self.bytes {
-            if i != 0i32 {
-                if i == 0x10i32 || *k != addr + 1 {
-                    writeln!(file, ":{:02x}{}{:02x}", i, data, (256 - checksum) & 0xff)?;
-                    data.clear();
-                    checksum = 0x10;
-                    i = 0i32;
-                } else {
-                    // we know that *k == addr + 1
-                    addr = *k;
-                    write!(data, "{:02x}", *v).unwrap();
-                    checksum = (checksum + *v as u16) & 0xff;
-                }
-            }
-            if i == 0i32 {
-                if *k > 0xfffff {
-                    // record 04
-                    Self::write_record04(file, *k)?;
-                } else if *k > 0xffff {
-                    // record 02
-                    Self::write_record02(file, *k)?;
+        let width = self.width as usize;
+        for (start, run) in &self.bytes {
+            for (i, data) in run.chunks(width).enumerate() {
+                let addr = start + (i * width) as u64;
+                if addr > 0xfffff {
+                    Self::write_record04(file, addr)?;
+                } else if addr > 0xffff {
+                    Self::write_record02(file, addr)?;
                 }
-                let offset = (*k & 0xffff) as u16;
-                for byte in &offset.to_be_bytes() {
-                    checksum = (checksum + *byte as u16) & 0xff;
-                }
-                addr = *k;
-                write!(data, "{:04x}00{:02x}", offset, *v).unwrap();
-                checksum = (checksum + *v as u16) & 0xff;
+                Self::write_record00(file, addr, data)?;
             }
-            i += 1i32;
-        }
-        if !data.is_empty() {
-            writeln!(file, ":{:02x}{}{:02x}", i, data, 256 - checksum)?;
         }
         Ok(())
     }
@@ -264,7 +294,7 @@ impl FileInternals {
         let mut file = OpenOptions::new()
             .write(true)
             .truncate(true)
-            .open(IHexPlugin::uri_to_path(&self.uri))?;
+            .open(&self.path)?;
         //write ssa and sla
         self.write_sa(&mut file)?;
         //write data
@@ -274,13 +304,10 @@ impl FileInternals {
         Ok(())
     }
     fn size(&self) -> u64 {
-        let Some((min, _)) = self.bytes.iter().next() else {
-            return 0;
-        };
-        let Some((max, _)) = self.bytes.iter().next_back() else {
+        let Some((max, run)) = self.bytes.iter().next_back() else {
             return 0;
         };
-        max - min + 1
+        max + run.len() as u64 - self.base()
     }
     fn base(&self) -> u64 {
         if let Some((k, _)) = self.bytes.iter().next() {
@@ -291,16 +318,31 @@ impl FileInternals {
     }
 }
 
+// Fill `buffer` with the bytes of `runs` starting at `raddr`, holes read as zeros.
+fn read_runs(runs: &BTreeMap<u64, Vec<u8>>, raddr: usize, buffer: &mut [u8]) {
+    buffer.fill(0);
+    let start = raddr as u64;
+    let end = start + buffer.len() as u64;
+    // the run containing start if any, then every run starting before end.
+    let first = runs.range(..=start).next_back().map_or(start, |(k, _)| *k);
+    for (k, run) in runs.range(first..end) {
+        let lo = start.max(*k);
+        let hi = end.min(k + run.len() as u64);
+        if lo < hi {
+            buffer[(lo - start) as usize..(hi - start) as usize]
+                .copy_from_slice(&run[(lo - k) as usize..(hi - k) as usize]);
+        }
+    }
+}
+
 impl RIOPluginOperations for FileInternals {
     fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
-        for (i, item) in buffer.iter_mut().enumerate() {
-            let addr = (i + raddr) as u64;
-            if let Some(v) = self.bytes.get(&addr) {
-                *item = *v;
-            } else {
-                *item = 0;
-            }
-        }
+        read_runs(&self.bytes, raddr, buffer);
+        Ok(())
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        read_runs(self.original.as_ref().unwrap_or(&self.bytes), raddr, buffer);
         Ok(())
     }
 
@@ -312,9 +354,7 @@ impl RIOPluginOperations for FileInternals {
                 "File Not Writable",
             )));
         }
-        for (i, item) in buffer.iter().enumerate() {
-            self.bytes.insert((i + raddr) as u64, *item);
-        }
+        self.insert(raddr as u64, buffer);
 
         if self.prot.contains(IoMode::WRITE) {
             // drop old file descriptor
@@ -323,10 +363,7 @@ impl RIOPluginOperations for FileInternals {
             self.save_ihex()?;
             // mmap new file
             let mut plug = defaultplugin::plugin();
-            let def_desc = plug.open(
-                &IHexPlugin::uri_to_path(&self.uri).to_string_lossy(),
-                IoMode::READ,
-            )?;
+            let def_desc = plug.open(&self.path.to_string_lossy(), IoMode::READ)?;
             self.file = def_desc.plugin_operations;
         }
         Ok(())
@@ -338,9 +375,22 @@ struct IHexPlugin {
 }
 
 impl IHexPlugin {
-    fn uri_to_path(uri: &str) -> &Path {
-        let path = uri.trim_start_matches("ihex://");
-        Path::new(path)
+    // Split uri into the file path and the number of bytes per data record, which is set
+    // with `ihex://<path>?width=<n>` and defaults to 16.
+    fn parse_uri(uri: &str) -> Result<(&Path, u8), IoError> {
+        let uri = uri.trim_start_matches("ihex://");
+        let Some((path, query)) = uri.split_once('?') else {
+            return Ok((Path::new(uri), 0x10));
+        };
+        let Some(width) = query.strip_prefix("width=") else {
+            return Err(IoError::Custom(format!("Unknown ihex option {query}")));
+        };
+        match str_to_size(width) {
+            Some(n @ 1..=255) => Ok((Path::new(path), n as u8)),
+            _ => Err(IoError::Custom(format!(
+                "Invalid ihex record width {width}"
+            ))),
+        }
     }
     fn new() -> IHexPlugin {
         IHexPlugin {
@@ -358,21 +408,26 @@ impl RIOPlugin for IHexPlugin {
         if !self.accept_uri(uri) {
             return Err(IoError::Custom(format!("Invalid uri {uri}")));
         }
-        let def_desc = self.defaultplugin.open(
-            &IHexPlugin::uri_to_path(uri).to_string_lossy(),
-            IoMode::READ,
-        )?;
+        let (path, width) = IHexPlugin::parse_uri(uri)?;
+        let def_desc = self
+            .defaultplugin
+            .open(&path.to_string_lossy(), IoMode::READ)?;
         let mut internal = FileInternals {
             file: def_desc.plugin_operations,
             bytes: BTreeMap::new(),
+            original: None,
             ssa: None,
             sla: None,
             prot: flags,
-            uri: uri.to_owned(),
+            path: path.to_owned(),
+            width,
         };
         let mut data = vec![0; def_desc.size as usize];
         internal.file.read(0x0, &mut data)?;
         internal.parse_ihex(&data)?;
+        if flags.contains(IoMode::COW) {
+            internal.original = Some(internal.bytes.clone());
+        }
         let desc = RIOPluginDesc {
             name: uri.to_owned(),
             perm: flags,
@@ -396,6 +451,7 @@ pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
 #[cfg(test)]
 mod test_ihex {
     use super::*;
+    use std::fs;
     use test_file::*;
 
     #[test]
@@ -447,6 +503,30 @@ mod test_ihex {
         // no sparce file with holes, no nothing but basic record 00 and record 01
         operate_on_copy(&tiny_ihex_write_cb, "../testing_binaries/rio/ihex/tiny.hex");
     }
+    fn tiny_ihex_cow_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("ihex://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::COW).unwrap();
+        file.plugin_operations.write(0x5, &[0x80, 0x90]).unwrap();
+        let mut buffer = [0; 4];
+        file.plugin_operations.read(0x4, &mut buffer).unwrap();
+        assert_eq!(buffer, [0x00, 0x80, 0x90, 0x00]);
+        file.plugin_operations
+            .read_original(0x4, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, [0x00, 0x09, 0x02, 0x00]);
+        drop(file);
+        let mut file = p.open(&uri, IoMode::READ).unwrap();
+        file.plugin_operations.read(0x4, &mut buffer).unwrap();
+        assert_eq!(buffer, [0x00, 0x09, 0x02, 0x00]);
+    }
+    #[test]
+    fn test_tiny_ihex_cow() {
+        operate_on_file(
+            &tiny_ihex_cow_cb,
+            b":0B00000002000002000902000380FE65\n:00000001FF\n",
+        );
+    }
     #[test]
     fn test_tiny_sparce_ihex_read() {
         //sparce file with holes, no nothing but basic record 00 and record 01
@@ -751,6 +831,223 @@ mod test_ihex {
             IoError::Custom("Invalid Ihex entry at line: 4".to_owned())
         );
     }
+    fn bad_checksum_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("ihex://{}", path.to_string_lossy());
+        assert_eq!(
+            p.open(&uri, IoMode::READ).err().unwrap(),
+            IoError::Custom("Bad checksum at line: 2".to_owned())
+        );
+    }
+    #[test]
+    fn test_bad_checksum() {
+        operate_on_file(
+            &bad_checksum_cb,
+            b":0B0010006164647265737320676170A7\n\
+              :0B002000616464726573732067617098\n\
+              :00000001FF\n",
+        );
+    }
+
+    #[test]
+    fn test_parse_uri() {
+        assert_eq!(
+            IHexPlugin::parse_uri("ihex:///tmp/a.hex").unwrap(),
+            (Path::new("/tmp/a.hex"), 0x10)
+        );
+        assert_eq!(
+            IHexPlugin::parse_uri("ihex:///tmp/a.hex?width=0x20").unwrap(),
+            (Path::new("/tmp/a.hex"), 0x20)
+        );
+        assert_eq!(
+            IHexPlugin::parse_uri("ihex:///tmp/a.hex?width=255").unwrap(),
+            (Path::new("/tmp/a.hex"), 255)
+        );
+        for width in ["0", "256", "", "x"] {
+            assert_eq!(
+                IHexPlugin::parse_uri(&format!("ihex:///tmp/a.hex?width={width}")).unwrap_err(),
+                IoError::Custom(format!("Invalid ihex record width {width}"))
+            );
+        }
+        assert_eq!(
+            IHexPlugin::parse_uri("ihex:///tmp/a.hex?size=8").unwrap_err(),
+            IoError::Custom("Unknown ihex option size=8".to_owned())
+        );
+    }
+
+    fn width_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("ihex://{}?width=8", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::READ | IoMode::WRITE).unwrap();
+        let data: Vec<u8> = (0..0x20).collect();
+        file.plugin_operations.write(0x100, &data).unwrap();
+        drop(file);
+        let text = fs::read_to_string(path).unwrap();
+        let widths: Vec<&str> = text
+            .lines()
+            .filter(|line| &line[7..9] == "00")
+            .map(|line| &line[1..3])
+            .collect();
+        assert_eq!(widths, ["08", "03", "08", "08", "08", "08"]);
+        // the file reads the same with any width.
+        let mut file = p
+            .open(&format!("ihex://{}", path.to_string_lossy()), IoMode::READ)
+            .unwrap();
+        let mut buffer = [0; 0x20];
+        file.plugin_operations.read(0x100, &mut buffer).unwrap();
+        assert_eq!(buffer[..], data[..]);
+        file.plugin_operations
+            .read(0x10, &mut buffer[..11])
+            .unwrap();
+        assert_eq!(&buffer[..11], b"address gap");
+    }
+    #[test]
+    fn test_width() {
+        operate_on_file(
+            &width_cb,
+            b":0B0010006164647265737320676170A7\n:00000001FF\n",
+        );
+    }
+
+    #[test]
+    fn test_runs() {
+        let mut internals = FileInternals {
+            file: Box::new(Dummy {}),
+            path: PathBuf::new(),
+            bytes: BTreeMap::new(),
+            original: None,
+            prot: IoMode::READ,
+            width: 0x10,
+            ssa: None,
+            sla: None,
+        };
+        assert_eq!((internals.base(), internals.size()), (0, 0));
+        internals.insert(0x10, &[1, 2, 3]);
+        internals.insert(0x20, &[4, 5]);
+        internals.insert(0x30, &[6]);
+        assert_eq!(internals.bytes.len(), 3);
+        // touching runs are merged.
+        internals.insert(0x13, &[7]);
+        internals.insert(0x1e, &[8, 9]);
+        assert_eq!(internals.bytes[&0x10], [1, 2, 3, 7]);
+        assert_eq!(internals.bytes[&0x1e], [8, 9, 4, 5]);
+        // overwriting inside a run keeps its tail.
+        internals.insert(0x11, &[0xff]);
+        assert_eq!(internals.bytes[&0x10], [1, 0xff, 3, 7]);
+        // one write spanning several runs joins them.
+        internals.insert(0x12, &[0xaa; 0x1f]);
+        assert_eq!(internals.bytes.len(), 1);
+        assert_eq!(internals.bytes[&0x10].len(), 0x21);
+        assert_eq!((internals.base(), internals.size()), (0x10, 0x21));
+        internals.insert(0x40, &[1]);
+        let mut buffer = [0xff; 0x12];
+        internals.read(0x2f, &mut buffer).unwrap();
+        let mut expected = [0; 0x12];
+        expected[..2].copy_from_slice(&[0xaa, 0xaa]);
+        expected[0x11] = 1;
+        assert_eq!(buffer, expected);
+        internals.read(0x100, &mut buffer).unwrap();
+        assert_eq!(buffer, [0; 0x12]);
+        assert_eq!((internals.base(), internals.size()), (0x10, 0x31));
+    }
+
+    fn generated_cb(path: &Path) {
+        // 1 MiB across 16 record 04 segments.
+        let mut p = plugin();
+        let uri = format!("ihex://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::READ).unwrap();
+        assert_eq!((file.raddr, file.size), (0x100000, 0x100000));
+        let mut data = vec![0; 0x100000];
+        file.plugin_operations.read(0x100000, &mut data).unwrap();
+        assert!(data
+            .iter()
+            .enumerate()
+            .all(|(i, b)| *b == (i as u64 + 0x100000).wrapping_mul(7) as u8));
+    }
+    #[test]
+    fn test_generated() {
+        let mut text = String::new();
+        for addr in (0x100000u64..0x200000).step_by(0x10) {
+            if addr.is_multiple_of(0x10000) {
+                let segment = (addr >> 16i32) as u8;
+                let checksum = 0u8.wrapping_sub(6).wrapping_sub(segment);
+                writeln!(text, ":0200000400{segment:02X}{checksum:02X}").unwrap();
+            }
+            let data: Vec<u8> = (addr..addr + 0x10)
+                .map(|a| a.wrapping_mul(7) as u8)
+                .collect();
+            let mut checksum = 0x10u8
+                .wrapping_add((addr >> 8) as u8)
+                .wrapping_add(addr as u8);
+            write!(text, ":10{:04X}00", addr & 0xffff).unwrap();
+            for byte in data {
+                checksum = checksum.wrapping_add(byte);
+                write!(text, "{byte:02X}").unwrap();
+            }
+            writeln!(text, "{:02X}", checksum.wrapping_neg()).unwrap();
+        }
+        text.push_str(":00000001FF\n");
+        operate_on_file(&generated_cb, text.as_bytes());
+    }
+
+    fn short_record_write_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("ihex://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::READ | IoMode::WRITE).unwrap();
+        // records shorter than 16 bytes must still get valid checksums.
+        file.plugin_operations.write(0x12, b"DE").unwrap();
+        drop(file);
+        let mut file = p.open(&uri, IoMode::READ).unwrap();
+        let mut buffer = [0; 11];
+        file.plugin_operations.read(0x10, &mut buffer).unwrap();
+        assert_eq!(&buffer, b"adDEess gap");
+    }
+    #[test]
+    fn test_short_record_write() {
+        operate_on_file(
+            &short_record_write_cb,
+            b":0B0010006164647265737320676170A7\n:00000001FF\n",
+        );
+    }
+
+    fn write_02_round_trip_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("ihex://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::READ | IoMode::WRITE).unwrap();
+        let data: Vec<u8> = (0..0x20).collect();
+        file.plugin_operations.write(0x12345, &data[..4]).unwrap();
+        // crosses from one segment to the next in the middle of a record.
+        file.plugin_operations.write(0x1fff8, &data).unwrap();
+        file.plugin_operations.write(0xfff00, &data[..8]).unwrap();
+        drop(file);
+        let text = fs::read_to_string(path).unwrap();
+        assert!(text.contains(":02000002"));
+        assert!(!text.contains(":02000004"));
+        let mut file = p.open(&uri, IoMode::READ).unwrap();
+        let mut buffer = [0; 0x20];
+        file.plugin_operations
+            .read(0x12345, &mut buffer[..4])
+            .unwrap();
+        assert_eq!(buffer[..4], data[..4]);
+        file.plugin_operations.read(0x1fff8, &mut buffer).unwrap();
+        assert_eq!(buffer[..], data[..]);
+        file.plugin_operations
+            .read(0xfff00, &mut buffer[..8])
+            .unwrap();
+        assert_eq!(buffer[..8], data[..8]);
+        file.plugin_operations
+            .read(0x10, &mut buffer[..11])
+            .unwrap();
+        assert_eq!(&buffer[..11], b"address gap");
+    }
+    #[test]
+    fn test_write_02_round_trip() {
+        operate_on_file(
+            &write_02_round_trip_cb,
+            b":0B0010006164647265737320676170A7\n:00000001FF\n",
+        );
+    }
+
     #[test]
     fn test_empty() {
         let mut p = plugin();
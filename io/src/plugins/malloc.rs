@@ -1,7 +1,14 @@
 //! RIO plugin that opens memory based virtual files.
 
 use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
-use crate::utils::{IoError, IoMode};
+use crate::utils::{str_to_size, IoError, IoMode};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use std::io;
 
 const METADATA: RIOPluginMetadata = RIOPluginMetadata {
@@ -13,6 +20,27 @@ const METADATA: RIOPluginMetadata = RIOPluginMetadata {
     license: "LGPL",
     version: "0.0.1",
 };
+// Without `std` there is no `io::Error` to carry the error kind, so only the message is kept.
+#[cfg(feature = "std")]
+fn permission_error(msg: &str) -> IoError {
+    IoError::Parse(io::Error::new(io::ErrorKind::PermissionDenied, msg))
+}
+#[cfg(not(feature = "std"))]
+fn permission_error(msg: &str) -> IoError {
+    IoError::Custom(msg.to_owned())
+}
+#[cfg(feature = "std")]
+fn overflow_error() -> IoError {
+    IoError::Parse(io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "BufferOverflow",
+    ))
+}
+#[cfg(not(feature = "std"))]
+fn overflow_error() -> IoError {
+    IoError::Custom("BufferOverflow".to_owned())
+}
+
 struct MallocInternal {
     data: Vec<u8>,
 }
@@ -30,10 +58,7 @@ impl MallocInternal {
 impl RIOPluginOperations for MallocInternal {
     fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
         if self.len() < raddr + buffer.len() {
-            return Err(IoError::Parse(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                "BufferOverflow",
-            )));
+            return Err(overflow_error());
         }
         buffer.copy_from_slice(&self.data[raddr..raddr + buffer.len()]);
         Ok(())
@@ -41,35 +66,20 @@ impl RIOPluginOperations for MallocInternal {
 
     fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
         if raddr + buffer.len() > self.len() {
-            return Err(IoError::Parse(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                "BufferOverflow",
-            )));
+            return Err(overflow_error());
         }
         self.data[raddr..raddr + buffer.len()].copy_from_slice(buffer);
         Ok(())
     }
-}
 
-struct MallocPlugin;
-
-impl MallocPlugin {
-    fn uri_to_size(uri: &str) -> Option<u64> {
-        let n = uri.trim_start_matches("malloc://");
-        if n.len() >= 2 {
-            match &*n[0..2].to_lowercase() {
-                "0b" => return u64::from_str_radix(&n[2..], 2).ok(),
-                "0x" => return u64::from_str_radix(&n[2..], 16).ok(),
-                _ => (),
-            }
-        }
-        if n.len() > 1 && n.starts_with('0') {
-            return u64::from_str_radix(&n[1..], 8).ok();
-        }
-        n.parse::<u64>().ok()
+    fn resize(&mut self, _uri: &str, size: u64) -> Result<String, IoError> {
+        self.data.resize(size as usize, 0);
+        Ok(format!("malloc://0x{size:x}"))
     }
 }
 
+struct MallocPlugin;
+
 impl RIOPlugin for MallocPlugin {
     fn get_metadata(&self) -> &'static RIOPluginMetadata {
         &METADATA
@@ -77,25 +87,22 @@ impl RIOPlugin for MallocPlugin {
 
     fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
         if flags.contains(IoMode::COW) {
-            return Err(IoError::Parse(io::Error::new(
-                io::ErrorKind::PermissionDenied,
+            return Err(permission_error(
                 "Can't open file with permission Copy-On-Write",
-            )));
+            ));
         }
 
         if !flags.contains(IoMode::READ) {
-            return Err(IoError::Parse(io::Error::new(
-                io::ErrorKind::PermissionDenied,
+            return Err(permission_error(
                 "Memory based files must have read permission",
-            )));
+            ));
         }
         if !flags.contains(IoMode::WRITE) {
-            return Err(IoError::Parse(io::Error::new(
-                io::ErrorKind::PermissionDenied,
+            return Err(permission_error(
                 "Memory based files must have write permission",
-            )));
+            ));
         }
-        let file = match MallocPlugin::uri_to_size(uri) {
+        let file = match str_to_size(uri.trim_start_matches("malloc://")) {
             Some(size) => MallocInternal::new(size),
             None => {
                 return Err(IoError::Custom(
@@ -117,6 +124,10 @@ impl RIOPlugin for MallocPlugin {
         let split: Vec<&str> = uri.split("://").collect();
         split.len() == 2 && split[0] == "malloc"
     }
+
+    fn supported_modes(&self) -> IoMode {
+        IoMode::READ | IoMode::WRITE
+    }
 }
 
 pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
@@ -148,6 +159,27 @@ mod test_malloc {
             .unwrap();
     }
 
+    #[test]
+    fn test_malloc_size_suffix() {
+        let mut p = plugin();
+        let file = p
+            .open("malloc://0x10K", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        assert_eq!(file.size, 0x4000);
+        let file = p
+            .open("malloc://1kb", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        assert_eq!(file.size, 1000);
+        let file = p
+            .open("malloc://0o100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        assert_eq!(file.size, 0o100);
+        let file = p
+            .open("malloc://1_000", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        assert_eq!(file.size, 1000);
+    }
+
     #[test]
     fn test_malloc_errors() {
         let mut p = plugin();
@@ -188,6 +220,27 @@ mod test_malloc {
         );
     }
 
+    #[test]
+    fn test_resize() {
+        let mut p = plugin();
+        let mut file = p
+            .open("malloc://0x10", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        file.plugin_operations.write(0x8, &[0xff; 8]).unwrap();
+        let uri = file.plugin_operations.resize(&file.name, 0x20).unwrap();
+        assert_eq!(uri, "malloc://0x20");
+        let mut buffer = [1; 0x20];
+        file.plugin_operations.read(0, &mut buffer).unwrap();
+        assert_eq!(buffer[0x8..0x10], [0xff; 8]);
+        assert_eq!(buffer[0x10..], [0; 0x10]);
+        file.plugin_operations.resize(&uri, 0xc).unwrap();
+        file.plugin_operations.read(0, &mut buffer[..0xc]).unwrap();
+        assert_eq!(buffer[0x8..0xc], [0xff; 4]);
+        file.plugin_operations
+            .read(0, &mut buffer[..0xd])
+            .unwrap_err();
+    }
+
     #[test]
     fn test_read_write_error() {
         let mut p = plugin();
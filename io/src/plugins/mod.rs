@@ -1,16 +1,84 @@
 //! List of built-in RIO plugins.
 
 use crate::io::RIO;
+use crate::plugin::{RIOPlugin, RIOPluginDesc};
+use crate::utils::{IoError, IoMode};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 pub mod base64;
+pub mod cache;
+pub mod concat;
+#[cfg(feature = "std")]
+pub mod data;
+#[cfg(feature = "std")]
 pub mod defaultplugin;
+#[cfg(feature = "std")]
 pub mod dummy;
+#[cfg(feature = "std")]
+pub mod elf;
+#[cfg(feature = "std")]
+pub mod gzip;
+#[cfg(feature = "std")]
 pub mod ihex;
 pub mod malloc;
+pub mod pattern;
+#[cfg(feature = "std")]
+pub mod remote;
+pub mod slice;
+#[cfg(feature = "std")]
 pub mod srec;
+pub mod xor;
+
+// Built-in plugins in the order they are loaded.
+#[cfg(feature = "std")]
+fn builtin_plugins() -> Vec<Box<dyn RIOPlugin + Sync + Send>> {
+    vec![
+        defaultplugin::plugin(),
+        ihex::plugin(),
+        malloc::plugin(),
+        base64::plugin(),
+        srec::plugin(),
+        gzip::plugin(),
+        remote::plugin(),
+        pattern::plugin(),
+        elf::plugin(),
+        data::plugin(),
+        xor::plugin(),
+        slice::plugin(),
+        concat::plugin(),
+        cache::plugin(),
+    ]
+}
+
+// file based plugins need `std`, only memory based files are available without it.
+#[cfg(not(feature = "std"))]
+fn builtin_plugins() -> Vec<Box<dyn RIOPlugin + Sync + Send>> {
+    vec![
+        malloc::plugin(),
+        pattern::plugin(),
+        xor::plugin(),
+        slice::plugin(),
+        concat::plugin(),
+        cache::plugin(),
+    ]
+}
+
 pub(crate) fn load_plugins(io: &mut RIO) {
-    io.load_plugin(defaultplugin::plugin());
-    io.load_plugin(ihex::plugin());
-    io.load_plugin(malloc::plugin());
-    io.load_plugin(base64::plugin());
-    io.load_plugin(srec::plugin());
+    for plugin in builtin_plugins() {
+        io.load_plugin(plugin);
+    }
+}
+
+/// Open `uri` using the first built-in plugin that accepts it, this is what plugins that
+/// transform other files use to open the files they wrap.
+pub(crate) fn open_nested(uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+    for mut plugin in builtin_plugins() {
+        if plugin.accept_uri(uri) {
+            return plugin.open(uri, flags);
+        }
+    }
+    Err(IoError::IoPluginNotFoundError(uri.to_owned()))
 }
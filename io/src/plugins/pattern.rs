@@ -0,0 +1,255 @@
+//! RIO plugin that opens read only files filled with deterministic byte patterns.
+//!
+//! URIs look like `pattern://<size>?<spec>` where `spec` is one of:
+//! - `cyclic`: De Bruijn sequence of order 4 over lowercase letters (same as pwntools `cyclic`),
+//!   any 4 byte window appears only once in every 456976 bytes.
+//! - `incr`: 0, 1, 2, ... wrapping after 0xff.
+//! - comma separated hex bytes such as `de,ad,be,ef` that are repeated over and over.
+//!
+//! Bytes are computed on the fly from the address being read, so the file is never allocated.
+
+use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
+use crate::utils::{str_to_bytes, str_to_size, IoError, IoMode};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+
+const METADATA: RIOPluginMetadata = RIOPluginMetadata {
+    name: "Pattern",
+    desc: "This plugin is used to create read only files filled with \
+           deterministic patterns (cyclic, incr or repeated bytes).",
+    author: "Oddcoder",
+    license: "LGPL",
+    version: "0.0.1",
+};
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const ORDER: usize = 4;
+// length of the De Bruijn sequence: every possible window of `ORDER` letters.
+const CYCLIC_PERIOD: usize = ALPHABET.len() * ALPHABET.len() * ALPHABET.len() * ALPHABET.len();
+
+/// Lexicographically smallest De Bruijn sequence generated as the concatenation of the Lyndon
+/// words whose length divides [`ORDER`] (Fredricksen-Kessler-Maiorana algorithm).
+#[derive(Clone)]
+struct DeBruijn {
+    word: [usize; ORDER],
+    len: usize,
+    pos: usize,
+}
+
+impl DeBruijn {
+    fn new() -> Self {
+        DeBruijn {
+            word: [0; ORDER],
+            len: 1,
+            pos: 0,
+        }
+    }
+
+    // Move to the next Lyndon word that is part of the sequence, false when there is none.
+    fn next_word(&mut self) -> bool {
+        loop {
+            let period = self.len;
+            while self.len < ORDER {
+                self.word[self.len] = self.word[self.len - period];
+                self.len += 1;
+            }
+            while self.len > 0 && self.word[self.len - 1] == ALPHABET.len() - 1 {
+                self.len -= 1;
+            }
+            if self.len == 0 {
+                return false;
+            }
+            self.word[self.len - 1] += 1;
+            if ORDER.is_multiple_of(self.len) {
+                self.pos = 0;
+                return true;
+            }
+        }
+    }
+}
+
+impl Iterator for DeBruijn {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> {
+        if self.pos == self.len && !self.next_word() {
+            return None;
+        }
+        self.pos += 1;
+        Some(ALPHABET[self.word[self.pos - 1]])
+    }
+}
+
+enum Pattern {
+    Cyclic,
+    Incr,
+    Repeat(Vec<u8>),
+}
+
+impl Pattern {
+    fn parse(spec: &str) -> Option<Self> {
+        match spec {
+            "cyclic" => Some(Pattern::Cyclic),
+            "incr" => Some(Pattern::Incr),
+            _ => str_to_bytes(spec).map(Pattern::Repeat),
+        }
+    }
+}
+
+impl RIOPluginOperations for Pattern {
+    fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        match self {
+            Pattern::Cyclic => {
+                let sequence = DeBruijn::new().cycle().skip(raddr % CYCLIC_PERIOD);
+                for (b, p) in buffer.iter_mut().zip(sequence) {
+                    *b = p;
+                }
+            }
+            Pattern::Incr => {
+                for (i, b) in buffer.iter_mut().enumerate() {
+                    *b = (raddr + i) as u8;
+                }
+            }
+            Pattern::Repeat(bytes) => {
+                let sequence = bytes.iter().cycle().skip(raddr % bytes.len());
+                for (b, p) in buffer.iter_mut().zip(sequence) {
+                    *b = *p;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, _raddr: usize, _buffer: &[u8]) -> Result<(), IoError> {
+        Err(IoError::Custom("Pattern files are read only".to_owned()))
+    }
+}
+
+struct PatternPlugin;
+
+impl RIOPlugin for PatternPlugin {
+    fn get_metadata(&self) -> &'static RIOPluginMetadata {
+        &METADATA
+    }
+
+    fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+        if flags.intersects(IoMode::WRITE | IoMode::COW) {
+            return Err(IoError::Custom("Pattern files are read only".to_owned()));
+        }
+        let uri = uri.trim_start_matches("pattern://");
+        let Some((size, spec)) = uri.split_once('?') else {
+            return Err(IoError::Custom(format!("Missing pattern in {uri}")));
+        };
+        let size = match str_to_size(size) {
+            Some(parsed) if parsed > 0 => parsed,
+            _ => return Err(IoError::Custom(format!("Invalid pattern size {size}"))),
+        };
+        let Some(pattern) = Pattern::parse(spec) else {
+            return Err(IoError::Custom(format!("Invalid pattern {spec}")));
+        };
+        let desc = RIOPluginDesc {
+            name: format!("pattern://{uri}"),
+            perm: flags,
+            raddr: 0,
+            size,
+            plugin_operations: Box::new(pattern),
+        };
+        Ok(desc)
+    }
+
+    fn accept_uri(&self, uri: &str) -> bool {
+        let split: Vec<&str> = uri.split("://").collect();
+        split.len() == 2 && split[0] == "pattern"
+    }
+
+    fn supported_modes(&self) -> IoMode {
+        IoMode::READ
+    }
+}
+
+pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
+    Box::new(PatternPlugin)
+}
+
+#[cfg(test)]
+mod test_pattern {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_de_bruijn() {
+        let sequence: Vec<u8> = DeBruijn::new().collect();
+        assert_eq!(sequence.len(), CYCLIC_PERIOD);
+        assert_eq!(&sequence[..24], b"aaaabaaacaaadaaaeaaafaaa");
+        let mut windows: Vec<&[u8]> = sequence.windows(ORDER).collect();
+        windows.sort_unstable();
+        windows.dedup();
+        assert_eq!(windows.len(), CYCLIC_PERIOD - ORDER + 1);
+    }
+
+    #[test]
+    fn test_cyclic() {
+        let mut p = plugin();
+        let mut file = p.open("pattern://0x1000?cyclic", IoMode::READ).unwrap();
+        assert_eq!(file.size, 0x1000);
+        let mut buffer = [0; 8];
+        file.plugin_operations.read(0, &mut buffer).unwrap();
+        assert_eq!(&buffer, b"aaaabaaa");
+        file.plugin_operations.read(0x1c, &mut buffer).unwrap();
+        assert_eq!(&buffer, b"haaaiaaa");
+        // the sequence ends with "zzzz" then starts over.
+        file.plugin_operations
+            .read(CYCLIC_PERIOD - 4, &mut buffer)
+            .unwrap();
+        assert_eq!(&buffer, b"zzzzaaaa");
+    }
+
+    #[test]
+    fn test_incr() {
+        let mut p = plugin();
+        let mut file = p.open("pattern://0x1000?incr", IoMode::READ).unwrap();
+        let mut buffer = [0; 4];
+        file.plugin_operations.read(0xfe, &mut buffer).unwrap();
+        assert_eq!(buffer, [0xfe, 0xff, 0, 1]);
+    }
+
+    #[test]
+    fn test_repeat() {
+        let mut p = plugin();
+        let mut file = p
+            .open("pattern://0x1000?de,ad,be,ef", IoMode::READ)
+            .unwrap();
+        let mut buffer = [0; 6];
+        file.plugin_operations.read(0x2, &mut buffer).unwrap();
+        assert_eq!(buffer, [0xbe, 0xef, 0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            file.plugin_operations.write(0, &buffer).unwrap_err(),
+            IoError::Custom("Pattern files are read only".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_pattern_errors() {
+        let mut p = plugin();
+        let errors = vec![
+            ("pattern://0x1000", "Missing pattern in 0x1000"),
+            ("pattern://0xg?incr", "Invalid pattern size 0xg"),
+            ("pattern://0?incr", "Invalid pattern size 0"),
+            ("pattern://0x10?de,adbeef", "Invalid pattern de,adbeef"),
+            ("pattern://0x10?", "Invalid pattern "),
+        ];
+        for (uri, msg) in errors {
+            assert_eq!(
+                p.open(uri, IoMode::READ).err().unwrap(),
+                IoError::Custom(msg.to_owned())
+            );
+        }
+        assert_eq!(
+            p.open("pattern://0x10?incr", IoMode::READ | IoMode::WRITE)
+                .err()
+                .unwrap(),
+            IoError::Custom("Pattern files are read only".to_owned())
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! RIO plugin that accesses the memory of a remote agent over TCP.
+//!
+//! URIs look like `remote://host:port` or `remote://host:port?size` where `size` is the size of
+//! the remote address space. Every read or write sends a request made of one opcode byte (0 for
+//! read and 1 for write), followed by the address and the number of bytes, both as big endian
+//! 64-bit integers, followed by the bytes themselves in case of writes. The agent replies with
+//! one status byte (0 on success) followed by the requested bytes in case of successful reads.
+
+use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
+use crate::utils::{str_to_size, IoError, IoMode};
+use std::io::{Read as _, Write as _};
+use std::net::TcpStream;
+
+const METADATA: RIOPluginMetadata = RIOPluginMetadata {
+    name: "Remote",
+    desc: "This plugin is used to read and write memory of remote agents over TCP.",
+    author: "Oddcoder",
+    license: "LGPL",
+    version: "0.0.1",
+};
+
+// size of the remote address space when not given in the uri, enough for 32-bit targets.
+const DEFAULT_SIZE: u64 = 0x1_0000_0000;
+const OP_READ: u8 = 0;
+const OP_WRITE: u8 = 1;
+
+struct RemoteInternal {
+    stream: TcpStream,
+}
+
+impl RemoteInternal {
+    // Send request header and payload then wait for the status byte.
+    fn request(&mut self, op: u8, raddr: usize, len: usize, payload: &[u8]) -> Result<(), IoError> {
+        let mut header = [0; 17];
+        header[0] = op;
+        header[1..9].copy_from_slice(&(raddr as u64).to_be_bytes());
+        header[9..].copy_from_slice(&(len as u64).to_be_bytes());
+        self.stream.write_all(&header)?;
+        self.stream.write_all(payload)?;
+        let mut status = [0];
+        self.stream.read_exact(&mut status)?;
+        if status[0] != 0 {
+            let action = if op == OP_READ { "read" } else { "write" };
+            return Err(IoError::Custom(format!(
+                "Remote agent failed to {action} 0x{len:x} bytes at 0x{raddr:x}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl RIOPluginOperations for RemoteInternal {
+    fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.request(OP_READ, raddr, buffer.len(), &[])?;
+        self.stream.read_exact(buffer)?;
+        Ok(())
+    }
+
+    fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
+        self.request(OP_WRITE, raddr, buffer.len(), buffer)
+    }
+}
+
+struct RemotePlugin;
+
+impl RemotePlugin {
+    // Split uri into the agent address and the size of the remote address space.
+    fn parse_uri(uri: &str) -> Result<(&str, u64), IoError> {
+        let uri = uri.trim_start_matches("remote://");
+        let Some((addr, size)) = uri.split_once('?') else {
+            return Ok((uri, DEFAULT_SIZE));
+        };
+        match str_to_size(size) {
+            Some(parsed) if parsed > 0 => Ok((addr, parsed)),
+            _ => Err(IoError::Custom(format!("Invalid remote size {size}"))),
+        }
+    }
+}
+
+impl RIOPlugin for RemotePlugin {
+    fn get_metadata(&self) -> &'static RIOPluginMetadata {
+        &METADATA
+    }
+
+    fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+        let (addr, size) = RemotePlugin::parse_uri(uri)?;
+        let stream = TcpStream::connect(addr)?;
+        let desc = RIOPluginDesc {
+            name: uri.to_owned(),
+            perm: flags,
+            raddr: 0,
+            size,
+            plugin_operations: Box::new(RemoteInternal { stream }),
+        };
+        Ok(desc)
+    }
+
+    fn accept_uri(&self, uri: &str) -> bool {
+        let split: Vec<&str> = uri.split("://").collect();
+        split.len() == 2 && split[0] == "remote"
+    }
+
+    fn supported_modes(&self) -> IoMode {
+        IoMode::READ | IoMode::WRITE
+    }
+}
+
+pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
+    Box::new(RemotePlugin)
+}
+
+#[cfg(test)]
+mod test_remote {
+    use super::*;
+    use crate::io::RIO;
+    use std::io;
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    // Agent serving `connections` connections one after the other over 0x100 bytes of memory.
+    fn agent(connections: usize) -> (String, JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let handle = thread::spawn(move || {
+            let mut memory = vec![0; 0x100];
+            for stream in listener.incoming().take(connections) {
+                let mut stream = stream.unwrap();
+                let mut header = [0; 17];
+                while stream.read_exact(&mut header).is_ok() {
+                    let addr = u64::from_be_bytes(header[1..9].try_into().unwrap()) as usize;
+                    let len = u64::from_be_bytes(header[9..].try_into().unwrap()) as usize;
+                    let mut payload = vec![0; if header[0] == OP_WRITE { len } else { 0 }];
+                    stream.read_exact(&mut payload).unwrap();
+                    let Some(bytes) = memory.get_mut(addr..addr + len) else {
+                        stream.write_all(&[1]).unwrap();
+                        continue;
+                    };
+                    if header[0] == OP_WRITE {
+                        bytes.copy_from_slice(&payload);
+                        stream.write_all(&[0]).unwrap();
+                    } else {
+                        stream.write_all(&[0]).unwrap();
+                        stream.write_all(bytes).unwrap();
+                    }
+                }
+            }
+            memory
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    fn test_parse_uri() {
+        assert_eq!(
+            RemotePlugin::parse_uri("remote://127.0.0.1:9000").unwrap(),
+            ("127.0.0.1:9000", DEFAULT_SIZE)
+        );
+        assert_eq!(
+            RemotePlugin::parse_uri("remote://localhost:9000?0x1000").unwrap(),
+            ("localhost:9000", 0x1000)
+        );
+        assert_eq!(
+            RemotePlugin::parse_uri("remote://localhost:9000?4096").unwrap(),
+            ("localhost:9000", 0x1000)
+        );
+        assert_eq!(
+            RemotePlugin::parse_uri("remote://localhost:9000?4G").unwrap(),
+            ("localhost:9000", 0x1_0000_0000)
+        );
+        assert_eq!(
+            RemotePlugin::parse_uri("remote://localhost:9000?010").unwrap(),
+            ("localhost:9000", 8)
+        );
+        for size in ["x", "0"] {
+            assert_eq!(
+                RemotePlugin::parse_uri(&format!("remote://localhost:9000?{size}")).unwrap_err(),
+                IoError::Custom(format!("Invalid remote size {size}"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_remote() {
+        let (addr, agent) = agent(1);
+        let mut p = plugin();
+        let mut file = p
+            .open(
+                &format!("remote://{addr}?0x100"),
+                IoMode::READ | IoMode::WRITE,
+            )
+            .unwrap();
+        assert_eq!(file.size, 0x100);
+        file.plugin_operations.write(0x10, b"remote").unwrap();
+        let mut buffer = [0; 8];
+        file.plugin_operations.read(0xf, &mut buffer).unwrap();
+        assert_eq!(&buffer, b"\0remote\0");
+        assert_eq!(
+            file.plugin_operations.read(0xfc, &mut buffer).unwrap_err(),
+            IoError::Custom("Remote agent failed to read 0x8 bytes at 0xfc".to_owned())
+        );
+        assert_eq!(
+            file.plugin_operations.write(0x100, &buffer).unwrap_err(),
+            IoError::Custom("Remote agent failed to write 0x8 bytes at 0x100".to_owned())
+        );
+        drop(file);
+        assert_eq!(&agent.join().unwrap()[0x10..0x16], b"remote");
+    }
+
+    #[test]
+    fn test_reconnect() {
+        let (addr, agent) = agent(2);
+        let mut io = RIO::new();
+        io.open(
+            &format!("remote://{addr}?0x100"),
+            IoMode::READ | IoMode::WRITE,
+        )
+        .unwrap();
+        io.pwrite(0x20, b"abc").unwrap();
+        let serialized = serde_json::to_string(&io).unwrap();
+        drop(io);
+        let io: RIO = serde_json::from_str(&serialized).unwrap();
+        let mut buffer = [0; 3];
+        io.pread(0x20, &mut buffer).unwrap();
+        assert_eq!(&buffer, b"abc");
+        drop(io);
+        agent.join().unwrap();
+    }
+
+    #[test]
+    fn test_connection_error() {
+        // bind then drop a listener to get a port that refuses connections.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut p = plugin();
+        let err = p
+            .open(&format!("remote://{addr}"), IoMode::READ)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            IoError::Parse(io::Error::from(io::ErrorKind::ConnectionRefused))
+        );
+    }
+}
@@ -10,7 +10,7 @@ use core::{fmt::Write as _, num::ParseIntError, str};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_while_m_n},
-    combinator::map_res,
+    combinator::{map_res, verify},
     sequence::tuple,
     IResult,
 };
@@ -34,6 +34,7 @@ const METADATA: RIOPluginMetadata = RIOPluginMetadata {
 struct SrecInternal {
     file: Box<dyn RIOPluginOperations + Sync + Send>, // defaultplugin
     bytes: BTreeMap<u64, u8>,                         // sparce array of bytes
+    original: Option<BTreeMap<u64, u8>>,              // bytes on disk, only kept for Copy-On-Write
     uri: String,
     prot: IoMode,
     start_address: Option<u64>, // I am not sure if this will always exist or not
@@ -82,18 +83,34 @@ fn hex_big_dword(input: &[u8]) -> IResult<&[u8], u32> {
     Ok((input, result))
 }
 
+// Sum of the address bytes and the data bytes of a record.
+fn bytes_sum(addr: impl Into<u32>, data: &[u8]) -> u8 {
+    addr.into()
+        .to_be_bytes()
+        .iter()
+        .chain(data)
+        .fold(0, |sum, byte| sum.wrapping_add(*byte))
+}
+
+// Parse the checksum byte of a record whose count, address and data bytes add up to `sum`,
+// the checksum is the ones' complement of that sum.
+fn checksum(input: &[u8], sum: u8) -> IResult<&[u8], u8> {
+    verify(hex_byte, |c: &u8| sum.wrapping_add(*c) == 0xff)(input)
+}
+
 fn parse_record0(input: &[u8]) -> IResult<&[u8], Record> {
     let (input, _) = tag("S0")(input)?;
     let (input, total_size) = hex_byte(input)?;
     let size = total_size - 3; // 2 bytes for the address, 1 byte for the checksum
-    let (mut input, _) = hex_big_word(input)?;
+    let (mut input, addr) = hex_big_word(input)?;
     let mut data = Vec::with_capacity(size as usize);
     for _ in 0..size {
         let x = hex_byte(input)?;
         input = x.0;
         data.push(x.1);
     }
-    let (input, _) = hex_byte(input)?; //checksum
+    let sum = total_size.wrapping_add(bytes_sum(addr, &data));
+    let (input, _) = checksum(input, sum)?;
     let (input, _) = parse_newline(input)?; //newline
     Ok((input, Record::Header(data)))
 }
@@ -108,7 +125,8 @@ fn parse_record1(input: &[u8]) -> IResult<&[u8], Record> {
         input = x.0;
         data.push(x.1);
     }
-    let (input, _) = hex_byte(input)?; //checksum
+    let sum = total_size.wrapping_add(bytes_sum(addr, &data));
+    let (input, _) = checksum(input, sum)?;
     let (input, _) = parse_newline(input)?; //newline
     Ok((input, Record::Data(addr as u64, data)))
 }
@@ -123,7 +141,8 @@ fn parse_record2(input: &[u8]) -> IResult<&[u8], Record> {
         input = x.0;
         data.push(x.1);
     }
-    let (input, _) = hex_byte(input)?; //checksum
+    let sum = total_size.wrapping_add(bytes_sum(addr, &data));
+    let (input, _) = checksum(input, sum)?;
     let (input, _) = parse_newline(input)?; //newline
     Ok((input, Record::Data(addr as u64, data)))
 }
@@ -138,40 +157,41 @@ fn parse_record3(input: &[u8]) -> IResult<&[u8], Record> {
         input = x.0;
         data.push(x.1);
     }
-    let (input, _) = hex_byte(input)?; //checksum
+    let sum = total_size.wrapping_add(bytes_sum(addr, &data));
+    let (input, _) = checksum(input, sum)?;
     let (input, _) = parse_newline(input)?; //newline
     Ok((input, Record::Data(addr as u64, data)))
 }
 fn parse_record5(input: &[u8]) -> IResult<&[u8], Record> {
     let (input, _) = tag("S503")(input)?;
     let (input, count) = hex_big_word(input)?;
-    let (input, _) = hex_byte(input)?; //checksum
+    let (input, _) = checksum(input, bytes_sum(count, &[3]))?;
     let (input, _) = parse_newline(input)?; //newline
     Ok((input, Record::Count(count as u64)))
 }
 fn parse_record6(input: &[u8]) -> IResult<&[u8], Record> {
     let (input, _) = tag("S604")(input)?;
     let (input, count) = hex_big_24bits(input)?;
-    let (input, _) = hex_byte(input)?; //checksum
+    let (input, _) = checksum(input, bytes_sum(count, &[4]))?;
     let (input, _) = parse_newline(input)?; //newline
     Ok((input, Record::Count(count as u64)))
 }
 fn parse_record7(input: &[u8]) -> IResult<&[u8], Record> {
     let (input, _) = tag("S705")(input)?;
     let (input, start) = hex_big_dword(input)?;
-    let (input, _) = hex_byte(input)?; //checksum
+    let (input, _) = checksum(input, bytes_sum(start, &[5]))?;
     Ok((input, Record::Eof(start as u64)))
 }
 fn parse_record8(input: &[u8]) -> IResult<&[u8], Record> {
     let (input, _) = tag("S804")(input)?;
     let (input, start) = hex_big_24bits(input)?;
-    let (input, _) = hex_byte(input)?; //checksum
+    let (input, _) = checksum(input, bytes_sum(start, &[4]))?;
     Ok((input, Record::Eof(start as u64)))
 }
 fn parse_record9(input: &[u8]) -> IResult<&[u8], Record> {
     let (input, _) = tag("S903")(input)?;
     let (input, start) = hex_big_word(input)?;
-    let (input, _) = hex_byte(input)?; //checksum
+    let (input, _) = checksum(input, bytes_sum(start, &[3]))?;
     Ok((input, Record::Eof(start as u64)))
 }
 
@@ -237,7 +257,7 @@ impl SrecInternal {
             ));
         }
         write!(file, "S0{:02x}0000", self.header.len() + 3).unwrap();
-        let mut checksum = self.header.len() as u16;
+        let mut checksum = self.header.len() as u16 + 3;
         for byte in &self.header {
             checksum = (checksum + *byte as u16) & 0xff;
             write!(file, "{byte:02x}").unwrap();
@@ -246,7 +266,7 @@ impl SrecInternal {
         Ok(())
     }
     fn write_data(&mut self, file: &mut File) -> Result<(), IoError> {
-        let mut checksum: u16 = 0x10;
+        let mut checksum: u16 = 0;
         let mut data = String::new();
         let mut record = "S1";
         let mut addr = 0;
@@ -324,7 +344,7 @@ impl SrecInternal {
         for byte in &start.to_be_bytes() {
             checksum = (checksum + *byte as u16) & 0xff;
         }
-        writeln!(file, "{checksum:02x}").unwrap();
+        writeln!(file, "{:02x}", !(checksum as u8)).unwrap();
         Ok(())
     }
     fn save_srec(&mut self) -> Result<(), IoError> {
@@ -339,16 +359,26 @@ impl SrecInternal {
     }
 }
 
+// Fill `buffer` with the bytes of `bytes` starting at `raddr`, holes read as zeros.
+fn read_bytes(bytes: &BTreeMap<u64, u8>, raddr: usize, buffer: &mut [u8]) {
+    for (i, item) in buffer.iter_mut().enumerate() {
+        let addr = (i + raddr) as u64;
+        if let Some(v) = bytes.get(&addr) {
+            *item = *v;
+        } else {
+            *item = 0;
+        }
+    }
+}
+
 impl RIOPluginOperations for SrecInternal {
     fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
-        for (i, item) in buffer.iter_mut().enumerate() {
-            let addr = (i + raddr) as u64;
-            if let Some(v) = self.bytes.get(&addr) {
-                *item = *v;
-            } else {
-                *item = 0;
-            }
-        }
+        read_bytes(&self.bytes, raddr, buffer);
+        Ok(())
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        read_bytes(self.original.as_ref().unwrap_or(&self.bytes), raddr, buffer);
         Ok(())
     }
 
@@ -413,6 +443,7 @@ impl RIOPlugin for SrecPlugin {
         let mut internal = SrecInternal {
             file: def_desc.plugin_operations,
             bytes: BTreeMap::new(),
+            original: None,
             prot: flags,
             uri: uri.to_owned(),
             start_address: None,
@@ -421,6 +452,9 @@ impl RIOPlugin for SrecPlugin {
         let mut data = vec![0; def_desc.size as usize];
         internal.file.read(0x0, &mut data)?;
         internal.parse_srec(&data)?;
+        if flags.contains(IoMode::COW) {
+            internal.original = Some(internal.bytes.clone());
+        }
         let desc = RIOPluginDesc {
             name: uri.to_owned(),
             perm: flags,
@@ -479,6 +513,41 @@ mod test_srec {
         }
     }
     #[test]
+    fn test_bad_checksum() {
+        let input = b"S1231000427900001142103C0020123C00004E4F123C00014E4F2841123C00024E4F2641BF\n";
+        assert!(parse_record1(input).is_err());
+        assert!(matches!(
+            parse_record9(b"S9030000FC"),
+            Ok((_, Record::Eof(0)))
+        ));
+        assert!(parse_record9(b"S9030000FD").is_err());
+    }
+    fn write_checksums_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("srec://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::READ | IoMode::WRITE).unwrap();
+        file.plugin_operations.write(0x1008, &[0xff; 8]).unwrap();
+        // the rewritten file must pass checksum validation.
+        file = p.open(&uri, IoMode::READ).unwrap();
+        let mut data = [0; 0x10];
+        file.plugin_operations.read(0x1000, &mut data).unwrap();
+        assert_eq!(
+            data,
+            [
+                0x42, 0x79, 0x00, 0x00, 0x11, 0x42, 0x10, 0x3C, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0xff, 0xff
+            ]
+        );
+    }
+    #[test]
+    fn test_write_checksums() {
+        operate_on_file(
+            &write_checksums_cb,
+            b"S1231000427900001142103C0020123C00004E4F123C00014E4F2841123C00024E4F2641BE\n\
+              S9030000FC\n",
+        );
+    }
+    #[test]
     fn test_record2() {
         let input = b"S2234210007900001142103C0020123C00004E4F123C00014E4F2841123C00024E4F2641BE\n";
         let (input, rec) = parse_record2(input).unwrap();
@@ -605,6 +674,28 @@ mod test_srec {
         );
     }
 
+    fn cow_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("srec://{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::COW).unwrap();
+        file.plugin_operations.write(0x1001, &[0xff, 0xff]).unwrap();
+        let mut buffer = [0; 4];
+        file.plugin_operations.read(0x1000, &mut buffer).unwrap();
+        assert_eq!(buffer, [1, 0xff, 0xff, 4]);
+        file.plugin_operations
+            .read_original(0x1000, &mut buffer)
+            .unwrap();
+        assert_eq!(buffer, [1, 2, 3, 4]);
+        drop(file);
+        let mut file = p.open(&uri, IoMode::READ).unwrap();
+        file.plugin_operations.read(0x1000, &mut buffer).unwrap();
+        assert_eq!(buffer, [1, 2, 3, 4]);
+    }
+    #[test]
+    fn test_cow() {
+        operate_on_file(&cow_cb, b"S107100001020304DE\nS9030000FC\n");
+    }
+
     fn write_s0_s1_s9_cb(path: &Path) {
         let mut p = plugin();
         let mut uri = "srec://".to_owned();
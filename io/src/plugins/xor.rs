@@ -0,0 +1,213 @@
+//! RIO plugin that XORs another file with a repeating key.
+//!
+//! URIs look like `xor://<key>@<uri>` where `key` is comma separated hex bytes such as
+//! `de,ad,be,ef` and `uri` is opened by whatever built-in plugin accepts it, so transforms can
+//! be stacked as in `xor://ff@b64:///path/to/file`. The key is aligned with the start of the
+//! inner file.
+
+use crate::plugin::{RIOPlugin, RIOPluginDesc, RIOPluginMetadata, RIOPluginOperations};
+use crate::plugins::open_nested;
+use crate::utils::{str_to_bytes, IoError, IoMode};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+
+const METADATA: RIOPluginMetadata = RIOPluginMetadata {
+    name: "Xor",
+    desc: "This plugin is used to open XOR encoded files, any file that can be \
+           opened by other plugins can be decoded with a repeating key.",
+    author: "Oddcoder",
+    license: "LGPL",
+    version: "0.0.1",
+};
+
+struct XorInternal {
+    file: Box<dyn RIOPluginOperations + Sync + Send>,
+    key: Vec<u8>,
+    base: usize, // raddr of the first byte of the inner file
+}
+
+impl XorInternal {
+    fn apply_key(&self, raddr: usize, buffer: &mut [u8]) {
+        let key = self
+            .key
+            .iter()
+            .cycle()
+            .skip((raddr - self.base) % self.key.len());
+        for (b, k) in buffer.iter_mut().zip(key) {
+            *b ^= k;
+        }
+    }
+}
+
+impl RIOPluginOperations for XorInternal {
+    fn read(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.file.read(raddr, buffer)?;
+        self.apply_key(raddr, buffer);
+        Ok(())
+    }
+
+    fn write(&mut self, raddr: usize, buffer: &[u8]) -> Result<(), IoError> {
+        let mut encoded = buffer.to_vec();
+        self.apply_key(raddr, &mut encoded);
+        self.file.write(raddr, &encoded)
+    }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        self.file.flush()
+    }
+
+    fn read_original(&mut self, raddr: usize, buffer: &mut [u8]) -> Result<(), IoError> {
+        self.file.read_original(raddr, buffer)?;
+        self.apply_key(raddr, buffer);
+        Ok(())
+    }
+}
+
+struct XorPlugin;
+
+impl XorPlugin {
+    // Split uri into the key and the uri of the inner file.
+    fn parse_uri(uri: &str) -> Result<(Vec<u8>, &str), IoError> {
+        let uri = uri.trim_start_matches("xor://");
+        let Some((key, inner)) = uri.split_once('@') else {
+            return Err(IoError::Custom(format!("Missing xor key in {uri}")));
+        };
+        match str_to_bytes(key) {
+            Some(key) => Ok((key, inner)),
+            None => Err(IoError::Custom(format!("Invalid xor key {key}"))),
+        }
+    }
+}
+
+impl RIOPlugin for XorPlugin {
+    fn get_metadata(&self) -> &'static RIOPluginMetadata {
+        &METADATA
+    }
+
+    fn open(&mut self, uri: &str, flags: IoMode) -> Result<RIOPluginDesc, IoError> {
+        let (key, inner) = XorPlugin::parse_uri(uri)?;
+        let file = open_nested(inner, flags)?;
+        let desc = RIOPluginDesc {
+            name: uri.to_owned(),
+            perm: file.perm,
+            raddr: file.raddr,
+            size: file.size,
+            plugin_operations: Box::new(XorInternal {
+                file: file.plugin_operations,
+                key,
+                base: file.raddr as usize,
+            }),
+        };
+        Ok(desc)
+    }
+
+    fn accept_uri(&self, uri: &str) -> bool {
+        uri.starts_with("xor://")
+    }
+}
+
+pub fn plugin() -> Box<dyn RIOPlugin + Sync + Send> {
+    Box::new(XorPlugin)
+}
+
+#[cfg(test)]
+mod test_xor {
+    use super::*;
+    use crate::io::RIO;
+    use alloc::vec;
+    use std::fs;
+    use std::path::Path;
+    use test_file::*;
+
+    #[test]
+    fn test_parse_uri() {
+        assert_eq!(
+            XorPlugin::parse_uri("xor://de,ad@/tmp/dump").unwrap(),
+            (vec![0xde, 0xad], "/tmp/dump")
+        );
+        assert_eq!(
+            XorPlugin::parse_uri("xor://0xff@b64:///tmp/a@b").unwrap(),
+            (vec![0xff], "b64:///tmp/a@b")
+        );
+        assert_eq!(
+            XorPlugin::parse_uri("xor://ff@xor://01@malloc://0x10").unwrap(),
+            (vec![0xff], "xor://01@malloc://0x10")
+        );
+        assert_eq!(
+            XorPlugin::parse_uri("xor:///tmp/dump").unwrap_err(),
+            IoError::Custom("Missing xor key in /tmp/dump".to_owned())
+        );
+        assert_eq!(
+            XorPlugin::parse_uri("xor://de,adbe@/tmp/dump").unwrap_err(),
+            IoError::Custom("Invalid xor key de,adbe".to_owned())
+        );
+        assert_eq!(
+            XorPlugin::parse_uri("xor://@/tmp/dump").unwrap_err(),
+            IoError::Custom("Invalid xor key ".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_accept_uri() {
+        let p = plugin();
+        assert!(p.accept_uri("xor://ff@/tmp/dump"));
+        assert!(p.accept_uri("xor://ff@b64:///tmp/dump"));
+        assert!(!p.accept_uri("/tmp/dump"));
+        assert!(!p.accept_uri("b64://xor://ff@/tmp/dump"));
+    }
+
+    fn xor_cb(path: &Path) {
+        let mut p = plugin();
+        let uri = format!("xor://de,ad,be,ef@{}", path.to_string_lossy());
+        let mut file = p.open(&uri, IoMode::READ | IoMode::WRITE).unwrap();
+        assert_eq!(file.size, DATA.len() as u64);
+        let mut buffer = [0; 5];
+        file.plugin_operations.read(0x2, &mut buffer).unwrap();
+        assert_eq!(
+            buffer,
+            [
+                DATA[2] ^ 0xbe,
+                DATA[3] ^ 0xef,
+                DATA[4] ^ 0xde,
+                DATA[5] ^ 0xad,
+                DATA[6] ^ 0xbe
+            ]
+        );
+        file.plugin_operations.write(0x5, &[0; 3]).unwrap();
+        file.plugin_operations.read(0x5, &mut buffer[..3]).unwrap();
+        assert_eq!(buffer[..3], [0; 3]);
+        drop(file);
+        assert_eq!(fs::read(path).unwrap()[0x5..0x8], [0xad, 0xbe, 0xef]);
+    }
+    #[test]
+    fn test_xor() {
+        operate_on_file(&xor_cb, DATA);
+    }
+
+    fn nested_cb(path: &Path) {
+        let mut io = RIO::new();
+        let uri = format!("xor://0f@xor://f0@{}", path.to_string_lossy());
+        io.open(&uri, IoMode::READ).unwrap();
+        let mut buffer = [0; 4];
+        io.pread(0x10, &mut buffer).unwrap();
+        let expected: Vec<u8> = DATA[0x10..0x14].iter().map(|b| b ^ 0xff).collect();
+        assert_eq!(buffer[..], expected);
+        // stacked on a memory based plugin.
+        let mut io = RIO::new();
+        io.open("xor://01,02@pattern://0x10?incr", IoMode::READ)
+            .unwrap();
+        io.pread(0x3, &mut buffer).unwrap();
+        assert_eq!(buffer, [0x01, 0x05, 0x07, 0x07]);
+        assert_eq!(
+            io.open("xor://01@nothing://here", IoMode::READ)
+                .unwrap_err(),
+            IoError::IoPluginNotFoundError("nothing://here".to_owned())
+        );
+    }
+    #[test]
+    fn test_nested() {
+        operate_on_file(&nested_cb, DATA);
+    }
+}
@@ -0,0 +1,23 @@
+//! Saved content of writable files, used to roll back [RIO] writes wholesale.
+
+#[cfg(doc)]
+use crate::io::RIO;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Content of one writable file as it was served when the snapshot was taken.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(crate) struct SnapshotFile {
+    pub(crate) hndl: u64,
+    pub(crate) name: String,
+    pub(crate) data: Vec<u8>,
+}
+
+/// Content of all files opened with write or Copy-On-Write permissions in a [RIO], see
+/// [`RIO::snapshot`] and [`RIO::restore`]. Read only files can not change, so they are not
+/// part of the snapshot.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub(crate) files: Vec<SnapshotFile>,
+}
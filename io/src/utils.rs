@@ -1,11 +1,23 @@
 //! Utility data structures for managing RIO.
 
 use alloc::fmt;
+use alloc::string::String;
+use alloc::vec::Vec;
 use bitflags::bitflags;
+use core::error::Error;
+use core::num::ParseIntError;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use std::io;
 
+// threads waiting on a file that a slow plugin is busy with should sleep rather than spin, a
+// spin lock is only used when there is no OS to block on.
+#[cfg(feature = "std")]
+pub(crate) use parking_lot::Mutex;
+#[cfg(not(feature = "std"))]
+pub(crate) use spin::Mutex;
+
 bitflags! {
     /// Set the mode for opening files.
     #[derive(Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
@@ -16,9 +28,22 @@ bitflags! {
     const READ = 4;
     /// Open file in Copy-On-Write mode.
     const COW = 8;
+    /// Mark file content as executable, plugins don't enforce it.
+    const EXEC = 16;
     }
 }
 
+/// Byte order used for decoding integers read from [RIO].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Endian {
+    /// Least significant byte first.
+    Little,
+    /// Most significant byte first.
+    Big,
+    /// Byte order of the host machine.
+    Native,
+}
+
 impl fmt::Display for IoMode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = self.iter_names().map(|(name, _)| name).join(" | ");
@@ -30,27 +55,30 @@ impl fmt::Display for IoMode {
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum IoError {
-    /// Reading or writing to an invalid address.
-    AddressNotFound,
+    /// Reading or writing to an invalid address, `at` is the first address that couldn't be resolved.
+    AddressNotFound { at: u64 },
     /// Memory addresses gets mapped in way that makes them overlap
     AddressesOverlapError,
-    /// There is no sutiable IO plugin for loading the given file encoding
-    IoPluginNotFoundError,
+    /// There is no sutiable IO plugin for loading the given URI.
+    IoPluginNotFoundError(String),
     /// Doing operationg on file handles that doesn't exist
     HndlNotFoundError,
     /// Too many files are opened.
     TooManyFilesError,
+    /// Strict write into a physical range that spans more than one file.
+    CrossDescriptorWrite,
     /// Custom error message.
     Custom(String),
     /// Error that is originating from [`std::io`]
+    #[cfg(feature = "std")]
     Parse(io::Error),
 }
 impl PartialEq for IoError {
     fn eq(&self, other: &IoError) -> bool {
         match self {
-            IoError::AddressNotFound => {
-                if let IoError::AddressNotFound = other {
-                    return true;
+            IoError::AddressNotFound { at } => {
+                if let IoError::AddressNotFound { at: at2 } = other {
+                    return at == at2;
                 }
             }
             IoError::AddressesOverlapError => {
@@ -58,9 +86,9 @@ impl PartialEq for IoError {
                     return true;
                 }
             }
-            IoError::IoPluginNotFoundError => {
-                if let IoError::IoPluginNotFoundError = other {
-                    return true;
+            IoError::IoPluginNotFoundError(uri) => {
+                if let IoError::IoPluginNotFoundError(uri2) = other {
+                    return uri == uri2;
                 }
             }
             IoError::TooManyFilesError => {
@@ -73,11 +101,17 @@ impl PartialEq for IoError {
                     return true;
                 }
             }
+            IoError::CrossDescriptorWrite => {
+                if let IoError::CrossDescriptorWrite = other {
+                    return true;
+                }
+            }
             IoError::Custom(s) => {
                 if let IoError::Custom(s2) = other {
                     return s == s2;
                 }
             }
+            #[cfg(feature = "std")]
             IoError::Parse(_) => {
                 if let IoError::Parse(_) = other {
                     return true;
@@ -90,19 +124,150 @@ impl PartialEq for IoError {
 impl fmt::Display for IoError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            IoError::AddressNotFound => write!(f, "Cannot resolve address."),
+            IoError::AddressNotFound { at } => write!(f, "Cannot resolve address 0x{at:x}."),
             IoError::AddressesOverlapError => write!(f, "Phyiscal addresses overlap."),
-            IoError::IoPluginNotFoundError => write!(f, "Can not find Suitable IO Plugin."),
+            IoError::IoPluginNotFoundError(uri) => {
+                write!(f, "Can not find Suitable IO Plugin for {uri}.")
+            }
             IoError::TooManyFilesError => write!(f, "You have too many open files."),
             IoError::HndlNotFoundError => write!(f, "Handle Does not exist."),
+            IoError::CrossDescriptorWrite => write!(f, "Write spans more than one file."),
             IoError::Custom(s) => write!(f, "{s}."),
+            #[cfg(feature = "std")]
             IoError::Parse(e) => e.fmt(f),
         }
     }
 }
 
+impl Error for IoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            IoError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl From<io::Error> for IoError {
     fn from(err: io::Error) -> IoError {
         IoError::Parse(err)
     }
 }
+
+// Multipliers that can follow a number, longer suffixes must come first.
+const SIZE_SUFFIXES: [(&str, u64); 6] = [
+    ("kb", 1000),
+    ("mb", 1000 * 1000),
+    ("gb", 1000 * 1000 * 1000),
+    ("k", 1 << 10),
+    ("m", 1 << 20),
+    ("g", 1 << 30),
+];
+
+// Split the multiplier that `n` ends with, in any case, from the number before it. Numbers
+// without a multiplier are returned as they are along with a multiplier of 1.
+fn split_size_suffix(n: &str) -> (&str, u64) {
+    SIZE_SUFFIXES
+        .iter()
+        .find_map(|(suffix, multiplier)| {
+            let start = n.len().checked_sub(suffix.len())?;
+            n.get(start..)?
+                .eq_ignore_ascii_case(suffix)
+                .then(|| (&n[..start], *multiplier))
+        })
+        .unwrap_or((n, 1))
+}
+
+/// Parse a number written in binary (`0b`), hexadecimal (`0x`), octal (`0o` or leading `0`) or
+/// decimal, `_` can be used for grouping digits. The number can be followed by `k`, `m` or `g`
+/// (powers of 1024) or `kb`, `mb` or `gb` (powers of 1000) multipliers. Prefixes and multipliers
+/// are case insensitive.
+///
+/// # Errors
+///
+/// Returns the error of parsing the digits, or an overflow error if the multiplied number
+/// doesn't fit in [`u64`].
+pub fn str_to_num(n: &str) -> Result<u64, ParseIntError> {
+    let n = n.replace('_', "");
+    let (n, multiplier) = split_size_suffix(&n);
+    let prefix = n.get(..2).unwrap_or_default();
+    let num = if prefix.eq_ignore_ascii_case("0b") {
+        u64::from_str_radix(&n[2..], 2)
+    } else if prefix.eq_ignore_ascii_case("0o") {
+        u64::from_str_radix(&n[2..], 8)
+    } else if prefix.eq_ignore_ascii_case("0x") {
+        u64::from_str_radix(&n[2..], 16)
+    } else if n.len() > 1 && n.starts_with('0') {
+        u64::from_str_radix(&n[1..], 8)
+    } else {
+        n.parse::<u64>()
+    }?;
+    // `ParseIntError` can't be constructed directly, so overflow errors are borrowed from `u64`.
+    num.checked_mul(multiplier)
+        .ok_or_else(|| u64::from_str_radix("10000000000000000", 16).unwrap_err())
+}
+
+/// Parse a size in plugin URIs as described in [`str_to_num`].
+pub(crate) fn str_to_size(n: &str) -> Option<u64> {
+    str_to_num(n).ok()
+}
+
+/// Parse comma separated hex bytes such as `de,ad,be,ef` (each byte may start with `0x`).
+pub(crate) fn str_to_bytes(n: &str) -> Option<Vec<u8>> {
+    n.split(',')
+        .map(|b| u8::from_str_radix(b.trim().trim_start_matches("0x"), 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test_utils {
+    use super::*;
+    use alloc::boxed::Box;
+
+    #[test]
+    fn test_str_to_size() {
+        assert_eq!(str_to_size("0x10"), Some(0x10));
+        assert_eq!(str_to_size("0B101"), Some(5));
+        assert_eq!(str_to_size("010"), Some(8));
+        assert_eq!(str_to_size("0x10K"), Some(0x4000));
+        assert_eq!(str_to_size("2m"), Some(2 << 20u8));
+        assert_eq!(str_to_size("3KB"), Some(3000));
+        assert_eq!(str_to_size("0b11Mb"), Some(3_000_000));
+        assert_eq!(str_to_size("1G"), Some(1 << 30u8));
+        assert_eq!(str_to_size("0x10000000000000000K"), None);
+        assert_eq!(str_to_size("17179869184G"), None);
+        assert_eq!(str_to_size("K"), None);
+        assert_eq!(str_to_size("1KK"), None);
+        assert_eq!(str_to_size("\u{3bb}"), None);
+        assert_eq!(str_to_size("0o100"), Some(0o100));
+        assert_eq!(str_to_size("0O100"), Some(0o100));
+        assert_eq!(str_to_size("1_000"), Some(1000));
+        assert_eq!(str_to_size("0X1_0k"), Some(0x4000));
+        assert_eq!(str_to_size("_"), None);
+        assert_eq!(str_to_size("a\u{3bb}"), None);
+    }
+
+    #[test]
+    fn test_mode_serde() {
+        let mode = IoMode::READ | IoMode::EXEC;
+        let serialized = serde_json::to_string(&mode).unwrap();
+        assert_eq!(serde_json::from_str::<IoMode>(&serialized).unwrap(), mode);
+        assert_eq!(mode.to_string(), "READ | EXEC");
+    }
+
+    #[test]
+    fn test_error_source() {
+        let err: Box<dyn Error> = Box::new(IoError::AddressNotFound { at: 0x10 });
+        assert_eq!(err.to_string(), "Cannot resolve address 0x10.");
+        assert!(err.source().is_none());
+        let err = IoError::from(io::Error::new(io::ErrorKind::NotFound, "missing"));
+        let source = err.source().unwrap();
+        assert_eq!(source.to_string(), "missing");
+        assert_eq!(
+            source.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+}
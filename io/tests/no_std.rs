@@ -0,0 +1,56 @@
+//! Address space usage that only relies on the memory based plugin, this is what is left of
+//! `rair_io` when built without the `std` feature. Run it in that configuration with
+//! `cargo test -p rair-io --no-default-features --test no_std`.
+
+use rair_io::{IoError, IoMode, RIO};
+
+#[test]
+fn test_malloc_only() {
+    let mut io = RIO::new();
+    let perm = IoMode::READ | IoMode::WRITE;
+    let hndl = io.open("malloc://0x100", perm).unwrap();
+    io.open_at("malloc://0x100", perm, 0x1000).unwrap();
+    assert_eq!(io.hndl_to_desc(hndl).unwrap().size(), 0x100);
+    io.pwrite(0xfe, b"ab").unwrap();
+    io.map(0xf0, 0x5000, 0x10).unwrap();
+    io.map(0x1000, 0x5010, 0x10).unwrap();
+    io.vwrite(0x5010, b"cd").unwrap();
+    let mut buf = [0; 4];
+    io.vread(0x500e, &mut buf).unwrap();
+    assert_eq!(&buf, b"abcd");
+    io.pread(0x1000, &mut buf[..2]).unwrap();
+    assert_eq!(&buf[..2], b"cd");
+    assert_eq!(
+        io.pread(0x100, &mut buf).unwrap_err(),
+        IoError::AddressNotFound { at: 0x100 }
+    );
+}
+
+#[cfg(not(feature = "std"))]
+#[test]
+fn test_no_file_plugins() {
+    let mut io = RIO::new();
+    assert_eq!(
+        io.open("foo.txt", IoMode::READ).unwrap_err(),
+        IoError::IoPluginNotFoundError("foo.txt".to_owned())
+    );
+}
+
+#[test]
+fn test_pattern() {
+    let mut io = RIO::new();
+    io.open("pattern://0x100?cyclic", IoMode::READ).unwrap();
+    let mut buf = [0; 8];
+    io.pread(0x4, &mut buf).unwrap();
+    assert_eq!(&buf, b"baaacaaa");
+}
+
+#[test]
+fn test_xor() {
+    let mut io = RIO::new();
+    io.open("xor://ff@malloc://0x10", IoMode::READ | IoMode::WRITE)
+        .unwrap();
+    let mut buf = [0; 2];
+    io.pread(0x4, &mut buf).unwrap();
+    assert_eq!(buf, [0xff, 0xff]);
+}
@@ -13,6 +13,10 @@ pub fn hist_file() -> PathBuf {
     history
 }
 
+pub fn rc_file() -> PathBuf {
+    project_dir().config_dir().join("rairrc")
+}
+
 #[cfg(test)]
 
 mod test_files {
@@ -22,4 +26,9 @@ mod test_files {
         let hist = hist_file();
         assert!(hist.ends_with("history"));
     }
+    #[test]
+    fn test_rc() {
+        let rc = rc_file();
+        assert!(rc.ends_with("rairrc"));
+    }
 }
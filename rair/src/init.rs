@@ -3,6 +3,7 @@
 use crate::lineformatter::LineFormatter;
 use rair_core::Core;
 use rustyline::{history::FileHistory, CompletionType, Config, EditMode, Editor};
+use std::path::Path;
 
 pub fn init_editor_from_core(core: &mut Core) -> Editor<LineFormatter, FileHistory> {
     let config = Config::builder()
@@ -13,3 +14,93 @@ pub fn init_editor_from_core(core: &mut Core) -> Editor<LineFormatter, FileHisto
     editor.set_helper(Some(LineFormatter::new(core.commands())));
     editor
 }
+
+/// Run the commands in the startup script `rc` if it exists, it must run before
+/// [`rair_core::autoload`] so that the variables it reads can be set there.
+pub fn source_rc(core: &mut Core, rc: &Path) {
+    if rc.exists() {
+        core.run("source", &[rc.to_string_lossy().into_owned()]);
+    }
+}
+
+#[cfg(test)]
+mod test_init {
+    use super::*;
+    use rair_core::{autoload, autosave, Writer};
+    use rair_eval::register_eval;
+    use rair_io::IoMode;
+    use std::fs;
+    use test_file::operate_on_dir;
+
+    fn new_core() -> Core {
+        let mut core = Core::new_no_colors();
+        register_eval(&mut core);
+        core.stderr = Writer::new_buf();
+        core.stdout = Writer::new_buf();
+        core
+    }
+
+    fn test_rc_autoload_cb(dir: &Path) {
+        let project = dir.join("project").to_string_lossy().into_owned();
+        let rc = dir.join("rairrc");
+        fs::write(
+            &rc,
+            format!("e prj.autosave = {project}\ne prj.autoload = true\n"),
+        )
+        .unwrap();
+        let mut core = new_core();
+        source_rc(&mut core, &rc);
+        core.io
+            .open("malloc://0x100", IoMode::READ | IoMode::WRITE)
+            .unwrap();
+        core.set_loc(0x20);
+        autosave(&mut core);
+        // next session is configured by the same script.
+        let mut core = new_core();
+        source_rc(&mut core, &rc);
+        autoload(&mut core);
+        assert_eq!(core.get_loc(), 0x20);
+        assert_eq!(core.io.uri_iter().count(), 1);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_rc_autoload() {
+        operate_on_dir(&test_rc_autoload_cb, &[]);
+    }
+
+    fn test_rc_history_cb(dir: &Path) {
+        let history = dir.join("history").to_string_lossy().into_owned();
+        let rc = dir.join("rairrc");
+        fs::write(&rc, format!("e core.historyFile = {history}\n")).unwrap();
+        let mut core = new_core();
+        source_rc(&mut core, &rc);
+        core.run("s", &["0x10".to_owned()]);
+        core.run("s", &["0x20".to_owned()]);
+        autosave(&mut core);
+        let mut core = new_core();
+        source_rc(&mut core, &rc);
+        autoload(&mut core);
+        core.run("history", &[]);
+        assert_eq!(
+            core.stdout.utf8_string().unwrap(),
+            " 0\tPhy\t0x00000000\n 1\tPhy\t0x00000010\n*2\tPhy\t0x00000000\n"
+        );
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+
+    #[test]
+    fn test_rc_history() {
+        operate_on_dir(&test_rc_history_cb, &[]);
+    }
+
+    #[test]
+    fn test_missing_rc() {
+        let mut core = new_core();
+        source_rc(&mut core, Path::new("/nonexistent/rairrc"));
+        autoload(&mut core);
+        assert_eq!(core.stdout.utf8_string().unwrap(), "");
+        assert_eq!(core.stderr.utf8_string().unwrap(), "");
+    }
+}
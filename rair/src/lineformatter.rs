@@ -89,11 +89,16 @@ impl Completer for LineFormatter {
             }
             p += 1;
         }
-        // next we parse the line
-        let t = ParseTree::construct(&line[0..p]);
+        // next we parse the last statement of the line
+        let mut start = line[..p].rfind(';').map_or(0, |i| i + 1);
+        start += line[start..p].len() - line[start..p].trim_start().len();
+        let t = ParseTree::construct(&line[start..p]);
         match t {
             Err(_) => Ok((0, Vec::new())),
-            Ok(tree) => Ok(self.tree_complete(tree)),
+            Ok(tree) => {
+                let (pos, candidates) = self.tree_complete(tree);
+                Ok((start + pos, candidates))
+            }
         }
     }
 }
@@ -9,16 +9,22 @@ mod rpel;
 
 use cli::Args;
 use core::mem;
-use init::init_editor_from_core;
-use rair_core::{panic_msg, Core, Writer};
+use files::rc_file;
+use init::{init_editor_from_core, source_rc};
+use rair_core::{autoload, panic_msg, Core, Writer};
+use rair_eval::register_eval;
 use rpel::prompt_read_parse_evaluate_loop;
 
 fn main() {
     let mut core = Core::new();
+    register_eval(&mut core);
     let editor = init_editor_from_core(&mut core);
     let args = Args::parse().unwrap_or_else(|e| panic_msg(&mut core, &e, ""));
+    source_rc(&mut core, &rc_file());
     match args {
         Args::Proj(proj) => {
+            // the project given on the command line replaces any autoloaded one.
+            autoload(&mut core);
             let stderr = mem::replace(&mut core.stderr, Writer::new_buf());
             core.run("load", &[proj]);
             let err_buf = mem::replace(&mut core.stderr, stderr)
@@ -33,6 +39,7 @@ fn main() {
                 .open_at(&uri, perms, base)
                 .unwrap_or_else(|e| panic_msg(&mut core, &e.to_string(), ""));
             core.set_loc(base);
+            autoload(&mut core);
         }
     }
     prompt_read_parse_evaluate_loop(core, editor);
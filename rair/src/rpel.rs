@@ -1,7 +1,7 @@
 //! Read-Parse-Evaluate-Loop implementation.
 
 use crate::{files::hist_file, lineformatter::LineFormatter};
-use rair_core::Core;
+use rair_core::{autosave, Autosaver, Core};
 use rair_eval::rair_eval;
 use rustyline::error::ReadlineError;
 use rustyline::history::FileHistory;
@@ -14,6 +14,7 @@ pub fn prompt_read_parse_evaluate_loop(
     mut core: Core,
     mut editor: Editor<LineFormatter, FileHistory>,
 ) -> ! {
+    let mut autosaver = Autosaver::default();
     loop {
         let prelude = &format!("[0x{:08x}]({})> ", core.get_loc(), core.mode);
         let (r, g, b) = core.env.read().get_color("color.2").unwrap();
@@ -23,9 +24,15 @@ pub fn prompt_read_parse_evaluate_loop(
                 editor.add_history_entry(line).unwrap();
                 editor.save_history(&hist_file()).unwrap();
                 rair_eval(&mut core, line);
+                autosaver.update(&mut core);
             }
             Err(ReadlineError::Interrupted) => writeln!(core.stdout, "CTRL-C").unwrap(),
-            Err(ReadlineError::Eof) => exit(0),
+            Err(ReadlineError::Eof) => {
+                // the final save must not race with a periodic one still being written.
+                drop(autosaver);
+                autosave(&mut core);
+                exit(0)
+            }
             Err(err) => writeln!(core.stdout, "Error: {err:?}").unwrap(),
         }
     }
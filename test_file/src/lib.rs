@@ -5,7 +5,7 @@ extern crate tempfile;
 use std::fs;
 use std::io::Write;
 use std::path::Path;
-use tempfile::NamedTempFile;
+use tempfile::{tempdir, NamedTempFile};
 
 pub const DATA: &[u8] = &[
     0x00, 0x01, 0x01, 0x02, 0x03, 0x05, 0x08, 0x0d, 0x15, 0x22, 0x37, 0x59, 0x90, 0xe9, 0x79, 0x62,
@@ -43,3 +43,11 @@ pub fn operate_on_files(test_function: &dyn Fn(&[&Path]), files_data: &[&[u8]])
     }
     test_function(&paths);
 }
+
+pub fn operate_on_dir(test_function: &dyn Fn(&Path), files: &[(&str, &[u8])]) {
+    let dir = tempdir().unwrap();
+    for (name, data) in files {
+        fs::write(dir.path().join(name), data).unwrap();
+    }
+    test_function(dir.path());
+}
@@ -11,6 +11,7 @@ where
     K: Distance,
 {
     root: Option<BKTreeNode<K, V>>,
+    len: usize,
 }
 struct BKTreeNode<K, V>
 where
@@ -60,6 +61,57 @@ where
 
         (exact, close)
     }
+
+    // Depth first search that keeps the `k` closest items found so far in `result` sorted by
+    // distance. Children are visited closest first so the bound tightens as early as possible.
+    fn find_nearest<'a>(&'a self, key: &K, k: usize, result: &mut Vec<(&'a K, &'a V, u64)>) {
+        let distance = self.key.distance(key);
+        if result.len() < k || distance < result[k - 1].2 {
+            let pos = result.partition_point(|item| item.2 <= distance);
+            result.insert(pos, (&self.key, &self.value, distance));
+            result.truncate(k);
+        }
+        let mut children: Vec<_> = self.children.iter().collect();
+        children.sort_by_key(|(d, _)| d.abs_diff(distance));
+        for (d, child) in children {
+            // By triangle inequality nothing in this subtree is closer than `|d - distance|`.
+            if result.len() == k && d.abs_diff(distance) >= result[k - 1].2 {
+                break;
+            }
+            child.find_nearest(key, k, result);
+        }
+    }
+
+    // Remove the first child node (at any depth) whose key matches `key` exactly, its
+    // descendants are inserted again under `self`.
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let distance = self.key.distance(key);
+        let child = self.children.get_mut(&distance)?;
+        if child.key.distance(key) != 0 {
+            return child.remove(key);
+        }
+        let removed = self.children.remove(&distance).unwrap();
+        let (value, orphans) = removed.into_value_and_descendants();
+        for (key, value) in orphans {
+            self.insert(key, value);
+        }
+        Some(value)
+    }
+
+    fn into_value_and_descendants(self) -> (V, Vec<(K, V)>) {
+        let mut descendants = Vec::new();
+        for child in self.children.into_values() {
+            child.into_entries(&mut descendants);
+        }
+        (self.value, descendants)
+    }
+
+    fn into_entries(self, entries: &mut Vec<(K, V)>) {
+        entries.push((self.key, self.value));
+        for child in self.children.into_values() {
+            child.into_entries(entries);
+        }
+    }
 }
 /// This trait used by [`BKTree`] to tell how close are 2 objects when fuzzy searching.
 /// In case of strings, the distance function could be something like Levenshtein distance,
@@ -76,7 +128,27 @@ where
     /// Returns a new BK-Tree
     #[must_use]
     pub fn new() -> BKTree<K, V> {
-        BKTree { root: None }
+        BKTree { root: None, len: 0 }
+    }
+
+    /// Returns the number of items stored in the tree.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns *true* if the tree has no items and *false* otherwise.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over all (*key*, *value*) pairs of the tree in no particular order.
+    #[must_use]
+    pub fn iter(&self) -> BKTreeIterator<'_, K, V> {
+        BKTreeIterator {
+            stack: self.root.iter().collect(),
+        }
     }
 
     /// Inserts a new (*key*, *value*) pair into the KB-Tree
@@ -86,6 +158,7 @@ where
         } else {
             self.root = Some(BKTreeNode::new(key, value));
         }
+        self.len += 1;
     }
 
     /// Search for the closest Item to *key* with a *tolerance* factor.
@@ -101,6 +174,70 @@ where
             (Vec::new(), Vec::new())
         }
     }
+
+    /// Search for the *k* items closest to *key* regardless of how far they are. The return value
+    /// is a vector of (*key*, *value*, *distance*) sorted by distance, items at equal distance
+    /// come in no particular order.
+    pub fn find_nearest(&self, key: &K, k: usize) -> Vec<(&K, &V, u64)> {
+        let mut result = Vec::with_capacity(k);
+        if let Some(root) = &self.root {
+            if k != 0 {
+                root.find_nearest(key, k, &mut result);
+            }
+        }
+        result
+    }
+
+    /// Removes the item whose key is exactly *key* and returns its value. Items that were
+    /// stored below the removed one are inserted again so that later searches still find them.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let root = self.root.as_mut()?;
+        let value = if root.key.distance(key) == 0 {
+            let (value, orphans) = self.root.take().unwrap().into_value_and_descendants();
+            // The first orphan gets promoted to be the new root, inserting orphans counts them again.
+            self.len -= orphans.len();
+            for (key, value) in orphans {
+                self.insert(key, value);
+            }
+            value
+        } else {
+            root.remove(key)?
+        };
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a BKTree<K, V>
+where
+    K: Distance,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = BKTreeIterator<'a, K, V>;
+    fn into_iter(self) -> BKTreeIterator<'a, K, V> {
+        self.iter()
+    }
+}
+
+/// Iterator over references to the items of [`BKTree`].
+pub struct BKTreeIterator<'a, K, V>
+where
+    K: Distance,
+{
+    stack: Vec<&'a BKTreeNode<K, V>>,
+}
+
+impl<'a, K, V> Iterator for BKTreeIterator<'a, K, V>
+where
+    K: Distance,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children.values());
+        Some((&node.key, &node.value))
+    }
 }
 
 fn osa_distance(str1: &str, str2: &str) -> u64 {
@@ -159,6 +296,39 @@ impl Distance for String {
 /// ```
 pub type SpellTree<V> = BKTree<String, V>;
 
+/// Binary key compared using the number of differing bits (Hamming distance). Keys of different
+/// lengths are as far from each other as possible (`u64::MAX`).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct HammingKey(pub Vec<u8>);
+
+impl Distance for HammingKey {
+    fn distance(&self, other: &Self) -> u64 {
+        if self.0.len() != other.0.len() {
+            return u64::MAX;
+        }
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| u64::from((a ^ b).count_ones()))
+            .sum()
+    }
+}
+
+/// A `BKTree` with binary keys searched by Hamming distance, useful for fixed length keys such
+/// as hashes or instruction encodings.
+///
+/// # Example
+/// ```
+/// use rair_trees::bktree::{HammingKey, HammingTree};
+/// let mut tree: HammingTree<&str> = HammingTree::new();
+/// tree.insert(HammingKey(vec![0xde, 0xad, 0xbe, 0xef]), "deadbeef");
+/// tree.insert(HammingKey(vec![0xca, 0xfe, 0xba, 0xbe]), "cafebabe");
+/// let (exact, approx) = tree.find(&HammingKey(vec![0xde, 0xad, 0xbe, 0xee]), 1);
+/// assert!(exact.is_empty());
+/// assert_eq!(approx, [&HammingKey(vec![0xde, 0xad, 0xbe, 0xef])]);
+/// ```
+pub type HammingTree<V> = BKTree<HammingKey, V>;
+
 #[cfg(test)]
 mod bktree_tests {
     use super::*;
@@ -200,4 +370,150 @@ mod bktree_tests {
         assert_eq!(res.0.len(), 0);
         assert_eq!(res.1.len(), 0);
     }
+
+    #[test]
+    fn test_find_nearest() {
+        let tree = spell_tree(&[
+            "hello",
+            "hell",
+            "held",
+            "helicopter",
+            "helium",
+            "helix",
+            "helmet",
+        ]);
+        let mut nearest = tree.find_nearest(&"helo".to_owned(), 3);
+        assert!(nearest.iter().all(|item| item.2 == 1));
+        nearest.sort();
+        let keys: Vec<&str> = nearest.iter().map(|item| *item.1).collect();
+        assert_eq!(keys, ["held", "hell", "hello"]);
+        let nearest = tree.find_nearest(&"helo".to_owned(), 5);
+        let distances: Vec<u64> = nearest.iter().map(|item| item.2).collect();
+        assert_eq!(distances, [1, 1, 1, 2, 3]);
+        assert_eq!(nearest[3].1, &"helix");
+        assert_eq!(tree.find_nearest(&"helo".to_owned(), 10).len(), 7);
+        assert_eq!(tree.find_nearest(&"helo".to_owned(), 0).len(), 0);
+        assert_eq!(
+            SpellTree::<()>::new()
+                .find_nearest(&"helo".to_owned(), 3)
+                .len(),
+            0
+        );
+    }
+
+    fn spell_tree(words: &[&'static str]) -> SpellTree<&'static str> {
+        let mut tree = SpellTree::new();
+        for word in words {
+            tree.insert((*word).to_owned(), *word);
+        }
+        tree
+    }
+
+    fn assert_all_found(tree: &SpellTree<&'static str>, words: &[&'static str]) {
+        for word in words {
+            assert_eq!(tree.find(&(*word).to_owned(), 0).0, [word]);
+        }
+    }
+
+    #[test]
+    fn test_len_iter() {
+        let words = [
+            "hello",
+            "hell",
+            "held",
+            "helicopter",
+            "helium",
+            "helix",
+            "helmet",
+        ];
+        let mut tree = SpellTree::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.iter().next(), None);
+        for word in &words {
+            tree.insert((*word).to_owned(), *word);
+        }
+        assert_eq!(tree.len(), 7);
+        assert!(!tree.is_empty());
+        let mut items: Vec<(&String, &&str)> = tree.iter().collect();
+        items.sort();
+        let mut expected: Vec<(String, &str)> =
+            words.iter().map(|w| ((*w).to_owned(), *w)).collect();
+        expected.sort();
+        assert!(items.iter().map(|(k, v)| ((*k).clone(), **v)).eq(expected));
+        tree.remove(&"hell".to_owned());
+        tree.remove(&"hello".to_owned());
+        tree.remove(&"hello".to_owned());
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.iter().count(), 5);
+    }
+
+    #[test]
+    fn test_remove_leaf() {
+        let mut tree = spell_tree(&["hello", "hell", "help"]);
+        assert_eq!(tree.remove(&"help".to_owned()), Some("help"));
+        assert_eq!(tree.find(&"help".to_owned(), 0).0.len(), 0);
+        assert_eq!(tree.find(&"help".to_owned(), 1).1, [&"hell"]);
+        assert_all_found(&tree, &["hello", "hell"]);
+        assert_eq!(tree.remove(&"help".to_owned()), None);
+    }
+
+    #[test]
+    fn test_remove_internal() {
+        // "hallo", "jello" and "hells" are all stored below "hell", the child of "hello".
+        let words = ["hello", "hell", "hallo", "jello", "hells", "help"];
+        let mut tree = spell_tree(&words);
+        assert_eq!(tree.remove(&"hell".to_owned()), Some("hell"));
+        assert_eq!(tree.find(&"hell".to_owned(), 0).0.len(), 0);
+        assert_all_found(&tree, &["hello", "hallo", "jello", "hells", "help"]);
+        let mut close = tree.find(&"hell".to_owned(), 1).1;
+        close.sort();
+        assert_eq!(close, [&"hello", &"hells", &"help"]);
+    }
+
+    #[test]
+    fn test_remove_root() {
+        let mut tree = spell_tree(&["hello", "hell", "help", "helmet"]);
+        assert_eq!(tree.remove(&"hello".to_owned()), Some("hello"));
+        assert_all_found(&tree, &["hell", "help", "helmet"]);
+        for word in ["hell", "help", "helmet"] {
+            assert_eq!(tree.remove(&word.to_owned()), Some(word));
+        }
+        assert_eq!(tree.find(&"hell".to_owned(), 10), (vec![], vec![]));
+        assert_eq!(tree.remove(&"hell".to_owned()), None);
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let key = HammingKey(vec![0xff, 0x00, 0x0f, 0xf0]);
+        assert_eq!(key.distance(&key.clone()), 0);
+        assert_eq!(key.distance(&HammingKey(vec![0x00, 0xff, 0x0f, 0xf0])), 16);
+        assert_eq!(key.distance(&HammingKey(vec![0xfe, 0x00, 0x0f, 0xf1])), 2);
+        assert_eq!(key.distance(&HammingKey(vec![0xff, 0x00, 0x0f])), u64::MAX);
+    }
+
+    #[test]
+    fn test_hamming_tree() {
+        let mut tree: HammingTree<u32> = HammingTree::new();
+        for value in [
+            0xdead_beefu32,
+            0xcafe_babe,
+            0x0000_0000,
+            0xffff_ffff,
+            0xdead_beee,
+        ] {
+            tree.insert(HammingKey(value.to_be_bytes().to_vec()), value);
+        }
+        // keys of a different length never match but don't break the search either.
+        tree.insert(HammingKey(vec![0xde, 0xad]), 0xdead);
+        let key = HammingKey(vec![0xde, 0xad, 0xbe, 0xe7]);
+        let nearest = tree.find_nearest(&key, 2);
+        assert_eq!(nearest.len(), 2);
+        assert_eq!((nearest[0].1, nearest[0].2), (&0xdead_beef, 1));
+        assert_eq!((nearest[1].1, nearest[1].2), (&0xdead_beee, 2));
+        let (exact, approx) = tree.find(&HammingKey(0xcafe_babeu32.to_be_bytes().to_vec()), 1);
+        assert_eq!(exact, [&0xcafe_babe]);
+        assert!(approx.is_empty());
+        assert_eq!(tree.find(&HammingKey(vec![0xff]), 8), (vec![], vec![]));
+        assert_eq!(tree.find_nearest(&key, 10).last().unwrap().2, u64::MAX);
+    }
 }
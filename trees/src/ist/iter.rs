@@ -4,7 +4,7 @@ use super::interval::Interval;
 use super::rb_helpers::AugData;
 use super::tree::IST;
 use crate::rbtree::TreeIterator;
-use alloc::vec::IntoIter;
+use alloc::vec::{IntoIter, Vec};
 
 /// Iterator for [IST]
 pub struct ISTIterator<K: Ord + Copy, V> {
@@ -4,6 +4,7 @@ use super::interval::Interval;
 use super::rb_helpers::AugData;
 use super::tree::IST;
 use crate::rbtree::TreeRefIterator;
+use alloc::vec::Vec;
 use core::slice::Iter;
 /// Iterator for [IST] reference
 pub struct ISTRefIterator<'a, K: Ord + Copy, V> {
@@ -2,6 +2,7 @@
 
 use super::interval::Interval;
 use crate::rbtree::RBTree;
+use alloc::vec::Vec;
 
 #[derive(Copy, Clone)]
 pub(super) struct AugData<K: Ord + Copy> {
@@ -10,8 +10,9 @@ impl<K: Ord + Copy + Serialize, V: Serialize> Serialize for IST<K, V> {
     where
         S: Serializer,
     {
-        let mut seq = serializer.serialize_seq(None)?;
-        // Size hint
+        // Size hint followed by 3 elements per node, non self describing formats such as
+        // bincode need the length upfront.
+        let mut seq = serializer.serialize_seq(Some(1 + 3 * self.size() as usize))?;
         seq.serialize_element(&self.size())?;
         for (lo, hi, data) in self {
             seq.serialize_element(&lo)?;
@@ -8,6 +8,8 @@ use super::iter::ISTIterator;
 use super::iter_ref::ISTRefIterator;
 use super::rb_helpers::{AugData, ISTHelpers};
 use crate::rbtree::{Augment, RBTree};
+use alloc::vec;
+use alloc::vec::Vec;
 
 /// Interval Query data type based on augmented binary search tree,
 /// written as *IST* but pronounced 'Interval Search Tree'.
@@ -1,12 +1,20 @@
 //!various trees impelementation for rair project
+//!
+//! With the default `std` feature turned off the crate is `no_std` and only needs `alloc`,
+//! [`bktree`] is not available in that case.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
 #[cfg(feature = "serialize")]
 extern crate serde;
 /// Approximate String search data structure.
+#[cfg(feature = "std")]
 pub mod bktree;
 /// Interval search tree implementation.
 pub mod ist;
+/// Prefix search data structure.
+pub mod prefix;
 
 /// Left-Leaning Red Black tree implementation built with augmentation in mind.
 pub mod rbtree;
@@ -0,0 +1,4 @@
+//! Prefix search data structure.
+
+mod tree;
+pub use self::tree::*;
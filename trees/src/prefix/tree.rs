@@ -0,0 +1,152 @@
+//! Prefix search data structure.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Trie keyed by strings used to find all keys starting with some prefix without looking at
+/// keys that don't. Keys are split into bytes so results come sorted lexicographically.
+pub struct PrefixTree<V> {
+    root: PrefixTreeNode<V>,
+    len: usize,
+}
+
+struct PrefixTreeNode<V> {
+    value: Option<V>,
+    children: BTreeMap<u8, PrefixTreeNode<V>>,
+}
+
+impl<V> Default for PrefixTreeNode<V> {
+    fn default() -> Self {
+        PrefixTreeNode {
+            value: None,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl<V> PrefixTreeNode<V> {
+    // Depth first walk pushing every stored key under `key` in lexicographic order.
+    fn collect<'a>(&'a self, key: &mut Vec<u8>, result: &mut Vec<(String, &'a V)>) {
+        if let Some(value) = &self.value {
+            // keys are only ever built from complete `&str`s.
+            result.push((String::from_utf8(key.clone()).unwrap(), value));
+        }
+        for (byte, child) in &self.children {
+            key.push(*byte);
+            child.collect(key, result);
+            key.pop();
+        }
+    }
+}
+
+impl<V> Default for PrefixTree<V> {
+    fn default() -> Self {
+        PrefixTree::new()
+    }
+}
+
+impl<V> PrefixTree<V> {
+    /// Returns a new empty prefix tree.
+    #[must_use]
+    pub fn new() -> PrefixTree<V> {
+        PrefixTree {
+            root: PrefixTreeNode::default(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of keys stored in the tree.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns *true* if the tree has no keys and *false* otherwise.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert `value` at `key`, the value previously stored at `key` is returned if any.
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        let mut node = &mut self.root;
+        for byte in key.bytes() {
+            node = node.children.entry(byte).or_default();
+        }
+        let old = node.value.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    fn node(&self, key: &str) -> Option<&PrefixTreeNode<V>> {
+        key.bytes()
+            .try_fold(&self.root, |node, byte| node.children.get(&byte))
+    }
+
+    /// Returns the value stored at `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.node(key)?.value.as_ref()
+    }
+
+    /// Returns all (*key*, *value*) pairs whose key starts with `prefix` sorted by key, an empty
+    /// prefix returns everything.
+    #[must_use]
+    pub fn find_prefix(&self, prefix: &str) -> Vec<(String, &V)> {
+        let mut result = Vec::new();
+        if let Some(node) = self.node(prefix) {
+            node.collect(&mut prefix.as_bytes().to_vec(), &mut result);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod prefix_tree_tests {
+    use super::*;
+    use alloc::vec;
+
+    fn keys<V>(found: Vec<(String, &V)>) -> Vec<String> {
+        found.into_iter().map(|(key, _)| key).collect()
+    }
+
+    #[test]
+    fn test_insert_get() {
+        let mut tree = PrefixTree::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.insert("map", 1u8), None);
+        assert_eq!(tree.insert("m", 2u8), None);
+        assert_eq!(tree.insert("map", 3u8), Some(1));
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.get("map"), Some(&3u8));
+        assert_eq!(tree.get("m"), Some(&2u8));
+        assert_eq!(tree.get("ma"), None);
+        assert_eq!(tree.get("maps"), None);
+        assert_eq!(tree.get(""), None);
+    }
+
+    #[test]
+    fn test_find_prefix() {
+        let mut tree = PrefixTree::new();
+        for (i, key) in [
+            "maps", "open", "m", "mode", "openMany", "map", "q", "\u{3bb}",
+        ]
+        .iter()
+        .enumerate()
+        {
+            tree.insert(key, i);
+        }
+        assert_eq!(keys(tree.find_prefix("m")), ["m", "map", "maps", "mode"]);
+        assert_eq!(keys(tree.find_prefix("op")), ["open", "openMany"]);
+        assert_eq!(keys(tree.find_prefix("openMany")), ["openMany"]);
+        assert_eq!(tree.find_prefix("mapsx"), vec![]);
+        assert_eq!(tree.find_prefix("map")[1], (String::from("maps"), &0));
+        assert_eq!(
+            keys(tree.find_prefix("")),
+            ["m", "map", "maps", "mode", "open", "openMany", "q", "\u{3bb}"]
+        );
+    }
+}
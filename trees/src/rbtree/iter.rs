@@ -3,6 +3,8 @@
 // Credits where credits goes!
 // https://codereview.stackexchange.com/questions/110161/binary-trees-in-rust-iterators
 use super::rbtree_wrapper::{Augment, RBTree};
+use alloc::vec;
+use alloc::vec::Vec;
 
 /// Iterator for [`RBtree`]
 pub struct TreeIterator<K: Ord + Copy, A: Copy, V> {
@@ -1,6 +1,8 @@
 //! Copy of iter.rs except for it deals with references :(.
 
 use super::rbtree_wrapper::{Augment, RBTree};
+use alloc::vec;
+use alloc::vec::Vec;
 
 // In case of iter we would tear down the tree structure and consumed nodes will no
 // longer exist but here we need to mark if a node is ever traversed or not and that
@@ -4,6 +4,7 @@ use super::color::Color;
 use super::iter::TreeIterator;
 use super::iter_ref::TreeRefIterator;
 use super::node::Node;
+use alloc::boxed::Box;
 use core::cmp::Ordering;
 use core::mem;
 
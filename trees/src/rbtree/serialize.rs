@@ -14,8 +14,9 @@ where
     where
         S: Serializer,
     {
-        let mut seq = serializer.serialize_seq(None)?;
-        // Size hint
+        // Size hint followed by 3 elements per node, non self describing formats such as
+        // bincode need the length upfront.
+        let mut seq = serializer.serialize_seq(Some(1 + 3 * self.size() as usize))?;
         seq.serialize_element(&self.size())?;
         for (key, aug_data, data) in self {
             seq.serialize_element(&key)?;